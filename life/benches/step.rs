@@ -0,0 +1,44 @@
+//! Time one generation of a large random soup. Save a baseline without the
+//! `rayon` feature, then compare stepping on several threads against it:
+//!
+//! ```sh
+//! cargo bench --bench step -- --save-baseline sequential
+//! cargo bench --bench step --features rayon -- --baseline sequential
+//! ```
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use life::{next_generation, LivingList, Rules, WorldBounds};
+use vec2::Vector2;
+
+/// The side length of the soup, which at a quarter full has about 100k cells
+const SIZE: i32 = 632;
+
+/// A soup about a quarter full, the same every run.
+fn soup() -> LivingList {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut cells = LivingList::default();
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            // xorshift64
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            if state % 4 == 0 {
+                cells.insert(Vector2::new(x, y));
+            }
+        }
+    }
+    cells
+}
+
+fn step(c: &mut Criterion) {
+    let cells = soup();
+    c.bench_function("step 100k-cell soup", |b| {
+        b.iter(|| next_generation(black_box(&cells), &Rules::CONWAY, &WorldBounds::Infinite))
+    });
+}
+
+criterion_group!(benches, step);
+criterion_main!(benches);
@@ -0,0 +1,66 @@
+//! Time the simulation on its own, without opening a window or touching the
+//! GPU. Run it with and without `--features rayon` to compare the
+//! stepping strategies:
+//!
+//! ```sh
+//! cargo run --release --bin bench -- pattern.rle 1000
+//! ```
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::{path::PathBuf, time::Instant};
+
+#[cfg(not(target_arch = "wasm32"))]
+use anyhow::Context;
+#[cfg(not(target_arch = "wasm32"))]
+use clap::Parser;
+#[cfg(not(target_arch = "wasm32"))]
+use life::{next_generation, parse_pattern_file, Rules, WorldBounds};
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Parser)]
+#[command(about = "Time how fast a pattern is simulated, without rendering it")]
+struct Args {
+    /// The pattern to start from, in any format the game can import
+    pattern: PathBuf,
+    /// How many generations to run
+    generations: u32,
+    /// The rules to run under, in B/S notation
+    #[arg(long, default_value_t = Rules::default(), value_parser = Rules::from_bs_string)]
+    rules: Rules,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    let bytes = std::fs::read(&args.pattern)
+        .with_context(|| format!("Couldn't read {}", args.pattern.display()))?;
+    let name = args.pattern.file_name().unwrap_or_default().to_string_lossy();
+    let mut cells = parse_pattern_file(&name, &bytes)
+        .with_context(|| format!("Couldn't parse {}", args.pattern.display()))?;
+    println!(
+        "Running {} for {} generations under {} from {} cells",
+        args.pattern.display(),
+        args.generations,
+        args.rules,
+        cells.len()
+    );
+
+    let start = Instant::now();
+    for _ in 0..args.generations {
+        cells = next_generation(&cells, &args.rules, &WorldBounds::Infinite);
+    }
+    let elapsed = start.elapsed();
+
+    println!("Total time: {elapsed:.3?}");
+    println!(
+        "Average: {:.1} generations/s",
+        args.generations as f64 / elapsed.as_secs_f64()
+    );
+    println!("Final population: {}", cells.len());
+    Ok(())
+}
+
+/// There's no terminal to run from on the web.
+#[cfg(target_arch = "wasm32")]
+fn main() {}
@@ -0,0 +1,36 @@
+//! Saving files for the user to keep, such as recordings and exported data.
+
+/// Save a file for the user. On native, it is written to the working
+/// directory.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_bytes(filename: &str, bytes: &[u8]) -> anyhow::Result<()> {
+    std::fs::write(filename, bytes)?;
+    log::info!("Saved {filename}");
+    Ok(())
+}
+
+/// Save a file for the user. On the web, it is offered as a download.
+#[cfg(target_arch = "wasm32")]
+pub fn save_bytes(filename: &str, bytes: &[u8]) -> anyhow::Result<()> {
+    use wasm_bindgen::JsCast;
+
+    let err = |e: wasm_bindgen::JsValue| anyhow::anyhow!("{e:?}");
+
+    let array = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let blob = web_sys::Blob::new_with_u8_array_sequence(&array).map_err(err)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(err)?;
+
+    let anchor = web_sys::window()
+        .and_then(|win| win.document())
+        .ok_or_else(|| anyhow::anyhow!("no document to download from"))?
+        .create_element("a")
+        .map_err(err)?
+        .dyn_into::<web_sys::HtmlAnchorElement>()
+        .map_err(|_| anyhow::anyhow!("created element was not an anchor"))?;
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    anchor.click();
+
+    web_sys::Url::revoke_object_url(&url).map_err(err)?;
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+use rustc_hash::FxHashSet;
+use vec2::Vector2;
+
+use super::LivingList;
+
+/// The seed for benchmark boards. It's fixed so that runs with the same
+/// settings are comparable.
+pub const BOARD_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// The parameters of a stress test.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchmarkSettings {
+    /// The width and height of the random board, in cells
+    pub size: u32,
+    /// The fraction of cells on the random board that start alive
+    pub density: f32,
+    /// The number of generations to time
+    pub steps: u32,
+}
+
+impl Default for BenchmarkSettings {
+    fn default() -> Self {
+        Self {
+            size: 256,
+            density: 0.3,
+            steps: 100,
+        }
+    }
+}
+
+/// The results of a finished stress test.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchmarkSummary {
+    pub steps: u32,
+    pub min: Duration,
+    pub avg: Duration,
+    pub max: Duration,
+    /// The number of living cells after the last step
+    pub final_population: usize,
+}
+
+/// A stress test in progress. Each step is timed from when it is requested to
+/// when its result replaces the board, so the worker thread is included.
+pub(super) struct Benchmark {
+    remaining: u32,
+    step_started: Option<Instant>,
+    timings: Vec<Duration>,
+}
+
+impl Benchmark {
+    pub fn new(steps: u32) -> Self {
+        Self {
+            remaining: steps,
+            step_started: None,
+            timings: Vec::with_capacity(steps as usize),
+        }
+    }
+
+    /// Whether another step should be requested now.
+    pub fn wants_step(&self) -> bool {
+        self.remaining > 0 && self.step_started.is_none()
+    }
+
+    pub fn start_step(&mut self, now: Instant) {
+        self.step_started = Some(now);
+    }
+
+    /// Record the end of a step, if one was started by the benchmark.
+    pub fn finish_step(&mut self, now: Instant) {
+        if let Some(started) = self.step_started.take() {
+            self.timings.push(now.saturating_duration_since(started));
+            self.remaining -= 1;
+        }
+    }
+
+    /// Summarize the timings, or `None` if there are steps left to run.
+    pub fn summary(&self, final_population: usize) -> Option<BenchmarkSummary> {
+        if self.remaining > 0 || self.timings.is_empty() {
+            return None;
+        }
+        let total: Duration = self.timings.iter().sum();
+        Some(BenchmarkSummary {
+            steps: self.timings.len() as u32,
+            min: *self.timings.iter().min()?,
+            avg: total / self.timings.len() as u32,
+            max: *self.timings.iter().max()?,
+            final_population,
+        })
+    }
+}
+
+/// Generate a square board of random cells with its top-left corner at (0, 0).
+pub fn random_board(size: u32, density: f32) -> LivingList {
+    random_board_seeded(size, density, BOARD_SEED)
+}
+
+/// Generate a random board like [`random_board`], from a different seed. The
+/// same seed always gives the same board.
+pub fn random_board_seeded(size: u32, density: f32, seed: u64) -> LivingList {
+    // A xorshift generator is plenty for scattering cells. It never leaves a
+    // state of zero, so that seed is nudged.
+    let mut state = seed.max(1);
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    let threshold = (density.clamp(0.0, 1.0) as f64 * u64::MAX as f64) as u64;
+    let mut cells = FxHashSet::default();
+    for y in 0..size as i32 {
+        for x in 0..size as i32 {
+            if next() < threshold {
+                cells.insert(Vector2::new(x, y));
+            }
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{bounds::WorldBounds, compute_step, rules::Rules};
+
+    #[test]
+    fn runs_requested_steps() {
+        let settings = BenchmarkSettings {
+            size: 32,
+            density: 0.3,
+            steps: 5,
+        };
+        let mut cells = random_board(settings.size, settings.density);
+        let mut benchmark = Benchmark::new(settings.steps);
+        let start = Instant::now();
+        let mut steps = 0;
+        while benchmark.wants_step() {
+            assert!(benchmark.summary(cells.len()).is_none());
+            benchmark.start_step(start);
+            cells = compute_step(&cells, &Rules::CONWAY, &WorldBounds::Infinite);
+            benchmark.finish_step(start + Duration::from_micros(10 * steps + 10));
+            steps += 1;
+        }
+        assert_eq!(steps, 5);
+
+        let summary = benchmark.summary(cells.len()).unwrap();
+        assert_eq!(summary.steps, 5);
+        assert_eq!(summary.final_population, cells.len());
+        assert_eq!(summary.min, Duration::from_micros(10));
+        assert_eq!(summary.max, Duration::from_micros(50));
+        assert_eq!(summary.avg, Duration::from_micros(30));
+
+        // Steps the benchmark didn't start aren't timed
+        benchmark.finish_step(start);
+        assert_eq!(benchmark.summary(0).unwrap().steps, 5);
+    }
+
+    #[test]
+    fn random_boards_are_repeatable() {
+        let board = random_board(64, 0.3);
+        assert_eq!(board, random_board(64, 0.3));
+        assert_ne!(board, random_board_seeded(64, 0.3, 1));
+        assert!(board.iter().all(|c| (0..64).contains(&c.x) && (0..64).contains(&c.y)));
+        // Close to the requested density
+        let density = board.len() as f32 / (64 * 64) as f32;
+        assert!((density - 0.3).abs() < 0.05, "density {density}");
+        assert!(random_board(64, 0.0).is_empty());
+    }
+}
@@ -0,0 +1,91 @@
+//! The shape of the world the simulation runs on.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use vec2::Vector2;
+
+/// Where cells can live.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum WorldBounds {
+    /// An unbounded plane
+    #[default]
+    Infinite,
+    /// A `width` by `height` board from (0, 0) whose edges wrap around to the
+    /// opposite side
+    Torus { width: i32, height: i32 },
+}
+
+impl WorldBounds {
+    /// Whether `cell` is part of the world.
+    pub fn contains(&self, cell: Vector2<i32>) -> bool {
+        match *self {
+            Self::Infinite => true,
+            Self::Torus { width, height } => {
+                (0..width).contains(&cell.x) && (0..height).contains(&cell.y)
+            }
+        }
+    }
+
+    /// Shrink an inclusive range of cells to the part inside the world, or
+    /// return `None` if none of it is.
+    pub fn clip(
+        &self,
+        min: Vector2<i32>,
+        max: Vector2<i32>,
+    ) -> Option<(Vector2<i32>, Vector2<i32>)> {
+        let (min, max) = match *self {
+            Self::Infinite => (min, max),
+            Self::Torus { width, height } => (
+                Vector2::new(min.x.max(0), min.y.max(0)),
+                Vector2::new(max.x.min(width - 1), max.y.min(height - 1)),
+            ),
+        };
+        (min.x <= max.x && min.y <= max.y).then_some((min, max))
+    }
+
+    /// The cells just outside the world that fall within an inclusive range,
+    /// for outlining its edge. An infinite world has no edge.
+    pub fn edge_cells(&self, min: Vector2<i32>, max: Vector2<i32>) -> Vec<Vector2<i32>> {
+        let Self::Torus { width, height } = *self else {
+            return Vec::new();
+        };
+        outline_cells(Vector2::new(-1, -1), Vector2::new(width, height), min, max)
+    }
+}
+
+/// The cells on the border of the rectangle from `corner_min` to `corner_max`
+/// that fall within the inclusive range from `min` to `max`.
+pub fn outline_cells(
+    corner_min: Vector2<i32>,
+    corner_max: Vector2<i32>,
+    min: Vector2<i32>,
+    max: Vector2<i32>,
+) -> Vec<Vector2<i32>> {
+    // Only the visible part of the outline is walked, however large the
+    // rectangle is
+    let xs = min.x.max(corner_min.x)..=max.x.min(corner_max.x);
+    let ys = min.y.max(corner_min.y)..=max.y.min(corner_max.y);
+    let rows = [corner_min.y, corner_max.y]
+        .into_iter()
+        .filter(|y| ys.contains(y))
+        .flat_map(|y| xs.clone().map(move |x| Vector2::new(x, y)));
+    let columns = [corner_min.x, corner_max.x]
+        .into_iter()
+        .filter(|x| xs.contains(x))
+        .flat_map(|x| ys.clone().map(move |y| Vector2::new(x, y)));
+    let mut cells: Vec<_> = rows.chain(columns).collect();
+    // The corners are in both a row and a column
+    cells.sort_unstable_by_key(|c| (c.y, c.x));
+    cells.dedup();
+    cells
+}
+
+impl fmt::Display for WorldBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Infinite => write!(f, "unbounded plane"),
+            Self::Torus { width, height } => write!(f, "{width}x{height} torus"),
+        }
+    }
+}
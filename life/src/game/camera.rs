@@ -0,0 +1,110 @@
+use vec2::Vector2;
+use winit::dpi::PhysicalSize;
+
+/// Where the view is and how far it's zoomed in, for converting between
+/// positions in the window, in the world, and on the grid of cells. The
+/// window is always one world unit tall.
+#[derive(Debug, Clone, Copy)]
+pub struct Camera {
+    /// The world position shown at the top-left corner of the square in the
+    /// middle of the window
+    pub pan: Vector2<f64>,
+    /// The size of each cell in world units
+    pub grid_size: f32,
+}
+
+impl Camera {
+    /// How many pixels the window's left edge is left of the middle square's.
+    ///
+    /// This matches the vertex shader, which centers the view horizontally. In
+    /// tall windows the shift is negative, which is still correct.
+    fn shift(size: PhysicalSize<u32>) -> f64 {
+        (size.width as f64 - size.height as f64) / 2.0
+    }
+
+    /// Convert a position in the window, in pixels, to world space.
+    pub fn screen_to_world(&self, screen: Vector2<f64>, size: PhysicalSize<u32>) -> Vector2<f64> {
+        let shifted = Vector2::new(screen.x - Self::shift(size), screen.y);
+        shifted / size.height as f64 + self.pan
+    }
+
+    /// Convert a world position to a position in the window, in pixels.
+    #[allow(dead_code)] // Kept as the inverse of `screen_to_world`
+    pub fn world_to_screen(&self, world: Vector2<f64>, size: PhysicalSize<u32>) -> Vector2<f64> {
+        let shifted = (world - self.pan) * size.height as f64;
+        Vector2::new(shifted.x + Self::shift(size), shifted.y)
+    }
+
+    /// The cell containing a world position.
+    pub fn world_to_cell(&self, world: Vector2<f64>) -> Vector2<i32> {
+        let cell = world / self.grid_size as f64;
+        cell.map(|c| c.floor() as i32)
+    }
+
+    /// The world position of the center of a cell.
+    pub fn cell_to_world(&self, cell: Vector2<i32>) -> Vector2<f64> {
+        let cell = Vector2::new(cell.x as f64 + 0.5, cell.y as f64 + 0.5);
+        cell * self.grid_size as f64
+    }
+
+    /// The cell under a position in the window.
+    pub fn screen_to_cell(&self, screen: Vector2<f64>, size: PhysicalSize<u32>) -> Vector2<i32> {
+        self.world_to_cell(self.screen_to_world(screen, size))
+    }
+
+    /// The position in the window of the center of a cell, in pixels.
+    #[allow(dead_code)] // Kept as the inverse of `screen_to_cell`
+    pub fn cell_to_screen(&self, cell: Vector2<i32>, size: PhysicalSize<u32>) -> Vector2<f64> {
+        self.world_to_screen(self.cell_to_world(cell), size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SQUARE: PhysicalSize<u32> = PhysicalSize::new(100, 100);
+    const WIDE: PhysicalSize<u32> = PhysicalSize::new(200, 100);
+    const TALL: PhysicalSize<u32> = PhysicalSize::new(100, 200);
+
+    fn camera() -> Camera {
+        Camera {
+            pan: Vector2::new(0.0, 0.0),
+            grid_size: 0.1,
+        }
+    }
+
+    #[test]
+    fn clicks_map_to_cells_in_any_window_shape() {
+        let camera = camera();
+        assert_eq!(camera.screen_to_cell(Vector2::new(55.0, 25.0), SQUARE), Vector2::new(5, 2));
+        assert_eq!(camera.screen_to_cell(Vector2::new(105.0, 25.0), WIDE), Vector2::new(5, 2));
+        assert_eq!(camera.screen_to_cell(Vector2::new(5.0, 50.0), TALL), Vector2::new(2, 2));
+        // Left of the middle square in a wide window
+        assert_eq!(camera.screen_to_cell(Vector2::new(0.0, 0.0), WIDE), Vector2::new(-5, 0));
+
+        // The middle of the window is the middle of the view
+        for size in [SQUARE, WIDE, TALL] {
+            let middle = Vector2::new(size.width as f64 / 2.0, size.height as f64 / 2.0);
+            let world = camera.screen_to_world(middle, size);
+            assert!((world - Vector2::new(0.5, 0.5)).magnitude() < 1e-9, "{size:?}");
+        }
+    }
+
+    #[test]
+    fn cells_round_trip_through_the_screen() {
+        for (pan, grid_size) in [((0.0, 0.0), 0.1), ((-3.25, 7.5), 0.013), ((100.0, -0.4), 2.5)] {
+            let camera = Camera {
+                pan: Vector2::new(pan.0, pan.1),
+                grid_size,
+            };
+            for size in [SQUARE, WIDE, TALL, PhysicalSize::new(1920, 1080)] {
+                for cell in [(0, 0), (-1, -1), (17, -250), (-4000, 31)] {
+                    let cell = Vector2::new(cell.0, cell.1);
+                    let screen = camera.cell_to_screen(cell, size);
+                    assert_eq!(camera.screen_to_cell(screen, size), cell, "{size:?}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,308 @@
+//! Stepping an unbounded board in 16x16 tiles.
+//!
+//! Each tile with living cells is stored as 16 rows of bits, and the next
+//! generation of a tile is computed for a whole row at a time by adding up the
+//! neighbor bits with bitwise operations. Only tiles with living cells, and
+//! the tiles next to their edges where cells could be born, are computed.
+//! With the `rayon` feature, the tiles are split between rayon's threads.
+
+use rustc_hash::FxHashMap;
+use vec2::Vector2;
+
+use super::{rules::Rules, Cancel, LivingList};
+
+/// The side length of a tile, which is the number of bits in a row
+const CHUNK_SIZE: i32 = 16;
+
+/// One row of bits per row of cells, with bit `x` set if the cell `x` cells
+/// from the left edge is alive.
+type Chunk = [u16; CHUNK_SIZE as usize];
+
+/// How many tiles each thread should have at least, below which stepping on
+/// more threads isn't worth starting them
+#[cfg(feature = "rayon")]
+const MIN_CHUNKS_PER_THREAD: usize = 256;
+
+/// Compute the generation after `prev` under `rules` on an unbounded board,
+/// or `None` if `cancel` stops it first. It is checked between tiles.
+pub fn step(prev: &LivingList, rules: &Rules, cancel: &Cancel) -> Option<LivingList> {
+    let chunks = to_chunks(prev);
+    let active = active_chunks(&chunks);
+    let mut next = LivingList::with_capacity_and_hasher(prev.len(), Default::default());
+    #[cfg(feature = "rayon")]
+    {
+        let threads = rayon::current_num_threads().min(active.len() / MIN_CHUNKS_PER_THREAD);
+        if threads > 1 {
+            step_parallel(&chunks, &active, rules, threads, cancel, &mut next);
+            return (!cancel.is_cancelled()).then_some(next);
+        }
+    }
+    step_sequential(&chunks, &active, rules, cancel, &mut next);
+    (!cancel.is_cancelled()).then_some(next)
+}
+
+/// Step the active tiles one after another on this thread, stopping early if
+/// `cancel` says to.
+fn step_sequential(
+    chunks: &FxHashMap<Vector2<i32>, Chunk>,
+    active: &[Vector2<i32>],
+    rules: &Rules,
+    cancel: &Cancel,
+    next: &mut LivingList,
+) {
+    for &key in active {
+        if cancel.is_cancelled() {
+            return;
+        }
+        let chunk = step_chunk(chunks, key, rules);
+        push_cells(next, key, &chunk);
+    }
+}
+
+/// Step the active tiles split evenly into `threads` parts on rayon's thread
+/// pool. The parts are stepped in parallel, and their cells are collected on
+/// this thread. Each part stops early if `cancel` says to.
+#[cfg(feature = "rayon")]
+fn step_parallel(
+    chunks: &FxHashMap<Vector2<i32>, Chunk>,
+    active: &[Vector2<i32>],
+    rules: &Rules,
+    threads: usize,
+    cancel: &Cancel,
+    next: &mut LivingList,
+) {
+    use rayon::prelude::*;
+
+    let per_thread = active.len().div_ceil(threads);
+    let parts: Vec<_> = active
+        .par_chunks(per_thread)
+        .map(|keys| {
+            keys.iter()
+                .take_while(|_| !cancel.is_cancelled())
+                .map(|&key| (key, step_chunk(chunks, key, rules)))
+                .filter(|(_, chunk)| chunk.iter().any(|row| *row != 0))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    for (key, chunk) in parts.into_iter().flatten() {
+        push_cells(next, key, &chunk);
+    }
+}
+
+/// Sort the living cells into tiles, keyed by tile position.
+fn to_chunks(cells: &LivingList) -> FxHashMap<Vector2<i32>, Chunk> {
+    let mut chunks: FxHashMap<Vector2<i32>, Chunk> = FxHashMap::default();
+    for cell in cells {
+        let key = Vector2::new(cell.x.div_euclid(CHUNK_SIZE), cell.y.div_euclid(CHUNK_SIZE));
+        let chunk = chunks.entry(key).or_default();
+        chunk[cell.y.rem_euclid(CHUNK_SIZE) as usize] |= 1 << cell.x.rem_euclid(CHUNK_SIZE);
+    }
+    chunks
+}
+
+/// The tiles that could have living cells in the next generation: those with
+/// living cells now, and their neighbors across any edge or corner that has a
+/// living cell on it.
+fn active_chunks(chunks: &FxHashMap<Vector2<i32>, Chunk>) -> Vec<Vector2<i32>> {
+    const LAST: usize = CHUNK_SIZE as usize - 1;
+    const RIGHT_BIT: u16 = 1 << LAST;
+
+    let mut active = Vec::with_capacity(chunks.len() * 2);
+    for (key, chunk) in chunks {
+        active.push(*key);
+        let left = chunk.iter().any(|row| row & 1 != 0);
+        let right = chunk.iter().any(|row| row & RIGHT_BIT != 0);
+        let top = chunk[0] != 0;
+        let bottom = chunk[LAST] != 0;
+        let neighbors = [
+            (-1, 0, left),
+            (1, 0, right),
+            (0, -1, top),
+            (0, 1, bottom),
+            (-1, -1, chunk[0] & 1 != 0),
+            (1, -1, chunk[0] & RIGHT_BIT != 0),
+            (-1, 1, chunk[LAST] & 1 != 0),
+            (1, 1, chunk[LAST] & RIGHT_BIT != 0),
+        ];
+        for (dx, dy, touching) in neighbors {
+            let neighbor = *key + Vector2::new(dx, dy);
+            if touching && !chunks.contains_key(&neighbor) {
+                active.push(neighbor);
+            }
+        }
+    }
+    active.sort_unstable_by_key(|k| (k.x, k.y));
+    active.dedup();
+    active
+}
+
+/// Compute the next generation of one tile.
+fn step_chunk(chunks: &FxHashMap<Vector2<i32>, Chunk>, key: Vector2<i32>, rules: &Rules) -> Chunk {
+    let rows = extended_rows(chunks, key);
+    let (birth, survival) = rules.masks();
+    let mut next = Chunk::default();
+    for (y, row) in next.iter_mut().enumerate() {
+        // Each word lines its neighbor up with the output bits, where bit
+        // `x + 1` of an extended row is the cell at `x`
+        let [above, middle, below] = [rows[y], rows[y + 1], rows[y + 2]];
+        let neighbors = [
+            above,
+            above >> 1,
+            above >> 2,
+            middle,
+            middle >> 2,
+            below,
+            below >> 1,
+            below >> 2,
+        ];
+        let planes = count_bits(&neighbors);
+        let alive = middle >> 1;
+
+        let mut result = 0;
+        for count in 0..9 {
+            let born = if alive_for(birth, count) { !alive } else { 0 };
+            let survives = if alive_for(survival, count) { alive } else { 0 };
+            if born | survives != 0 {
+                result |= count_mask(&planes, count) & (born | survives);
+            }
+        }
+        *row = result as u16;
+    }
+    next
+}
+
+fn alive_for(mask: u32, count: u32) -> bool {
+    mask >> count & 1 != 0
+}
+
+/// The rows of a tile with the row above and below it and one cell on either
+/// side, taken from the neighboring tiles. Bit `x + 1` of each is the cell at
+/// `x`, from -1 to 16.
+fn extended_rows(
+    chunks: &FxHashMap<Vector2<i32>, Chunk>,
+    key: Vector2<i32>,
+) -> [u32; CHUNK_SIZE as usize + 2] {
+    const LAST: usize = CHUNK_SIZE as usize - 1;
+    let get = |dx, dy| chunks.get(&(key + Vector2::new(dx, dy)));
+    let row_of = |dy: i32, y: usize| -> u32 {
+        let center = get(0, dy).map_or(0, |c| c[y] as u32);
+        let left = get(-1, dy).map_or(0, |c| (c[y] >> LAST) as u32);
+        let right = get(1, dy).map_or(0, |c| (c[y] & 1) as u32);
+        left | center << 1 | right << (CHUNK_SIZE + 1)
+    };
+
+    let mut rows = [0; CHUNK_SIZE as usize + 2];
+    rows[0] = row_of(-1, LAST);
+    for y in 0..CHUNK_SIZE as usize {
+        rows[y + 1] = row_of(0, y);
+    }
+    rows[CHUNK_SIZE as usize + 1] = row_of(1, 0);
+    rows
+}
+
+/// Add up words bit by bit, giving the binary digits of each bit's total from
+/// least to most significant.
+fn count_bits(words: &[u32; 8]) -> [u32; 4] {
+    let mut planes = [0; 4];
+    for &word in words {
+        let mut carry = word;
+        for plane in &mut planes {
+            let sum = *plane ^ carry;
+            carry &= *plane;
+            *plane = sum;
+        }
+    }
+    planes
+}
+
+/// The bits whose total from `count_bits` is `count`.
+fn count_mask(planes: &[u32; 4], count: u32) -> u32 {
+    planes.iter().enumerate().fold(!0, |mask, (i, plane)| {
+        mask & if count >> i & 1 != 0 { *plane } else { !*plane }
+    })
+}
+
+/// Add the living cells of a tile to a list.
+fn push_cells(cells: &mut LivingList, key: Vector2<i32>, chunk: &Chunk) {
+    let origin = key * CHUNK_SIZE;
+    for (y, &row) in chunk.iter().enumerate() {
+        let mut bits = row;
+        while bits != 0 {
+            let x = bits.trailing_zeros() as i32;
+            cells.insert(origin + Vector2::new(x, y as i32));
+            bits &= bits - 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vec2::Vector2;
+
+    use std::sync::atomic::AtomicU64;
+
+    use super::{step, Rules};
+    use crate::game::{
+        benchmark::random_board_seeded,
+        bounds::WorldBounds,
+        step_fuzz::{reference_step, translate},
+        Cancel,
+    };
+
+    #[test]
+    fn large_soup_matches_reference() {
+        // Big enough to cover many tiles on both sides of the origin
+        let soup = random_board_seeded(160, 0.35, 1511);
+        let mut expected = translate(&soup, Vector2::new(-80, -70));
+        let mut actual = expected.clone();
+        let rules = Rules::CONWAY;
+        for generation in 1..=50 {
+            expected = reference_step(&expected, &rules, &WorldBounds::Infinite);
+            actual = step(&actual, &rules, &Cancel::NEVER).unwrap();
+            assert_eq!(
+                actual, expected,
+                "the tiles differ at generation {generation}"
+            );
+        }
+    }
+
+    #[test]
+    fn cancelled_steps_give_nothing() {
+        let soup = random_board_seeded(160, 0.35, 1521);
+        let epoch = AtomicU64::new(3);
+        assert!(step(&soup, &Rules::CONWAY, &Cancel::when_changed(&epoch, 3)).is_some());
+        assert!(step(&soup, &Rules::CONWAY, &Cancel::when_changed(&epoch, 2)).is_none());
+    }
+}
+
+#[cfg(all(test, feature = "rayon"))]
+mod parallel_tests {
+    use vec2::Vector2;
+
+    use super::{active_chunks, step_parallel, step_sequential, to_chunks, Rules};
+    use crate::game::{benchmark::random_board_seeded, step_fuzz::translate, Cancel, LivingList};
+
+    #[test]
+    fn parallel_matches_sequential() {
+        let mut cells = translate(
+            &random_board_seeded(200, 0.3, 1512),
+            Vector2::new(-90, -110),
+        );
+        let rules = Rules::CONWAY;
+        for generation in 1..=20 {
+            let chunks = to_chunks(&cells);
+            let active = active_chunks(&chunks);
+            let mut sequential = LivingList::default();
+            step_sequential(&chunks, &active, &rules, &Cancel::NEVER, &mut sequential);
+            // More threads than the step would pick for a board this size, so
+            // that the split is exercised
+            let mut parallel = LivingList::default();
+            step_parallel(&chunks, &active, &rules, 7, &Cancel::NEVER, &mut parallel);
+            assert_eq!(
+                parallel, sequential,
+                "the threads differ at generation {generation}"
+            );
+            cells = sequential;
+        }
+    }
+}
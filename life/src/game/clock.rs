@@ -0,0 +1,69 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+/// Where the game gets the current time from. Everything that paces the game
+/// asks the clock instead of calling `Instant::now()`, so that time can be
+/// driven by hand when checking timing-dependent behavior.
+#[derive(Debug, Clone, Default)]
+pub enum Clock {
+    /// The system's monotonic clock
+    #[default]
+    System,
+    /// A clock that only moves when it is told to
+    #[cfg_attr(not(test), allow(dead_code))] // Only constructed in tests
+    Manual(ManualClock),
+}
+
+impl Clock {
+    pub fn now(&self) -> Instant {
+        match self {
+            Self::System => Instant::now(),
+            Self::Manual(clock) => clock.now(),
+        }
+    }
+
+    /// The time that has passed since `earlier`, or zero if `earlier` is in
+    /// the future.
+    pub fn since(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+}
+
+/// A clock that starts at the moment it is created and stands still until it
+/// is advanced. Clones share the same time.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    start: Instant,
+    /// Nanoseconds since `start`
+    elapsed: Arc<AtomicU64>,
+}
+
+#[cfg_attr(not(test), allow(dead_code))] // Only used in tests
+impl ManualClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn now(&self) -> Instant {
+        self.start + Duration::from_nanos(self.elapsed.load(Ordering::Relaxed))
+    }
+
+    /// Move the clock forward.
+    pub fn advance(&self, by: Duration) {
+        self.elapsed
+            .fetch_add(by.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
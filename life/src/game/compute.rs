@@ -0,0 +1,310 @@
+//! Choosing what computes each generation.
+
+#![cfg_attr(target_arch = "wasm32", allow(dead_code))]
+
+use super::{bounds::WorldBounds, rules::Rules, try_compute_step, Cancel, LivingList};
+
+/// What steps the simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ComputeBackend {
+    /// Count the neighbors of living cells on the CPU, in the worker thread
+    /// where there is one
+    #[default]
+    CpuWorker,
+    /// Step a dense grid around the living cells in a compute shader. Boards
+    /// too spread out for one grid are still stepped on the CPU.
+    GpuCompute,
+}
+
+impl ComputeBackend {
+    pub const ALL: [Self; 2] = [Self::CpuWorker, Self::GpuCompute];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::CpuWorker => "CPU",
+            Self::GpuCompute => "GPU",
+        }
+    }
+}
+
+/// Compute the next generation, on the GPU if one is given and it can handle
+/// the board, and on the CPU otherwise. Returns `None` if `cancel` stops the
+/// step, which the GPU only checks before it starts.
+pub fn step_on(
+    gpu: Option<&GpuStepper>,
+    prev: &LivingList,
+    rules: &Rules,
+    bounds: &WorldBounds,
+    cancel: &Cancel,
+) -> Option<LivingList> {
+    if cancel.is_cancelled() {
+        return None;
+    }
+    gpu.and_then(|gpu| gpu.step(prev, rules, bounds))
+        .or_else(|| try_compute_step(prev, rules, bounds, cancel))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use gpu::GpuStepper;
+
+/// Compute shaders aren't available through WebGL, and reading results back
+/// can't block on the web, so there is never a GPU stepper there.
+#[cfg(target_arch = "wasm32")]
+pub enum GpuStepper {}
+
+#[cfg(target_arch = "wasm32")]
+impl GpuStepper {
+    pub fn step(
+        &self,
+        _prev: &LivingList,
+        _rules: &Rules,
+        _bounds: &WorldBounds,
+    ) -> Option<LivingList> {
+        match *self {}
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod gpu {
+    use std::sync::{mpsc, Arc};
+
+    use vec2::Vector2;
+    use wgpu::util::DeviceExt;
+
+    use super::{LivingList, Rules, WorldBounds};
+
+    /// The side length of a workgroup in `step.wgsl`
+    const WORKGROUP_SIZE: u32 = 8;
+
+    /// Steps the simulation in a compute shader. On an unbounded board, the
+    /// living cells are copied into a grid covering their bounding box plus
+    /// one cell on each side, which is every cell that could be alive in the
+    /// next generation since rules with B0 aren't allowed. A torus is copied
+    /// whole into a grid whose edges wrap around.
+    pub struct GpuStepper {
+        device: Arc<wgpu::Device>,
+        queue: Arc<wgpu::Queue>,
+        pipeline: wgpu::ComputePipeline,
+        bind_group_layout: wgpu::BindGroupLayout,
+    }
+
+    impl GpuStepper {
+        /// Set up the compute pipeline, or return `None` if the adapter can't
+        /// run compute shaders.
+        pub fn new(
+            adapter: &wgpu::Adapter,
+            device: Arc<wgpu::Device>,
+            queue: Arc<wgpu::Queue>,
+        ) -> Option<Self> {
+            let downlevel = adapter.get_downlevel_capabilities();
+            let limits = device.limits();
+            if !downlevel
+                .flags
+                .contains(wgpu::DownlevelFlags::COMPUTE_SHADERS)
+                || limits.max_storage_buffers_per_shader_stage < 2
+                || limits.max_compute_invocations_per_workgroup < WORKGROUP_SIZE.pow(2)
+            {
+                return None;
+            }
+
+            let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("Step Shader"),
+                source: wgpu::ShaderSource::Wgsl(include_str!("./step.wgsl").into()),
+            });
+            let storage = |binding, read_only| wgpu::BindGroupLayoutEntry {
+                binding,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            };
+            let bind_group_layout =
+                device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("step_bind_group_layout"),
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: wgpu::ShaderStages::COMPUTE,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                        storage(1, true),
+                        storage(2, false),
+                    ],
+                });
+            let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Step Pipeline Layout"),
+                bind_group_layouts: &[&bind_group_layout],
+                push_constant_ranges: &[],
+            });
+            let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("Step Pipeline"),
+                layout: Some(&pipeline_layout),
+                module: &shader,
+                entry_point: "main",
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            });
+
+            Some(Self {
+                device,
+                queue,
+                pipeline,
+                bind_group_layout,
+            })
+        }
+
+        /// Compute the next generation, or return `None` if the board is too
+        /// spread out to fit in one grid or the device fails.
+        pub fn step(
+            &self,
+            prev: &LivingList,
+            rules: &Rules,
+            bounds: &WorldBounds,
+        ) -> Option<LivingList> {
+            let (origin, width, height) = match *bounds {
+                WorldBounds::Infinite => {
+                    let first = *prev.iter().next()?;
+                    let (min, max) = prev.iter().fold((first, first), |(min, max), cell| {
+                        (
+                            Vector2::new(min.x.min(cell.x), min.y.min(cell.y)),
+                            Vector2::new(max.x.max(cell.x), max.y.max(cell.y)),
+                        )
+                    });
+                    (
+                        Vector2::new(min.x as i64 - 1, min.y as i64 - 1),
+                        max.x as i64 - min.x as i64 + 3,
+                        max.y as i64 - min.y as i64 + 3,
+                    )
+                }
+                WorldBounds::Torus { width, height } => {
+                    (Vector2::new(0, 0), width as i64, height as i64)
+                }
+            };
+            let wrap = u32::from(*bounds != WorldBounds::Infinite);
+
+            let limits = self.device.limits();
+            let max_cells = limits.max_storage_buffer_binding_size as i64 / 4;
+            let max_groups = limits.max_compute_workgroups_per_dimension as i64;
+            let groups = |len: i64| (len + WORKGROUP_SIZE as i64 - 1) / WORKGROUP_SIZE as i64;
+            if width * height > max_cells
+                || groups(width) > max_groups
+                || groups(height) > max_groups
+            {
+                return None;
+            }
+            let (width, height) = (width as usize, height as usize);
+
+            let mut grid = vec![0u32; width * height];
+            for cell in prev {
+                let x = (cell.x as i64 - origin.x) as usize;
+                let y = (cell.y as i64 - origin.y) as usize;
+                grid[y * width + x] = 1;
+            }
+            let size = (grid.len() * 4) as u64;
+
+            let (birth, survival) = rules.masks();
+            let params = [width as u32, height as u32, birth, survival, wrap];
+            let params_buffer = self
+                .device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Step Params Buffer"),
+                    contents: bytemuck::cast_slice(&params),
+                    usage: wgpu::BufferUsages::UNIFORM,
+                });
+            let src_buffer = self
+                .device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Step Source Buffer"),
+                    contents: bytemuck::cast_slice(&grid),
+                    usage: wgpu::BufferUsages::STORAGE,
+                });
+            let dst_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Step Destination Buffer"),
+                size,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            });
+            let readback_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Step Readback Buffer"),
+                size,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("step_bind_group"),
+                layout: &self.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: params_buffer.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: src_buffer.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: dst_buffer.as_entire_binding(),
+                    },
+                ],
+            });
+
+            let mut encoder = self
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("Step Encoder"),
+                });
+            {
+                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                    label: Some("Step Pass"),
+                    timestamp_writes: None,
+                });
+                pass.set_pipeline(&self.pipeline);
+                pass.set_bind_group(0, &bind_group, &[]);
+                pass.dispatch_workgroups(
+                    groups(width as i64) as u32,
+                    groups(height as i64) as u32,
+                    1,
+                );
+            }
+            encoder.copy_buffer_to_buffer(&dst_buffer, 0, &readback_buffer, 0, size);
+            let submission = self.queue.submit(Some(encoder.finish()));
+
+            let slice = readback_buffer.slice(..);
+            let (tx, rx) = mpsc::channel();
+            slice.map_async(wgpu::MapMode::Read, move |result| {
+                let _ = tx.send(result);
+            });
+            self.device
+                .poll(wgpu::Maintain::WaitForSubmissionIndex(submission));
+            if let Err(e) = rx.recv().ok()? {
+                log::warn!("Failed to read a step back from the GPU: {e}");
+                return None;
+            }
+
+            let next = {
+                let data = slice.get_mapped_range();
+                let cells: &[u32] = bytemuck::cast_slice(&data);
+                cells
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, alive)| **alive == 1)
+                    .map(|(i, _)| {
+                        let x = (i % width) as i64 + origin.x;
+                        let y = (i / width) as i64 + origin.y;
+                        Vector2::new(x as i32, y as i32)
+                    })
+                    .collect()
+            };
+            readback_buffer.unmap();
+            Some(next)
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! A compact summary of the game's state, for attaching to bug reports.
+
+use std::fmt;
+
+use vec2::Vector2;
+
+use super::{bounds::WorldBounds, compute::ComputeBackend, rules::Rules};
+
+/// What's needed to reproduce most issues, taken at one moment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostics {
+    pub population: usize,
+    pub generation: u64,
+    /// The inclusive bounding box of the living cells as `(min, max)`
+    pub bounding_box: Option<(Vector2<i32>, Vector2<i32>)>,
+    pub grid_size: f32,
+    pub pan_position: Vector2<f64>,
+    pub playing: bool,
+    /// Whether a generation has been requested but hasn't arrived yet
+    pub worker_busy: bool,
+    /// Whether steps are computed on the main thread instead of a worker
+    pub worker_degraded: bool,
+    pub compute_backend: ComputeBackend,
+    pub rules: Rules,
+    pub bounds: WorldBounds,
+}
+
+impl Diagnostics {
+    /// Print the diagnostics to stderr, or to the console on the web.
+    pub fn print(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        eprintln!("{self}");
+        #[cfg(target_arch = "wasm32")]
+        web_sys::console::log_1(&self.to_string().into());
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "--- life diagnostics ---")?;
+        writeln!(
+            f,
+            "population {} at generation {}",
+            self.population, self.generation
+        )?;
+        match self.bounding_box {
+            Some((min, max)) => writeln!(
+                f,
+                "bounds ({}, {}) to ({}, {})",
+                min.x, min.y, max.x, max.y
+            )?,
+            None => writeln!(f, "bounds none")?,
+        }
+        writeln!(f, "rule {} on the {}", self.rules, self.bounds)?;
+        writeln!(
+            f,
+            "grid size {}, pan ({}, {})",
+            self.grid_size, self.pan_position.x, self.pan_position.y
+        )?;
+        write!(
+            f,
+            "{}, worker {}{}, compute on {}",
+            if self.playing { "playing" } else { "paused" },
+            if self.worker_busy { "busy" } else { "idle" },
+            if self.worker_degraded {
+                " (on the main thread)"
+            } else {
+                ""
+            },
+            self.compute_backend.name(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{compute_step, patterns};
+
+    #[test]
+    fn summarizes_a_stepped_glider() {
+        let rules = Rules::from_bs_string("B36/S23").unwrap();
+        let bounds = WorldBounds::Infinite;
+        let mut cells = patterns::parse_rle("bo$2bo$3o!").unwrap();
+        for _ in 0..4 {
+            cells = compute_step(&cells, &rules, &bounds);
+        }
+        let diagnostics = Diagnostics {
+            population: cells.len(),
+            generation: 4,
+            bounding_box: patterns::bounding_box(&cells),
+            grid_size: 0.05,
+            pan_position: Vector2::new(-1.5, 2.0),
+            playing: false,
+            worker_busy: false,
+            worker_degraded: true,
+            compute_backend: ComputeBackend::CpuWorker,
+            rules,
+            bounds,
+        };
+        assert_eq!(diagnostics.bounding_box, Some((Vector2::new(1, 1), Vector2::new(3, 3))));
+        assert_eq!(
+            diagnostics.to_string(),
+            "--- life diagnostics ---\n\
+             population 5 at generation 4\n\
+             bounds (1, 1) to (3, 3)\n\
+             rule B36/S23 on the unbounded plane\n\
+             grid size 0.05, pan (-1.5, 2)\n\
+             paused, worker idle (on the main thread), compute on CPU"
+        );
+    }
+}
@@ -11,14 +11,14 @@ use std::thread::JoinHandle;
 #[cfg(feature = "native_threads")]
 use std::sync::{
     self,
-    atomic::{self, AtomicBool},
+    atomic::{self, AtomicBool, AtomicU64},
     mpsc, Condvar, Mutex
 };
 
 use winit::{
-    dpi::{PhysicalPosition, PhysicalSize},
+    dpi::PhysicalPosition,
     event::{ElementState, KeyEvent, MouseButton, MouseScrollDelta, WindowEvent},
-    keyboard::{Key, KeyCode, NamedKey, PhysicalKey, SmolStr},
+    keyboard::{Key, KeyCode, ModifiersState, NamedKey, PhysicalKey, SmolStr},
     window::Window,
 };
 #[cfg(not(target_arch = "wasm32"))]
@@ -31,19 +31,137 @@ use crate::game::saving::SaveFile;
 #[cfg(feature = "saving")]
 use self::saving::SaveGame;
 
-use super::render::Cell;
+use super::render::{
+    recording::GifSettings, Cell, CellShape, OverlayCell, CELL_COLOR, CLEAR_COLOR,
+};
 use vec2::Vector2;
 
 #[cfg(feature = "saving")]
 pub mod saving;
 
+pub mod patterns;
+
+pub mod benchmark;
+
+mod stability;
+use stability::StabilityDetector;
+
+pub mod spaceships;
+use spaceships::{Spaceship, SpaceshipDetector};
+
+pub mod clock;
+use clock::Clock;
+
+pub mod snapshot;
+use snapshot::SimulationSnapshot;
+
+#[cfg(any(target_arch = "wasm32", test))]
+mod session;
+#[cfg(target_arch = "wasm32")]
+use session::{Session, SessionAutosave};
+
+pub mod camera;
+use camera::Camera;
+
+pub mod compute;
+use compute::{ComputeBackend, GpuStepper};
+
+pub mod diagnostics;
+use diagnostics::Diagnostics;
+
+pub mod series;
+use series::{GenerationPopulation, PopulationSeries};
+
+pub mod step_diff;
+use step_diff::StepDiff;
+use benchmark::{Benchmark, BenchmarkSettings, BenchmarkSummary};
+
+pub mod rules;
+use rules::Rules;
+
+pub mod bounds;
+use bounds::WorldBounds;
+
+pub mod presets;
+
+mod chunks;
+
+#[cfg(test)]
+mod step_fuzz;
+
+/// How many edits can be undone before the oldest are forgotten
+const UNDO_LIMIT: usize = 128;
+
+/// How long the step key has to be held before it starts stepping repeatedly
+const HOLD_STEP_DELAY: Duration = Duration::from_millis(400);
+
+/// How many of the most recent steps the measured step rate is taken over
+const STEP_RATE_SAMPLES: usize = 32;
+/// How long ago a step can have finished and still count toward the measured
+/// step rate
+const STEP_RATE_WINDOW: Duration = Duration::from_secs(5);
+
+/// How fast holding the movement keys pans the view, in cells per second
+const KEY_PAN_SPEED: f64 = 20.0;
+
+/// How long the board must sit still without input before power saving starts,
+/// unless that's changed
+const DEFAULT_POWER_SAVE_AFTER: Duration = Duration::from_secs(30);
+
 /// The interval between simulation steps in auto-play mode.
 const DEFAULT_INTERVAL: Duration = Duration::from_millis(300);
-/// The factor by which the interval will be multiplied or divided when
-/// the player changes the simulation speed.
+/// The interval between simulation steps in auto-play mode without a worker
+/// thread. Every step stalls the event loop then, so it's longer to leave
+/// time for everything else.
+const DEGRADED_INTERVAL: Duration = Duration::from_millis(500);
+/// Whether steps are computed on the main thread because there's no worker
+/// thread in this build
+pub const WORKER_DEGRADED: bool = !cfg!(feature = "native_threads");
+/// The shortest allowed interval between simulation steps. Anything shorter
+/// would just peg the CPU without visibly speeding anything up.
+pub const MIN_INTERVAL: Duration = Duration::from_millis(1);
+/// The most auto-play steps that can be waiting to be caught up on. Without a
+/// limit, a long stall would be followed by a long burst of steps.
+const MAX_CATCH_UP_STEPS: u32 = 10;
+/// How many generations the worker thread may compute ahead by default.
+#[cfg(feature = "native_threads")]
+const DEFAULT_WORKER_CAPACITY: usize = 2;
+/// The color of the cells a pasted pattern will occupy when placed.
+const PASTE_PREVIEW_TINT: [f32; 4] = [1.0, 1.0, 1.0, 0.35];
+/// The color of the cells just outside a bounded world
+const WORLD_EDGE_TINT: [f32; 4] = [0.5, 0.5, 0.5, 0.6];
+/// The color of the marker at the living cells' center of mass
+const CENTROID_MARKER_TINT: [f32; 4] = [1.0, 0.1, 0.6, 0.9];
+/// The color of the newest centroid in the trail, which older ones fade from
+const CENTROID_TRAIL_TINT: [f32; 4] = [1.0, 0.4, 0.8, 0.5];
+/// The color of the outline around the selected cells
+const SELECTION_TINT: [f32; 4] = [0.3, 0.6, 1.0, 0.6];
+/// How many generations' centroids the trail shows, unless that's changed
+const DEFAULT_TRAIL_LENGTH: usize = 32;
+/// The color of each cell in the neighbor count overlay, indexed by how many
+/// living neighbors it has. Counts that kill are blue or red, the count that
+/// lets cells survive is green, and the count that brings them to life yellow.
+const NEIGHBOR_COUNT_TINTS: [[f32; 4]; 9] = [
+    [0.1, 0.2, 0.9, 0.45],
+    [0.2, 0.5, 0.9, 0.45],
+    [0.2, 0.9, 0.3, 0.45],
+    [1.0, 0.9, 0.1, 0.45],
+    [1.0, 0.6, 0.1, 0.45],
+    [1.0, 0.4, 0.1, 0.45],
+    [1.0, 0.2, 0.1, 0.45],
+    [0.9, 0.1, 0.1, 0.45],
+    [0.8, 0.0, 0.2, 0.45],
+];
+/// The default factor by which the interval will be multiplied or divided
+/// when the player changes the simulation speed.
 const INTERVAL_P: f32 = 1.2;
+/// The smallest and largest cell sizes, as fractions of the window's height
+const MIN_GRID_SIZE: f32 = 0.005;
+const MAX_GRID_SIZE: f32 = 1.0;
+/// How long the speed is shown on screen after the player changes it.
+const SPEED_INDICATOR_DURATION: Duration = Duration::from_millis(1500);
 
-type LivingList = FxHashSet<Vector2<i32>>;
+pub type LivingList = FxHashSet<Vector2<i32>>;
 
 pub struct GameState {
     pan_position: Vector2<f64>,
@@ -56,6 +174,8 @@ pub struct GameState {
     window: Arc<Window>,
     mouse_position: Option<Vector2<f64>>,
     grid_size: f32,
+    /// The zoom the game started at
+    default_grid_size: f32,
     drag_state: DragState,
     /// A queue of inputs that were made during computation and therefore
     /// deferred.
@@ -64,21 +184,151 @@ pub struct GameState {
     /// Synchronization between the main thread and the computing thread
     thread_data: ThreadData,
     living_cell_count: usize,
+    /// The gap between rendered cells as a fraction of their size
+    cell_padding: f32,
+    /// The color behind the cells, as RGBA
+    clear_color: [f32; 4],
+    /// The solid color of living cells when zoomed far out, as RGBA
+    cell_color: [f32; 4],
+    cell_shape: CellShape,
+    /// Whether and where the camera is following the living cells
+    follow: Option<FollowState>,
+    /// A pattern waiting to be placed with the mouse, with the top-left of its
+    /// bounding box at (0, 0).
+    paste_buffer: Option<LivingList>,
+    /// Whether to color cells by their number of living neighbors while
+    /// paused
+    show_neighbor_counts: bool,
+    /// Whether to mark the living cells' center of mass
+    show_centroid: bool,
+    /// Whether to draw lines along the cell boundaries
+    show_grid: bool,
+    /// Whether to draw faint copies of the cells on the edges of a torus past
+    /// the opposite edges
+    show_wrap_ghosts: bool,
+    /// The centers of mass of the most recent generations, oldest first
+    centroid_trail: VecDeque<Vector2<f64>>,
+    /// When the most recent steps finished, oldest first
+    step_times: VecDeque<Instant>,
+    /// How many centroids the trail keeps
+    trail_length: usize,
+    /// Whether the view keeps moving for a moment after a quick pan
+    pan_inertia: bool,
+    /// Whether scrolling and dragging are ignored, so the view can't be moved
+    /// by accident
+    camera_locked: bool,
+    /// Whether cells can still be toggled while the camera is locked
+    edit_while_locked: bool,
+    /// Whether dragging with the left button brings cells to life, rather
+    /// than clicks toggling them
+    paint_mode: bool,
+    /// The last cell painted while the left button is held in paint mode
+    brush: Option<Vector2<i32>>,
+    /// Whether the board was remembered for undoing during this brush stroke
+    stroke_remembered: bool,
+    /// The opposite corners of the selected rectangle of cells, inclusive
+    selection: Option<(Vector2<i32>, Vector2<i32>)>,
+    /// The cell a selection started from, while it is being dragged out
+    selection_anchor: Option<Vector2<i32>>,
+    /// The cells last copied from a selection, relative to its top-left
+    clipboard: Option<LivingList>,
+    glide: Option<Glide>,
+    /// The movement keys held for panning
+    pan_keys: FxHashSet<KeyCode>,
+    /// When the held movement keys last moved the view, while any are held
+    key_pan_at: Option<Instant>,
+    /// Keeps the board and view across page reloads
+    #[cfg(target_arch = "wasm32")]
+    session: SessionAutosave,
+    /// The factor by which the arrow keys change the speed
+    speed_factor: f32,
+    /// When the arrow keys last changed the speed
+    speed_changed_at: Option<Instant>,
+    /// The source of the current time for everything that paces the game
+    clock: Clock,
+    /// What steps the simulation
+    compute_backend: ComputeBackend,
+    /// Steps the simulation on the graphics device, if it supports that
+    gpu_stepper: Option<Arc<GpuStepper>>,
+    /// Which neighbor counts give birth and which let cells survive
+    rules: Rules,
+    bounds: WorldBounds,
+    /// Whether to catch up on auto-play steps instead of skipping them
+    catch_up: bool,
+    /// Auto-play steps that have come due but haven't been taken yet
+    pending_steps: u32,
+    /// The generation that the simulation is being run to as fast as steps
+    /// can be computed, if any
+    target_generation: Option<u64>,
+    /// While the step key is held, when the next step is due
+    held_step_due: Option<Instant>,
+    /// Paces auto-play by ticks instead of the clock in deterministic mode
+    ticks: TickPacer,
+    /// Watches for the board settling into a still life or oscillator
+    stability: StabilityDetector,
+    /// Finds moving clusters each generation, if enabled
+    spaceships: Option<SpaceshipDetector>,
+    /// What the last step changed, if it was taken while paused
+    last_step: Option<StepDiff>,
+    /// The population at which auto-play stops, if any
+    population_cap: Option<usize>,
+    /// Whether to stop auto-play whenever a detected cycle returns to its
+    /// phase zero
+    pause_at_phase_zero: bool,
+    /// How long auto-play may run without input before it is paused, if at
+    /// all
+    idle_timeout: Option<Duration>,
+    /// How long the board must sit still without input before the window
+    /// stops redrawing until something happens, or `None` to keep checking
+    /// for changes every frame
+    power_save_after: Option<Duration>,
+    /// When the player last gave any input
+    last_input: Instant,
+    /// Whether auto-play was paused by the idle timeout, and there hasn't
+    /// been input since
+    idle_paused: bool,
+    /// The index of the save that was last loaded, if any
+    #[cfg(feature = "saving")]
+    current_save: Option<usize>,
+    /// Whether flipping through the saves wraps around at the ends
+    #[cfg(feature = "saving")]
+    wrap_saves: bool,
+    /// Whether to save the board automatically when it becomes stable
+    #[cfg(feature = "saving")]
+    snapshot_on_stable: bool,
+    /// The cycle that was last saved automatically, so it isn't saved twice
+    #[cfg(feature = "saving")]
+    last_snapshot: Option<u64>,
+    /// The stress test currently running, if any
+    benchmark: Option<Benchmark>,
+    /// The results of the last finished stress test
+    benchmark_summary: Option<BenchmarkSummary>,
 
     /// These are for the statistics view
     pub step_count: u64,
     pub living_count_history: Vec<usize>,
+    /// The name of the save the board was loaded from, until it's cleared
+    start_pattern: Option<String>,
+    /// The seed of the random board the run started from, until it's cleared
+    start_seed: Option<u64>,
 
     /// Changes to the state between renders are tracked here if they are
     /// relevant to the renderer so that they can be passed back on the next
     /// update.
     changes: StateChanges,
 
-    /// Represents a list of times that the "player" manually toggled a cell.
+    /// Represents a list of cells that the "player" manually changed, oldest
+    /// first.
     ///
     /// It is updated using `Self::step_count`, so may not be accurate if that
     /// is incorrectly manipulated.
-    pub toggle_record: Vec<u64>,
+    pub toggle_record: Vec<Toggle>,
+    /// The boards from before recent edits, oldest first, for undoing them
+    undo_stack: VecDeque<SimulationSnapshot>,
+    /// The boards from before recent undos, most recently undone last
+    redo_stack: Vec<SimulationSnapshot>,
+    /// The modifier keys currently held, for shortcuts
+    modifiers: ModifiersState,
 
     /// Saving data that is kept in memory during play and saved to disk when
     /// the game is closed.
@@ -100,8 +350,121 @@ impl GameState {
         self.interval
     }
 
+    /// Set the interval between steps in auto-play mode. It is clamped to be
+    /// no shorter than `MIN_INTERVAL`.
     pub fn set_interval(&mut self, to: Duration) {
-        self.interval = to;
+        self.interval = clamp_interval(to);
+    }
+
+    pub fn get_speed_factor(&self) -> f32 {
+        self.speed_factor
+    }
+
+    /// Set the factor by which each press of the arrow keys multiplies or
+    /// divides the speed. Factors below 1 would reverse the keys, so they are
+    /// raised to 1.
+    pub fn set_speed_factor(&mut self, factor: f32) {
+        self.speed_factor = factor.max(1.0);
+    }
+
+    /// Speed up (or slow down) auto-play by the speed factor and show the new
+    /// speed on screen for a moment.
+    fn change_speed(&mut self, faster: bool) {
+        self.interval = changed_interval(self.interval, self.speed_factor, faster);
+        self.speed_changed_at = Some(self.clock.now());
+    }
+
+    /// The auto-play speed in steps per second, if it was changed with the
+    /// keyboard recently enough that it should still be shown.
+    pub fn speed_indicator(&self) -> Option<f64> {
+        self.speed_changed_at
+            .filter(|t| self.clock.since(*t) < SPEED_INDICATOR_DURATION)
+            .map(|_| self.interval.as_secs_f64().recip())
+    }
+
+    pub fn get_cell_padding(&self) -> f32 {
+        self.cell_padding
+    }
+
+    /// Set the gap between rendered cells as a fraction of their size.
+    pub fn set_cell_padding(&mut self, to: f32) {
+        self.cell_padding = to;
+        self.changes.cell_padding = Some(to);
+    }
+
+    pub fn get_clear_color(&self) -> [f32; 4] {
+        self.clear_color
+    }
+
+    /// Set the color that the frame is cleared to behind the cells.
+    pub fn set_clear_color(&mut self, color: [f32; 4]) {
+        self.clear_color = color;
+        self.changes.clear_color = Some(color);
+    }
+
+    pub fn get_cell_color(&self) -> [f32; 4] {
+        self.cell_color
+    }
+
+    /// Set the solid color living cells are drawn in when zoomed far out. Each
+    /// component is clamped to the range 0 to 1.
+    pub fn set_cell_color(&mut self, color: [f32; 4]) {
+        let color = color.map(|c| c.clamp(0.0, 1.0));
+        self.cell_color = color;
+        self.changes.cell_color = Some(color);
+    }
+
+    pub fn get_cell_shape(&self) -> CellShape {
+        self.cell_shape
+    }
+
+    /// Set whether living cells are drawn with their texture or as solid
+    /// squares or circles.
+    pub fn set_cell_shape(&mut self, shape: CellShape) {
+        self.cell_shape = shape;
+        self.changes.cell_shape = Some(shape);
+    }
+
+    /// The current view, for converting between window, world, and cell
+    /// positions.
+    pub fn camera(&self) -> Camera {
+        Camera {
+            pan: self.pan_position,
+            grid_size: self.grid_size,
+        }
+    }
+
+    /// The size of each cell as a fraction of the window's height.
+    #[cfg_attr(not(feature = "saving"), allow(dead_code))]
+    pub fn grid_size(&self) -> f32 {
+        self.grid_size
+    }
+
+    /// Set the zoom level, clamped to `MIN_GRID_SIZE..=MAX_GRID_SIZE`.
+    pub fn set_grid_size(&mut self, size: f32) {
+        self.grid_size = size.clamp(MIN_GRID_SIZE, MAX_GRID_SIZE);
+        self.changes.grid_size = Some(self.grid_size);
+        self.changes.cells = Some(self.get_cells());
+        self.refresh_overlay();
+    }
+
+    /// The world-space position of the top-left corner of the view.
+    #[cfg_attr(not(feature = "saving"), allow(dead_code))]
+    pub fn pan_position(&self) -> Vector2<f64> {
+        self.pan_position
+    }
+
+    pub fn set_pan_position(&mut self, position: Vector2<f64>) {
+        self.pan_position = position;
+        self.changes.offset = Some(position);
+        if self.paste_buffer.is_some()
+            || self.selection.is_some()
+            || self.show_neighbor_counts
+            || self.show_centroid
+            || self.bounds != WorldBounds::Infinite
+        {
+            self.refresh_overlay();
+        }
     }
 
     /// Toggles playing. If it is starting, then it steps immediately.
@@ -109,10 +472,466 @@ impl GameState {
         if self.loop_state.is_playing() {
             self.loop_state = LoopState::Stopped;
         } else {
+            self.register_input();
             self.step();
-            let now = Instant::now();
+            let now = self.clock.now();
             self.loop_state = LoopState::Playing { last_update: now }
         }
+        // The neighbor counts are hidden while playing
+        if self.show_neighbor_counts {
+            self.refresh_overlay();
+        }
+    }
+
+    /// Bring cells to life across the visible part of the board wherever the
+    /// dead cell texture is dark, for an artistic starting point.
+    pub fn etch_background(&mut self, threshold: u8) {
+        let (min, max) = self.visible_cell_bounds();
+        let cells = patterns::etch_background(threshold, min, max);
+        self.stamp_pattern(cells, Vector2::new(0, 0));
+    }
+
+    /// The cells that are currently alive.
+    pub fn living_cells(&self) -> &LivingList {
+        &self.living_cells
+    }
+
+    /// Whether `cell` is alive. Edits that are still queued aren't reflected.
+    pub fn get_cell(&self, cell: Vector2<i32>) -> bool {
+        self.living_cells.contains(&cell)
+    }
+
+    /// The number of living cells adjacent to `cell`.
+    pub fn neighbors_alive(&self, cell: Vector2<i32>) -> u8 {
+        living_neighbors(&self.living_cells, cell, &self.bounds)
+    }
+
+    /// The inclusive range of cells that are at least partially visible in the
+    /// window, as `(top_left, bottom_right)`.
+    pub fn visible_cell_bounds(&self) -> (Vector2<i32>, Vector2<i32>) {
+        let size = self.window.inner_size();
+        let corner = Vector2::new(size.width as f64, size.height as f64);
+        let camera = self.camera();
+        (
+            camera.screen_to_cell(Vector2::new(0.0, 0.0), size),
+            camera.screen_to_cell(corner, size),
+        )
+    }
+
+    /// Start placing a pattern with the mouse. Until the next left click, the
+    /// cells it will occupy are highlighted under the cursor.
+    pub fn begin_paste(&mut self, cells: LivingList) {
+        let Some((min, _)) = patterns::bounding_box(&cells) else {
+            return;
+        };
+        self.paste_buffer = Some(cells.into_iter().map(|c| c - min).collect());
+        self.refresh_overlay();
+    }
+
+    /// Whether a pattern is waiting to be placed with the mouse.
+    pub fn is_pasting(&self) -> bool {
+        self.paste_buffer.is_some()
+    }
+
+    /// Stop placing a pattern with the mouse without placing it.
+    pub fn cancel_paste(&mut self) {
+        if self.paste_buffer.take().is_some() {
+            self.refresh_overlay();
+        }
+    }
+
+    /// Cancel any operation in progress, such as placing a pattern. Returns
+    /// whether there was anything to cancel.
+    pub fn cancel_pending(&mut self) -> bool {
+        let pending =
+            self.is_pasting() || self.selection.is_some() || self.target_generation.is_some();
+        self.cancel_paste();
+        self.clear_selection();
+        self.target_generation = None;
+        pending
+    }
+
+    /// Place the pattern waiting in the paste buffer with its top-left at the
+    /// cell under `mouse_position`.
+    fn commit_paste(&mut self, mouse_position: Vector2<f64>) {
+        let Some(cells) = self.paste_buffer.take() else {
+            return;
+        };
+        let size = self.window.inner_size();
+        let at = self.camera().screen_to_cell(mouse_position, size);
+        self.stamp_pattern(cells, at);
+        self.refresh_overlay();
+    }
+
+    /// The opposite corners of the selected rectangle, inclusive, if there is
+    /// a selection.
+    pub fn selection(&self) -> Option<(Vector2<i32>, Vector2<i32>)> {
+        self.selection
+    }
+
+    /// Select the cells between two opposite corners, inclusive. Selecting a
+    /// single cell, as a click without a drag would, selects nothing.
+    fn select(&mut self, a: Vector2<i32>, b: Vector2<i32>) {
+        let selection = (a != b).then(|| {
+            (
+                Vector2::new(a.x.min(b.x), a.y.min(b.y)),
+                Vector2::new(a.x.max(b.x), a.y.max(b.y)),
+            )
+        });
+        if selection != self.selection {
+            self.selection = selection;
+            self.refresh_overlay();
+        }
+    }
+
+    /// Forget the selection without changing the cells in it.
+    pub fn clear_selection(&mut self) {
+        self.selection_anchor = None;
+        if self.selection.take().is_some() {
+            self.refresh_overlay();
+        }
+    }
+
+    /// The living cells within the selection.
+    pub fn selection_cells(&self) -> LivingList {
+        let Some((min, max)) = self.selection else {
+            return LivingList::default();
+        };
+        self.living_cells
+            .iter()
+            .filter(|c| c.x >= min.x && c.x <= max.x && c.y >= min.y && c.y <= max.y)
+            .copied()
+            .collect()
+    }
+
+    /// Copy the living cells in the selection to the clipboard, relative to
+    /// the selection's top-left corner.
+    pub fn copy_selection(&mut self) {
+        let Some((min, _)) = self.selection else {
+            return;
+        };
+        let cells = self.selection_cells().into_iter().map(|c| c - min).collect();
+        self.clipboard = Some(cells);
+    }
+
+    /// Copy the selection to the clipboard, then kill the cells in it.
+    pub fn cut_selection(&mut self) {
+        self.copy_selection();
+        self.delete_selection();
+    }
+
+    /// Whether anything has been copied to the clipboard.
+    pub fn has_clipboard(&self) -> bool {
+        self.clipboard.is_some()
+    }
+
+    /// Bring the cells on the clipboard to life with the selection's
+    /// top-left corner at `at`.
+    #[allow(dead_code)] // Part of the selection API, though pasting goes through the mouse
+    pub fn paste_clipboard(&mut self, at: Vector2<i32>) {
+        if let Some(cells) = self.clipboard.clone() {
+            self.stamp_pattern(cells, at);
+        }
+    }
+
+    /// Transform the clipboard, and the pattern being placed if there is one,
+    /// with a function such as `patterns::rotate_cw`.
+    pub fn transform_clipboard(&mut self, transform: patterns::Transform) {
+        if let Some(cells) = &mut self.clipboard {
+            *cells = transform(cells);
+        }
+        if let Some(buffer) = &mut self.paste_buffer {
+            *buffer = transform(buffer);
+            self.refresh_overlay();
+        }
+    }
+
+    /// Start placing the cells on the clipboard with the mouse.
+    pub fn begin_paste_clipboard(&mut self) {
+        if let Some(cells) = self.clipboard.clone() {
+            self.begin_paste(cells);
+        }
+    }
+
+    /// Recompute the overlay cells and pass them to the renderer. Only cells
+    /// that are visible are included.
+    fn refresh_overlay(&mut self) {
+        let (min, max) = self.visible_cell_bounds();
+        let visible =
+            |c: &Vector2<i32>| c.x >= min.x && c.x <= max.x && c.y >= min.y && c.y <= max.y;
+        let camera = self.camera();
+
+        let mut overlay: Vec<OverlayCell> = self
+            .bounds
+            .edge_cells(min, max)
+            .into_iter()
+            .map(|c| OverlayCell {
+                cell: to_cell(c, &camera),
+                tint: WORLD_EDGE_TINT,
+            })
+            .collect();
+        // Counting every frame would be too slow while playing
+        if self.show_neighbor_counts && !self.is_playing() {
+            overlay.extend(
+                neighbor_count_tints(&self.living_cells, &self.bounds)
+                    .into_iter()
+                    .filter(|(c, _)| visible(c))
+                    .map(|(c, tint)| OverlayCell {
+                        cell: to_cell(c, &camera),
+                        tint,
+                    }),
+            );
+        }
+        if let Some(buffer) = &self.paste_buffer
+            && let Some(at) = self.hovered_cell()
+        {
+            overlay.extend(
+                buffer
+                    .iter()
+                    .map(|c| *c + at)
+                    .filter(visible)
+                    .map(|c| OverlayCell {
+                        cell: to_cell(c, &camera),
+                        tint: PASTE_PREVIEW_TINT,
+                    }),
+            );
+        }
+        if let Some((corner_min, corner_max)) = self.selection {
+            overlay.extend(
+                bounds::outline_cells(corner_min, corner_max, min, max)
+                    .into_iter()
+                    .map(|c| OverlayCell {
+                        cell: to_cell(c, &camera),
+                        tint: SELECTION_TINT,
+                    }),
+            );
+        }
+        if self.show_centroid {
+            let len = self.centroid_trail.len();
+            overlay.extend(self.centroid_trail.iter().enumerate().map(|(i, c)| {
+                let [r, g, b, a] = CENTROID_TRAIL_TINT;
+                OverlayCell {
+                    cell: centroid_to_cell(*c, &camera),
+                    tint: [r, g, b, a * (i + 1) as f32 / len as f32],
+                }
+            }));
+            // There's nothing to mark on an empty board
+            if let Some(c) = patterns::centroid(&self.living_cells) {
+                overlay.push(OverlayCell {
+                    cell: centroid_to_cell(c, &camera),
+                    tint: CENTROID_MARKER_TINT,
+                });
+            }
+        }
+        self.changes.overlay = Some(overlay);
+    }
+
+    pub fn is_showing_neighbor_counts(&self) -> bool {
+        self.show_neighbor_counts
+    }
+
+    /// Show or hide the neighbor count overlay. It is only drawn while
+    /// paused.
+    pub fn set_showing_neighbor_counts(&mut self, show: bool) {
+        self.show_neighbor_counts = show;
+        self.refresh_overlay();
+    }
+
+    /// Refresh the overlays that depend on the board if it changed.
+    fn update_board_overlays(&mut self) {
+        if (self.show_neighbor_counts || self.show_centroid) && self.changes.cells.is_some() {
+            self.refresh_overlay();
+        }
+    }
+
+    pub fn is_showing_grid(&self) -> bool {
+        self.show_grid
+    }
+
+    /// Show or hide lines along the cell boundaries.
+    pub fn set_showing_grid(&mut self, show: bool) {
+        self.show_grid = show;
+        self.changes.grid_visible = Some(show);
+    }
+
+    pub fn is_showing_wrap_ghosts(&self) -> bool {
+        self.show_wrap_ghosts
+    }
+
+    /// Show or hide faint copies of the cells along the edges of a torus past
+    /// the opposite edges, where they are neighbors across the wrap.
+    pub fn set_showing_wrap_ghosts(&mut self, show: bool) {
+        self.show_wrap_ghosts = show;
+        self.refresh_wrap_ghosts();
+    }
+
+    /// Tell the renderer the size of the torus to draw wrap ghosts for, if
+    /// any.
+    fn refresh_wrap_ghosts(&mut self) {
+        self.changes.wrap_ghosts = Some(match self.bounds {
+            WorldBounds::Torus { width, height } if self.show_wrap_ghosts => {
+                Some(Vector2::new(width, height))
+            }
+            _ => None,
+        });
+        self.changes.cells = Some(self.get_cells());
+    }
+
+    pub fn is_showing_centroid(&self) -> bool {
+        self.show_centroid
+    }
+
+    /// Show or hide the marker at the living cells' center of mass and the
+    /// trail of where it was in recent generations.
+    pub fn set_showing_centroid(&mut self, show: bool) {
+        self.show_centroid = show;
+        self.centroid_trail.clear();
+        self.record_centroid();
+        self.refresh_overlay();
+    }
+
+    pub fn get_trail_length(&self) -> usize {
+        self.trail_length
+    }
+
+    /// Set how many generations' centers of mass the trail shows. 0 shows
+    /// only the marker.
+    pub fn set_trail_length(&mut self, length: usize) {
+        self.trail_length = length;
+        while self.centroid_trail.len() > length {
+            self.centroid_trail.pop_front();
+        }
+        self.refresh_overlay();
+    }
+
+    /// Add the current center of mass to the trail, dropping the oldest one if
+    /// it's full.
+    fn record_centroid(&mut self) {
+        if !self.show_centroid || self.trail_length == 0 {
+            return;
+        }
+        if let Some(c) = patterns::centroid(&self.living_cells) {
+            push_to_trail(&mut self.centroid_trail, c, self.trail_length);
+        }
+    }
+
+    /// The cell under the mouse cursor, or `None` if it isn't in the window.
+    pub fn hovered_cell(&self) -> Option<Vector2<i32>> {
+        let size = self.window.inner_size();
+        self.mouse_position
+            .map(|p| self.camera().screen_to_cell(p, size))
+    }
+
+    /// Compute which cells would be born and which would die on the next step
+    /// without advancing the simulation. Returns `(births, deaths)`.
+    pub fn preview_step(&self) -> (LivingList, LivingList) {
+        step_changes(&self.living_cells, &self.rules, &self.bounds)
+    }
+
+    /// The cells born and killed by the last step, if it was taken while
+    /// paused and the board hasn't been edited since.
+    pub fn last_step(&self) -> Option<&StepDiff> {
+        self.last_step.as_ref()
+    }
+
+    /// Start recording a GIF. Deterministic recordings step the simulation
+    /// themselves, so auto-play is stopped; real-time recordings capture
+    /// auto-play, so it is started.
+    pub fn start_recording(&mut self, settings: GifSettings) {
+        if settings.deterministic == self.is_playing() {
+            self.toggle_playing();
+        }
+        self.changes.start_recording = Some(settings);
+    }
+
+    /// Draw living cells with an image, such as the contents of a PNG file,
+    /// instead of the built-in texture.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn set_cell_texture(&mut self, bytes: Vec<u8>) {
+        self.changes.cell_texture = Some(bytes);
+    }
+
+    /// Save a PNG of the board as it's drawn on screen, without the GUI.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn save_screenshot(&mut self) {
+        self.changes.screenshot = true;
+    }
+
+    /// Replace the board with a large random one and time how long the
+    /// requested number of steps take. Auto-play is stopped so that only the
+    /// benchmark's steps are measured.
+    pub fn start_benchmark(&mut self, settings: BenchmarkSettings) {
+        if self.is_playing() {
+            self.toggle_playing();
+        }
+        self.clear();
+        let half = settings.size as i32 / 2;
+        let at = self.view_center_cell() - Vector2::new(half, half);
+        self.stamp_pattern(benchmark::random_board(settings.size, settings.density), at);
+        self.start_seed = Some(benchmark::BOARD_SEED);
+        self.benchmark = Some(Benchmark::new(settings.steps));
+    }
+
+    pub fn is_benchmarking(&self) -> bool {
+        self.benchmark.is_some()
+    }
+
+    pub fn benchmark_summary(&self) -> Option<&BenchmarkSummary> {
+        self.benchmark_summary.as_ref()
+    }
+
+    /// Stop auto-play and run the simulation to `generation` as fast as steps
+    /// can be computed. Edits that are still queued, such as loading a save,
+    /// are applied first. Nothing happens if the board is already there.
+    pub fn run_to_generation(&mut self, generation: u64) {
+        if self.is_playing() {
+            self.toggle_playing();
+        }
+        self.target_generation = Some(generation);
+    }
+
+    /// The generation the simulation is being run to, if any.
+    pub fn target_generation(&self) -> Option<u64> {
+        self.target_generation
+    }
+
+    /// Take the next step toward the target generation once the previous one
+    /// is done.
+    fn drive_target(&mut self) {
+        let Some(target) = self.target_generation else {
+            return;
+        };
+        if self.step_count >= target {
+            self.target_generation = None;
+        } else if !self.worker_busy() && self.input_queue.is_empty() {
+            self.step();
+        }
+    }
+
+    /// Take a step if the step key has been held long enough, then schedule
+    /// the next one an interval later.
+    fn drive_held_step(&mut self) {
+        let Some(due) = self.held_step_due else {
+            return;
+        };
+        let now = self.clock.now();
+        if now < due || self.worker_busy() {
+            return;
+        }
+        self.step();
+        self.held_step_due = Some(next_held_step(due, self.interval, now));
+    }
+
+    /// Request the benchmark's next step once the previous one is done.
+    fn drive_benchmark(&mut self) {
+        let busy = self.worker_busy();
+        if let Some(benchmark) = &mut self.benchmark
+            && benchmark.wants_step()
+            && !busy
+        {
+            benchmark.start_step(self.clock.now());
+            self.step();
+        }
     }
 
     /// Get a vector of all the cells that should be rendered
@@ -120,12 +939,16 @@ impl GameState {
         let res: Vec<Cell> = self
             .living_cells
             .iter()
-            .map(|i| to_cell(*i, self.grid_size))
+            .map(|i| to_cell(*i, &self.camera()))
             .collect();
         res
     }
 
     fn handle_scroll(&mut self, delta: MouseScrollDelta) {
+        if self.camera_locked {
+            return;
+        }
+
         #[cfg(not(target_arch = "wasm32"))]
         const PIXEL_MUL: f64 = 3.0;
 
@@ -138,21 +961,13 @@ impl GameState {
             * 0.000005
             * match delta {
                 MouseScrollDelta::LineDelta(_, n) => n as f64,
-                MouseScrollDelta::PixelDelta(PhysicalPosition { y, .. }) => y * PIXEL_MUL
+                MouseScrollDelta::PixelDelta(PhysicalPosition { y, .. }) => y * PIXEL_MUL,
             };
 
-        self.grid_size = (self.grid_size as f64 * (1.0 + change)).clamp(0.005, 1.0) as f32;
-        self.changes.grid_size = Some(self.grid_size);
+        self.set_grid_size((self.grid_size as f64 * (1.0 + change)) as f32);
 
         let center = if let Some(v) = self.mouse_position {
-            let aspect_ratio = size.width as f64 / size.height as f64;
-            let shift_amount = (size.width as f64 - size.height as f64) / 2.0;
-            let x_shifted = v.x - shift_amount;
-            let x_scaled = x_shifted * aspect_ratio;
-            Vector2::<f64>::scale(
-                Vector2::new(x_scaled, v.y),
-                Vector2::new((size.width as f64).recip(), (size.height as f64).recip()),
-            ) + self.pan_position
+            self.camera().screen_to_world(v, size)
         } else {
             Vector2::<f64>::new(0.0, 0.0)
         };
@@ -164,15 +979,63 @@ impl GameState {
         let extra_offset = center * change;
 
         // extra_offset is actually the inverse of the way pan_position works
-        self.pan_position += extra_offset;
-        self.changes.offset = Some(self.pan_position);
-        self.changes.cells = Some(self.get_cells());
+        self.set_pan_position(self.pan_position + extra_offset);
     }
 
     pub fn handle_window_event(&mut self, event: &WindowEvent) {
         let c_char = SmolStr::new_static("c");
+        let i_char = SmolStr::new_static("i");
+        let l_char = SmolStr::new_static("l");
+        let f_char = SmolStr::new_static("f");
+        let r_char = SmolStr::new_static("r");
+        let shift_r_char = SmolStr::new_static("R");
+        let h_char = SmolStr::new_static("h");
+        let v_char = SmolStr::new_static("v");
+
+        if matches!(
+            event,
+            WindowEvent::KeyboardInput { .. }
+                | WindowEvent::MouseInput { .. }
+                | WindowEvent::MouseWheel { .. }
+                | WindowEvent::CursorMoved { .. }
+                | WindowEvent::Touch(_)
+        ) {
+            self.register_input();
+        }
 
         match event {
+            // Copy, cut and paste the selection with Ctrl+C, Ctrl+X and
+            // Ctrl+V, or Cmd on macOS. These come first so that Ctrl+C
+            // doesn't clear the board.
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        physical_key:
+                            PhysicalKey::Code(code @ (KeyCode::KeyC | KeyCode::KeyX | KeyCode::KeyV)),
+                        repeat: false,
+                        state: ElementState::Pressed,
+                        ..
+                    },
+                ..
+            } if self.modifiers.control_key() || self.modifiers.super_key() => match code {
+                KeyCode::KeyC => self.copy_selection(),
+                KeyCode::KeyX => self.cut_selection(),
+                _ => self.begin_paste_clipboard(),
+            },
+
+            // Kill the selected cells with Delete or Backspace
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        logical_key: Key::Named(NamedKey::Delete | NamedKey::Backspace),
+                        state: ElementState::Pressed,
+                        ..
+                    },
+                ..
+            } => {
+                self.delete_selection();
+            }
+
             // Clear the screen when "c" pressed
             WindowEvent::KeyboardInput {
                 event:
@@ -187,32 +1050,156 @@ impl GameState {
                 self.clear();
             }
 
-            // Speed up
+            // Invert the visible part of the board when "i" pressed
             WindowEvent::KeyboardInput {
                 event:
                     KeyEvent {
-                        logical_key: Key::Named(NamedKey::ArrowUp),
+                        logical_key: Key::Character(keystr),
+                        repeat: false,
                         state: ElementState::Pressed,
                         ..
                     },
                 ..
-            } => self.interval = self.interval.div_f32(INTERVAL_P),
+            } if *keystr == i_char => {
+                self.invert_visible();
+            }
 
-            // Slow down
+            // Lock or unlock the camera when "l" pressed
             WindowEvent::KeyboardInput {
                 event:
                     KeyEvent {
-                        logical_key: Key::Named(NamedKey::ArrowDown),
+                        logical_key: Key::Character(keystr),
+                        repeat: false,
                         state: ElementState::Pressed,
                         ..
                     },
                 ..
-            } => self.interval = self.interval.mul_f32(INTERVAL_P),
-
-            // Forget the cursor position if it left the window
-            WindowEvent::CursorLeft { .. } => {
-                self.mouse_position = None;
-                //self.drag_state = DragState::NotDragging;
+            } if *keystr == l_char => {
+                self.set_camera_locked(!self.camera_locked);
+            }
+
+            // Fit the living cells in the view when "f" pressed
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        logical_key: Key::Character(keystr),
+                        repeat: false,
+                        state: ElementState::Pressed,
+                        ..
+                    },
+                ..
+            } if *keystr == f_char && !self.camera_locked => {
+                self.center_on_living();
+            }
+
+            // Turn or mirror the clipboard with "r", shift+"r", "h" and "v"
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        logical_key: Key::Character(keystr),
+                        repeat: false,
+                        state: ElementState::Pressed,
+                        ..
+                    },
+                ..
+            } if [&r_char, &shift_r_char, &h_char, &v_char].contains(&keystr) => {
+                let transform = if *keystr == r_char {
+                    patterns::rotate_cw
+                } else if *keystr == shift_r_char {
+                    patterns::rotate_ccw
+                } else if *keystr == h_char {
+                    patterns::flip_horizontal
+                } else {
+                    patterns::flip_vertical
+                };
+                self.transform_clipboard(transform);
+            }
+
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers.state();
+            }
+
+            // Undo with Ctrl+Z, or Cmd+Z on macOS, and redo with Ctrl+Y or
+            // Ctrl+Shift+Z. The physical key is used so that the shortcuts
+            // stay in place on every layout.
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        physical_key: PhysicalKey::Code(code @ (KeyCode::KeyZ | KeyCode::KeyY)),
+                        state: ElementState::Pressed,
+                        ..
+                    },
+                ..
+            } if self.modifiers.control_key() || self.modifiers.super_key() => {
+                if *code == KeyCode::KeyY || self.modifiers.shift_key() {
+                    self.redo();
+                } else {
+                    self.undo();
+                }
+            }
+
+            // Print diagnostics for bug reports with F3
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        logical_key: Key::Named(NamedKey::F3),
+                        repeat: false,
+                        state: ElementState::Pressed,
+                        ..
+                    },
+                ..
+            } => {
+                self.diagnostics().print();
+            }
+
+            // Speed up
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        logical_key: Key::Named(NamedKey::ArrowUp),
+                        state: ElementState::Pressed,
+                        ..
+                    },
+                ..
+            } => self.change_speed(true),
+
+            // Slow down
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        logical_key: Key::Named(NamedKey::ArrowDown),
+                        state: ElementState::Pressed,
+                        ..
+                    },
+                ..
+            } => self.change_speed(false),
+
+            // Flip through the saves
+            #[cfg(feature = "saving")]
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        logical_key: Key::Named(NamedKey::PageUp),
+                        state: ElementState::Pressed,
+                        ..
+                    },
+                ..
+            } => self.load_prev_save(),
+            #[cfg(feature = "saving")]
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        logical_key: Key::Named(NamedKey::PageDown),
+                        state: ElementState::Pressed,
+                        ..
+                    },
+                ..
+            } => self.load_next_save(),
+
+            // Forget the cursor position if it left the window
+            WindowEvent::CursorLeft { .. } => {
+                self.mouse_position = None;
+                //self.drag_state = DragState::NotDragging;
             }
 
             // Zooming with scroll
@@ -229,7 +1216,12 @@ impl GameState {
             // This block also handles panning
             WindowEvent::CursorMoved { position, .. } => {
                 self.mouse_position = Some([position.x, position.y].into());
-                if let DragState::Dragging { prev_pos } = self.drag_state {
+                if let DragState::Dragging {
+                    prev_pos,
+                    velocity,
+                    moved_at,
+                } = self.drag_state
+                {
                     let pos = self.mouse_position.unwrap();
                     let size = self.window.inner_size();
                     let w = size.width as f64;
@@ -242,9 +1234,38 @@ impl GameState {
                     let raw_diff = Vector2::<f64>::scale(norm_diff, Vector2::new(ratio, 1.0));
                     let diff = raw_diff; // self.grid_size as f64;
 
-                    self.pan_position -= diff;
-                    self.drag_state = DragState::Dragging { prev_pos: pos };
-                    self.changes.offset = Some(self.pan_position);
+                    // Smooth out the velocity, since cursor events are uneven
+                    let now = self.clock.now();
+                    let dt = now.saturating_duration_since(moved_at).as_secs_f64();
+                    let velocity = if dt > 0.0 {
+                        Vector2::<f64>::lerp(velocity, diff * -dt.recip(), 0.5)
+                    } else {
+                        velocity
+                    };
+
+                    self.drag_state = DragState::Dragging {
+                        prev_pos: pos,
+                        velocity,
+                        moved_at: now,
+                    };
+                    self.set_pan_position(self.pan_position - diff);
+                }
+                if self.paste_buffer.is_some() {
+                    self.refresh_overlay();
+                }
+                if let Some(anchor) = self.selection_anchor {
+                    let size = self.window.inner_size();
+                    let cell = self.camera().screen_to_cell(self.mouse_position.unwrap(), size);
+                    self.select(anchor, cell);
+                }
+                if let Some(last) = self.brush {
+                    let size = self.window.inner_size();
+                    let cell = self.camera().screen_to_cell(self.mouse_position.unwrap(), size);
+                    if cell != last {
+                        // Fill in the cells skipped over by fast movements
+                        self.paint_cells(line_cells(last, cell)[1..].to_vec(), false);
+                        self.brush = Some(cell);
+                    }
                 }
             }
 
@@ -254,8 +1275,15 @@ impl GameState {
                 state: ElementState::Pressed,
                 ..
             } => {
-                if let Some(p) = self.mouse_position {
-                    self.drag_state = DragState::Dragging { prev_pos: p };
+                if let Some(p) = self.mouse_position
+                    && !self.camera_locked
+                {
+                    self.glide = None;
+                    self.drag_state = DragState::Dragging {
+                        prev_pos: p,
+                        velocity: Vector2::new(0.0, 0.0),
+                        moved_at: self.clock.now(),
+                    };
                 }
             }
 
@@ -265,7 +1293,7 @@ impl GameState {
                 state: ElementState::Released,
                 ..
             } => {
-                self.drag_state = DragState::NotDragging;
+                self.release_drag();
             }
 
             // Toggle autoplay with space
@@ -281,17 +1309,89 @@ impl GameState {
                 self.toggle_playing();
             }
 
-            // Individual step with Tab
+            // Pan with WASD while held. The physical keys are used so that
+            // they stay in place on every layout.
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        physical_key: PhysicalKey::Code(code),
+                        state,
+                        ..
+                    },
+                ..
+            } if pan_key_direction(*code).is_some() => match state {
+                ElementState::Pressed if !self.camera_locked => {
+                    self.glide = None;
+                    self.pan_keys.insert(*code);
+                    self.key_pan_at.get_or_insert(self.clock.now());
+                }
+                ElementState::Pressed => {}
+                ElementState::Released => {
+                    self.pan_keys.remove(code);
+                    if self.pan_keys.is_empty() {
+                        self.key_pan_at = None;
+                    }
+                }
+            },
+
+            // Individual step with Tab, and more while it's held. The
+            // system's key repeat is ignored in favor of the hold timing.
             WindowEvent::KeyboardInput {
                 event:
                     KeyEvent {
                         logical_key: Key::Named(NamedKey::Tab),
                         state: ElementState::Pressed,
+                        repeat: false,
                         ..
                     },
                 ..
             } => {
                 self.step();
+                self.held_step_due = Some(self.clock.now() + HOLD_STEP_DELAY);
+            }
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        logical_key: Key::Named(NamedKey::Tab),
+                        state: ElementState::Released,
+                        ..
+                    },
+                ..
+            } => {
+                self.held_step_due = None;
+            }
+            // Key releases won't arrive if focus moves elsewhere first
+            WindowEvent::Focused(false) => {
+                self.held_step_due = None;
+                self.stop_key_pan();
+                self.brush = None;
+                self.selection_anchor = None;
+            }
+
+            // Stop painting or selecting
+            WindowEvent::MouseInput {
+                state: ElementState::Released,
+                button: MouseButton::Left,
+                ..
+            } => {
+                self.brush = None;
+                self.selection_anchor = None;
+            }
+
+            // Start selecting a rectangle of cells by dragging with shift
+            // held
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+                ..
+            } if let Some(mouse_position) = self.mouse_position
+                && self.modifiers.shift_key()
+                && self.paste_buffer.is_none() =>
+            {
+                let size = self.window.inner_size();
+                let cell = self.camera().screen_to_cell(mouse_position, size);
+                self.selection_anchor = Some(cell);
+                self.select(cell, cell);
             }
 
             // Cell state toggling with LMB
@@ -299,219 +1399,1505 @@ impl GameState {
                 state: ElementState::Pressed,
                 button: MouseButton::Left,
                 ..
-            } if let Some(mouse_position) = self.mouse_position => {
-                self.handle_left(mouse_position);
+            } if let Some(mouse_position) = self.mouse_position
+                && (!self.camera_locked || self.edit_while_locked) =>
+            {
+                if self.paste_buffer.is_some() {
+                    self.commit_paste(mouse_position);
+                } else if self.paint_mode {
+                    let size = self.window.inner_size();
+                    let cell = self.camera().screen_to_cell(mouse_position, size);
+                    self.brush = Some(cell);
+                    self.paint_cells(vec![cell], true);
+                } else {
+                    self.handle_left(mouse_position);
+                }
             }
             _ => (),
         };
     }
 
-    /// Clear the screen
-    fn clear_action(&mut self) {
-        self.living_cells.clear();
-        self.step_count = 0;
-        self.living_count_history = vec![0];
-        self.living_cell_count = 0;
+    /// Replace the living cells with the next generation, update everything
+    /// that tracks the simulation's progress, and pass the cells on to be
+    /// drawn.
+    fn finish_step(&mut self, next: LivingList) {
+        self.advance(next);
+        self.changes.cells = Some(self.get_cells());
+    }
 
-        self.changes.cells = Some(Vec::new());
-        self.toggle_record.clear();
+    /// Compute `n` generations on this thread, stopping early if the
+    /// population cap is reached. The cells are only passed on to be drawn
+    /// once, at the end.
+    fn step_n_action(&mut self, n: u64) {
+        let cap = self.population_cap;
+        step_n(self, n, cap);
     }
 
-    /// Resolve the input queue (`self.input_queue`)
-    fn resolve_queue(&mut self) {
-        while let Some(i) = self.input_queue.pop_front() {
-            match i {
-                QueueAction::Clear => {
-                    self.clear_action();
-                }
-                QueueAction::Toggle(cell) => {
-                    self.left_action(cell);
-                }
-                #[cfg(feature = "saving")]
-                QueueAction::Load(save) => {
-                    self.load_action(save);
+    /// How many generations have actually been computed per second recently,
+    /// which is below the auto-play speed when steps take too long. It drops
+    /// toward 0 once stepping stops.
+    pub fn steps_per_second(&self) -> f64 {
+        let now = self.clock.now();
+        let mut recent = self
+            .step_times
+            .iter()
+            .filter(|t| now.saturating_duration_since(**t) <= STEP_RATE_WINDOW);
+        let Some(first) = recent.next() else {
+            return 0.0;
+        };
+        // Measured from the first step, so it's the steps after it that count
+        let steps = recent.count();
+        let elapsed = now.saturating_duration_since(*first).as_secs_f64();
+        if steps == 0 || elapsed == 0.0 {
+            return 0.0;
+        }
+        steps as f64 / elapsed
+    }
+
+    /// Replace the living cells with the next generation and update everything
+    /// that tracks the simulation's progress, without drawing them.
+    fn advance(&mut self, next: LivingList) {
+        // Only manual steps are inspected, so auto-play doesn't pay for this
+        self.last_step = (!self.is_playing())
+            .then(|| StepDiff::between(&self.living_cells, &next, self.step_count + 1));
+        let previous_count = record_generation(
+            &mut self.living_cells,
+            &mut self.living_cell_count,
+            &mut self.living_count_history,
+            &mut self.step_count,
+            next,
+        );
+        self.record_centroid();
+        if self.step_times.len() == STEP_RATE_SAMPLES {
+            self.step_times.pop_front();
+        }
+        self.step_times.push_back(self.clock.now());
+
+        if self.living_cell_count == 0 && previous_count > 0 {
+            self.changes.events.push(GameEvent::BoardEmptied);
+        }
+        if let Some(cap) = self.population_cap
+            && self.living_cell_count >= cap
+            && previous_count < cap
+        {
+            // Stop before the population grows any further
+            if self.is_playing() {
+                self.toggle_playing();
+            }
+            self.changes.events.push(GameEvent::PopulationCapReached);
+        }
+        if let Some(detector) = &mut self.spaceships {
+            detector.record(&self.living_cells);
+        }
+        if let Some(stabilized) = self.stability.record(&self.living_cells) {
+            let period = stabilized.period;
+            self.changes.events.push(GameEvent::Stabilized { period });
+            self.snapshot_stable(stabilized);
+        }
+        if self.pause_at_phase_zero && self.is_playing() && self.stability.is_at_phase_zero() {
+            self.toggle_playing();
+        }
+
+        let now = self.clock.now();
+        if let Some(benchmark) = &mut self.benchmark {
+            benchmark.finish_step(now);
+            if let Some(summary) = benchmark.summary(self.living_cell_count) {
+                log::info!(
+                    "Benchmark: {} steps, min {:?}, avg {:?}, max {:?}",
+                    summary.steps,
+                    summary.min,
+                    summary.avg,
+                    summary.max
+                );
+                self.benchmark_summary = Some(summary);
+                self.benchmark = None;
+            }
+        }
+
+        if let Some(follow) = &mut self.follow {
+            match patterns::centroid(&self.living_cells) {
+                // Put the centroid in the middle of the window
+                Some(c) => {
+                    let world = (c + Vector2::new(0.5, 0.5)) * self.grid_size as f64;
+                    follow.target = world - Vector2::new(0.5, 0.5);
                 }
+                // There's nothing left to follow
+                None => self.follow = None,
             }
         }
     }
 
-    /// Handle a left click by toggling the particular cell. This should not be
-    /// called if the click was on the GUI.
-    fn left_action(&mut self, cell_pos: Vector2<i32>) {
-        if let Some(i) = self.living_cells.get(&cell_pos).cloned() {
-            self.living_cells.remove(&i);
+    /// Count the auto-play steps that have come due since the last update.
+    /// Unless catching up, steps that can't be taken right away are dropped.
+    fn schedule_steps(&mut self) {
+        let due = self.loop_state.update(&self.interval, self.clock.now());
+        self.pending_steps = if !self.loop_state.is_playing() {
+            0
+        } else if self.ticks.per_step.is_some() {
+            // Ticks schedule the steps instead
+            self.pending_steps
         } else {
-            self.living_cells.insert(cell_pos);
+            queue_due_steps(self.pending_steps, due, self.catch_up)
+        };
+    }
+
+    /// Take one of the pending auto-play steps if a step can be started now.
+    fn take_due_step(&mut self) -> bool {
+        if self.pending_steps == 0 || self.worker_busy() {
+            return false;
         }
+        self.pending_steps -= 1;
+        true
+    }
 
-        let cells = self.get_cells();
-        self.toggle_record.push(self.step_count);
-        self.changes.cells = Some(cells);
+    pub fn get_ticks_per_step(&self) -> Option<u32> {
+        self.ticks.per_step
     }
 
-    #[cfg(feature = "saving")]
-    fn load_action(&mut self, save: SaveGame) {
-        self.clear_action();
-        self.living_cells = save.living_cells();
-        self.pan_position = save.pan_position();
-        self.grid_size = save.grid_size();
+    /// Set how many ticks auto-play takes per generation, or `None` to pace it
+    /// by the clock. Counting ticks instead of time makes runs reproducible
+    /// regardless of how fast the machine is.
+    pub fn set_ticks_per_step(&mut self, ticks: Option<u32>) {
+        self.ticks.set_per_step(ticks);
+        self.pending_steps = 0;
+    }
 
-        self.changes.cells = Some(self.get_cells());
-        self.changes.grid_size = Some(self.grid_size);
-        self.changes.offset = Some(self.pan_position);
+    /// Advance deterministic mode by one tick, such as once per frame. Every
+    /// `ticks_per_step` ticks during auto-play, one generation comes due, and
+    /// none are ever skipped.
+    pub fn tick(&mut self) {
+        if self.ticks.tick(self.is_playing()) {
+            self.pending_steps += 1;
+        }
     }
-}
 
-#[cfg(feature = "native_threads")]
-impl GameState {
-    pub fn new(window: Arc<Window>, grid_size: f32) -> Self {
-        use StepThreadNotification as STN;
-        let (tx, rx) = mpsc::channel();
-        let condvar = Condvar::new();
-        let notification = Mutex::new(StepThreadNotification::Waiting);
-        let shared_thread_data = Arc::new(SharedThreadData {
-            condvar,
-            notification,
-            computing: AtomicBool::new(false),
-        });
-        let join_handle = {
-            let thread_data = Arc::clone(&shared_thread_data);
-            std::thread::spawn(move || loop {
-                let cvar = &thread_data.condvar;
-                let lock = &thread_data.notification;
-                let data_guard = lock.lock().unwrap();
-                let mut data_guard = cvar.wait(data_guard).unwrap();
-                match &*data_guard {
-                    STN::Exit => break,
-                    STN::Waiting => (),
-                    STN::Compute(data) => {
-                        thread_data
-                            .computing
-                            .store(true, sync::atomic::Ordering::Relaxed);
-                        tx.send(compute_step(data)).unwrap();
-                        *data_guard = STN::Waiting;
-                    }
-                }
-            })
-        };
+    pub fn is_catching_up(&self) -> bool {
+        self.catch_up
+    }
 
-        let local_thread_data = LocalThreadData { join_handle, rx };
+    /// Set whether auto-play steps that come due while the previous step is
+    /// still being computed are taken later rather than skipped. Catching up
+    /// keeps the number of generations in line with the speed setting, at the
+    /// cost of uneven pacing while it catches up.
+    pub fn set_catching_up(&mut self, catch_up: bool) {
+        self.catch_up = catch_up;
+    }
 
-        let thread_data = ThreadData {
-            local: local_thread_data,
-            shared: shared_thread_data,
-        };
+    pub fn get_population_cap(&self) -> Option<usize> {
+        self.population_cap
+    }
 
-        #[cfg(feature = "saving")]
-        let save_file = SaveFile::new("./save.json".into()).unwrap();
+    /// Set the population at which auto-play is stopped, or `None` to let it
+    /// grow without limit.
+    pub fn set_population_cap(&mut self, cap: Option<usize>) {
+        self.population_cap = cap;
+    }
 
-        Self {
-            pan_position: [0.0, 0.0].into(),
-            living_cells: FxHashSet::default(),
-            loop_state: LoopState::new(),
-            interval: DEFAULT_INTERVAL,
-            window,
-            mouse_position: None,
-            grid_size,
-            drag_state: DragState::NotDragging,
-            thread_data,
-            input_queue: VecDeque::new(),
-            living_cell_count: 0,
-            step_count: 0,
-            living_count_history: vec![0],
-            changes: StateChanges::default(),
-            toggle_record: Vec::new(),
-            #[cfg(feature = "saving")]
-            save_file: Some(save_file),
-            #[cfg(target_arch = "wasm32")]
-            scroll_mode: Default::default(),
-        }
+    pub fn get_idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout
     }
 
-    #[cfg(feature = "saving")]
-    pub fn load_save(&mut self, save: &SaveGame) {
-        if self
-            .thread_data
-            .shared
-            .computing
-            .load(atomic::Ordering::Relaxed)
-        {
-            self.input_queue.push_back(QueueAction::Load(save.clone()));
-        } else {
-            self.load_action(save.clone());
-        }
+    /// Set how long auto-play may run without any input before it is paused,
+    /// or `None` to let it run forever.
+    pub fn set_idle_timeout(&mut self, timeout: Option<Duration>) {
+        self.idle_timeout = timeout;
+        self.register_input();
     }
 
-    pub fn step(&mut self) {
-        if self
-            .thread_data
-            .shared
-            .computing
-            .load(atomic::Ordering::Relaxed)
-        {
-            return;
+    /// Whether auto-play was paused for lack of input, and nothing has
+    /// happened since.
+    pub fn is_idle_paused(&self) -> bool {
+        self.idle_paused
+    }
+
+    pub fn get_power_save_after(&self) -> Option<Duration> {
+        self.power_save_after
+    }
+
+    /// Set how long the board must sit still without input before power
+    /// saving starts, or `None` to never save power.
+    pub fn set_power_save_after(&mut self, after: Option<Duration>) {
+        self.power_save_after = after;
+    }
+
+    /// Whether steps are computed on the main thread instead of a worker
+    /// thread, which makes the interface stall while they're computed.
+    pub fn is_worker_degraded(&self) -> bool {
+        WORKER_DEGRADED
+    }
+
+    /// Whether nothing will change until the player does something, because
+    /// auto-play is stopped, the camera is still, and there has been no input
+    /// for the power save delay. While this is true the window only needs to
+    /// redraw in response to events.
+    pub fn is_power_saving(&self) -> bool {
+        let activity = Activity {
+            playing: self.is_playing(),
+            gliding: self.glide.is_some(),
+            key_panning: self.key_pan_at.is_some(),
+            worker_busy: self.worker_busy(),
+            benchmarking: self.benchmark.is_some(),
+            input_queued: !self.input_queue.is_empty(),
+        };
+        can_power_save(activity, self.power_save_after, self.clock.since(self.last_input))
+    }
+
+    /// The population of every generation since the board was last cleared or
+    /// loaded, with where the run started.
+    pub fn population_series(&self) -> PopulationSeries {
+        PopulationSeries {
+            start_pattern: self.start_pattern.clone(),
+            seed: self.start_seed,
+            ..PopulationSeries::new(
+                &self.living_count_history,
+                self.step_count,
+                self.rules.to_string(),
+            )
         }
-        let mut noti_lock = self.thread_data.shared.notification.lock().unwrap();
-        *noti_lock = StepThreadNotification::Compute(self.living_cells.clone());
-        self.thread_data.shared.condvar.notify_all();
     }
 
-    pub fn clear(&mut self) {
-        if self
-            .thread_data
-            .shared
-            .computing
-            .load(atomic::Ordering::Relaxed)
+    /// The population history as CSV, with a `generation,population,was_edited`
+    /// row for every generation since the board was last cleared or loaded.
+    /// `was_edited` marks the generations in which cells were changed by hand.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn export_history_csv(&self) -> String {
+        let edited: FxHashSet<u64> = self.toggle_record.iter().map(|t| t.step).collect();
+        let mut out = String::from("generation,population,was_edited\n");
+        for GenerationPopulation {
+            generation,
+            population,
+        } in self.population_series().generations
         {
-            self.input_queue.push_back(QueueAction::Clear);
-        } else {
-            self.clear_action();
+            let was_edited = edited.contains(&generation);
+            out.push_str(&format!("{generation},{population},{was_edited}\n"));
         }
+        out
     }
 
-    fn handle_left(&mut self, mouse_position: Vector2<f64>) {
+    /// Forget where the run started, for when the board is cleared.
+    fn forget_start(&mut self) {
+        self.start_pattern = None;
+        self.start_seed = None;
+    }
+
+    /// Collect a summary of the current state for bug reports.
+    pub fn diagnostics(&self) -> Diagnostics {
+        Diagnostics {
+            population: self.living_cells.len(),
+            generation: self.step_count,
+            bounding_box: patterns::bounding_box(&self.living_cells),
+            grid_size: self.grid_size,
+            pan_position: self.pan_position,
+            playing: self.is_playing(),
+            worker_busy: self.worker_busy(),
+            worker_degraded: self.is_worker_degraded(),
+            compute_backend: self.compute_backend,
+            rules: self.rules,
+            bounds: self.bounds,
+        }
+    }
+
+    /// Restart the idle timeout.
+    fn register_input(&mut self) {
+        self.last_input = self.clock.now();
+        self.idle_paused = false;
+    }
+
+    /// Pause auto-play if it has gone on for longer than the idle timeout
+    /// without input.
+    fn check_idle(&mut self) {
+        if self.is_playing() && idle_expired(self.idle_timeout, self.clock.since(self.last_input)) {
+            self.toggle_playing();
+            self.idle_paused = true;
+        }
+    }
+
+    pub fn is_detecting_spaceships(&self) -> bool {
+        self.spaceships.is_some()
+    }
+
+    /// Start or stop looking for spaceships each generation. Detection takes
+    /// a few generations to find anything after it starts.
+    pub fn set_detecting_spaceships(&mut self, detect: bool) {
+        self.spaceships = detect.then(SpaceshipDetector::default);
+    }
+
+    /// The spaceships found in the latest generation, if detecting them.
+    pub fn spaceships(&self) -> &[Spaceship] {
+        self.spaceships.as_ref().map_or(&[], |d| d.spaceships())
+    }
+
+    /// Forget the recent generations, for when the board is changed by
+    /// something other than a step.
+    fn forget_history(&mut self) {
+        self.stability.reset();
+        self.last_step = None;
+        if let Some(detector) = &mut self.spaceships {
+            detector.reset();
+        }
+    }
+
+    /// The period of the cycle the board has settled into, if it has.
+    /// Still lifes have a period of 1.
+    pub fn stable_period(&self) -> Option<u32> {
+        self.stability.period()
+    }
+
+    pub fn is_pausing_at_phase_zero(&self) -> bool {
+        self.pause_at_phase_zero
+    }
+
+    /// Set whether auto-play stops when the board is in a cycle and returns to
+    /// the first state of it that was seen, so that it always stops on the
+    /// same phase of an oscillator.
+    pub fn set_pausing_at_phase_zero(&mut self, pause: bool) {
+        self.pause_at_phase_zero = pause;
+    }
+
+    /// Load the save at `index` in the save file, if there is one. The rules
+    /// and world bounds it was saved with are only used if `adopt_settings` is
+    /// set.
+    #[cfg(feature = "saving")]
+    pub fn load_save_index(&mut self, index: usize, adopt_settings: bool) {
+        let Some(save) = self
+            .save_file
+            .as_ref()
+            .and_then(|f| f.saves_iter().nth(index))
+        else {
+            return;
+        };
+        self.current_save = Some(index);
+        self.load_save(&save, adopt_settings);
+        self.start_pattern = Some(save.name);
+        self.start_seed = None;
+    }
+
+    /// Add the cells of the save at `index` to the board, keeping the ones
+    /// already there and the view. If `adopt_settings` is set, the save's
+    /// rules and world bounds are used from then on.
+    #[cfg(feature = "saving")]
+    pub fn merge_save_index(&mut self, index: usize, adopt_settings: bool) {
+        let Some(save) = self
+            .save_file
+            .as_ref()
+            .and_then(|f| f.saves_iter().nth(index))
+        else {
+            return;
+        };
+        let simulation = save.simulation();
+        self.current_save = Some(index);
+        if adopt_settings {
+            self.set_rules(simulation.rules);
+            self.set_world_bounds(simulation.bounds);
+        }
+        self.stamp_pattern(simulation.cells(), Vector2::new(0, 0));
+    }
+
+    /// Load the save after the one that was last loaded, or the first one if
+    /// none has been loaded, along with its rules and world bounds.
+    #[cfg(feature = "saving")]
+    pub fn load_next_save(&mut self) {
+        let count = self.save_file.as_ref().map_or(0, |f| f.save_count());
+        if let Some(i) = adjacent_save(self.current_save, count, true, self.wrap_saves) {
+            self.load_save_index(i, true);
+        }
+    }
+
+    /// Load the save before the one that was last loaded, or the first one if
+    /// none has been loaded, along with its rules and world bounds.
+    #[cfg(feature = "saving")]
+    pub fn load_prev_save(&mut self) {
+        let count = self.save_file.as_ref().map_or(0, |f| f.save_count());
+        if let Some(i) = adjacent_save(self.current_save, count, false, self.wrap_saves) {
+            self.load_save_index(i, true);
+        }
+    }
+
+    #[cfg(feature = "saving")]
+    pub fn is_wrapping_saves(&self) -> bool {
+        self.wrap_saves
+    }
+
+    /// Set whether flipping past the last save goes back to the first, and
+    /// the other way around.
+    #[cfg(feature = "saving")]
+    pub fn set_wrapping_saves(&mut self, wrap: bool) {
+        self.wrap_saves = wrap;
+    }
+
+    #[cfg(feature = "saving")]
+    pub fn is_snapshotting_on_stable(&self) -> bool {
+        self.snapshot_on_stable
+    }
+
+    /// Set whether the board is saved automatically whenever it settles into
+    /// a still life or oscillator.
+    #[cfg(feature = "saving")]
+    pub fn set_snapshotting_on_stable(&mut self, snapshot: bool) {
+        self.snapshot_on_stable = snapshot;
+    }
+
+    /// Save the board after it has become stable, if enabled and it isn't the
+    /// same cycle as the last automatic save.
+    #[cfg(feature = "saving")]
+    fn snapshot_stable(&mut self, stabilized: stability::Stabilized) {
+        if !self.snapshot_on_stable
+            || self.living_cells.is_empty()
+            || self.last_snapshot == Some(stabilized.cycle_hash)
+        {
+            return;
+        }
+        let name = stable_save_name(stabilized.period, self.step_count);
+        let save = SaveGame::new(self, name);
+        let Some(save_file) = self.save_file.as_mut() else {
+            return;
+        };
+        if save_file.is_full() {
+            log::warn!("Not saving stable board because there are too many saves");
+            return;
+        }
+        save_file.add_save(save);
+        self.last_snapshot = Some(stabilized.cycle_hash);
+    }
+
+    /// Boards can't be saved without the saving feature.
+    #[cfg(not(feature = "saving"))]
+    fn snapshot_stable(&mut self, _stabilized: stability::Stabilized) {}
+
+    pub fn is_following(&self) -> bool {
+        self.follow.is_some()
+    }
+
+    /// Start or stop moving the camera to follow the living cells' centroid
+    /// as they move.
+    pub fn set_following(&mut self, following: bool) {
+        self.follow = following.then(|| FollowState {
+            target: self.pan_position,
+            last_update: self.clock.now(),
+        });
+    }
+
+    pub fn has_pan_inertia(&self) -> bool {
+        self.pan_inertia
+    }
+
+    /// Set whether the view keeps gliding after being flicked with the mouse.
+    pub fn set_pan_inertia(&mut self, inertia: bool) {
+        self.pan_inertia = inertia;
+        if !inertia {
+            self.glide = None;
+        }
+    }
+
+    pub fn compute_backend(&self) -> ComputeBackend {
+        self.compute_backend
+    }
+
+    /// Whether the graphics device can step the simulation, so that
+    /// `ComputeBackend::GpuCompute` can be chosen.
+    pub fn supports_gpu_compute(&self) -> bool {
+        self.gpu_stepper.is_some()
+    }
+
+    /// Choose what steps the simulation. GPU compute is ignored where it isn't
+    /// supported, so the CPU keeps stepping.
+    pub fn set_compute_backend(&mut self, backend: ComputeBackend) {
+        if backend == ComputeBackend::GpuCompute && !self.supports_gpu_compute() {
+            log::warn!("GPU compute isn't supported here, so steps stay on the CPU");
+            return;
+        }
+        self.compute_backend = backend;
+    }
+
+    /// Replace the GPU stepper, such as after getting a new graphics device.
+    /// Without one, stepping falls back to the CPU.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn set_gpu_stepper(&mut self, stepper: Option<GpuStepper>) {
+        self.gpu_stepper = stepper.map(Arc::new);
+        if self.gpu_stepper.is_none() {
+            self.compute_backend = ComputeBackend::CpuWorker;
+        }
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    pub fn world_bounds(&self) -> WorldBounds {
+        self.bounds
+    }
+
+    /// The GPU stepper to step with, if GPU compute is chosen.
+    fn active_gpu(&self) -> Option<&Arc<GpuStepper>> {
+        self.gpu_stepper
+            .as_ref()
+            .filter(|_| self.compute_backend == ComputeBackend::GpuCompute)
+    }
+
+    pub fn is_camera_locked(&self) -> bool {
+        self.camera_locked
+    }
+
+    /// Set whether scrolling and dragging are ignored. Locking the camera
+    /// stops any pan or glide in progress.
+    pub fn set_camera_locked(&mut self, locked: bool) {
+        self.camera_locked = locked;
+        if locked {
+            self.drag_state = DragState::NotDragging;
+            self.glide = None;
+            self.stop_key_pan();
+        }
+    }
+
+    pub fn can_edit_while_locked(&self) -> bool {
+        self.edit_while_locked
+    }
+
+    /// Set whether clicking still toggles cells while the camera is locked.
+    pub fn set_edit_while_locked(&mut self, edit: bool) {
+        self.edit_while_locked = edit;
+    }
+
+    pub fn is_paint_mode(&self) -> bool {
+        self.paint_mode
+    }
+
+    /// Set whether holding the left button and dragging paints living cells.
+    /// Otherwise, each click toggles one cell.
+    pub fn set_paint_mode(&mut self, paint: bool) {
+        self.paint_mode = paint;
+        self.brush = None;
+    }
+
+    /// Stop panning, and start gliding if the cursor was still moving.
+    fn release_drag(&mut self) {
+        /// How long the cursor can rest before release without the view
+        /// gliding afterwards
+        const FLICK_WINDOW: Duration = Duration::from_millis(50);
+
+        let DragState::Dragging {
+            velocity, moved_at, ..
+        } = std::mem::replace(&mut self.drag_state, DragState::NotDragging)
+        else {
+            return;
+        };
+        let now = self.clock.now();
+        if self.pan_inertia && now.saturating_duration_since(moved_at) < FLICK_WINDOW {
+            self.glide = Some(Glide {
+                velocity,
+                last_update: now,
+            });
+        }
+    }
+
+    /// Move the view along with its glide, slowing it down.
+    fn update_glide(&mut self) {
+        let now = self.clock.now();
+        let Some(glide) = &mut self.glide else {
+            return;
+        };
+        let moved = glide.advance(now);
+        if glide.has_stopped() {
+            self.glide = None;
+        }
+        self.set_pan_position(self.pan_position + moved);
+    }
+
+    /// Pan the view in the direction of the held movement keys, by the time
+    /// since they last moved it.
+    fn update_key_pan(&mut self) {
+        let Some(last) = self.key_pan_at else {
+            return;
+        };
+        let now = self.clock.now();
+        self.key_pan_at = Some(now);
+        let direction = self
+            .pan_keys
+            .iter()
+            .filter_map(|key| pan_key_direction(*key))
+            .fold(Vector2::new(0.0, 0.0), |sum, d| sum + d);
+        // Opposite keys cancel out
+        if direction == Vector2::new(0.0, 0.0) {
+            return;
+        }
+        let dt = now.saturating_duration_since(last).as_secs_f64();
+        let distance = KEY_PAN_SPEED * self.grid_size as f64 * dt;
+        self.set_pan_position(self.pan_position + direction.normalized() * distance);
+    }
+
+    /// Forget the held movement keys.
+    fn stop_key_pan(&mut self) {
+        self.pan_keys.clear();
+        self.key_pan_at = None;
+    }
+
+    /// Ease the camera towards the followed pattern.
+    fn update_follow(&mut self) {
+        /// How quickly the camera catches up, in inverse seconds
+        const FOLLOW_RATE: f64 = 4.0;
+
+        let now = self.clock.now();
+        let Some(follow) = &mut self.follow else {
+            return;
+        };
+        let dt = now.saturating_duration_since(follow.last_update).as_secs_f64();
+        follow.last_update = now;
+        if self.pan_position == follow.target {
+            return;
+        }
+
+        let t = 1.0 - (-dt * FOLLOW_RATE).exp();
+        let target = follow.target;
+        self.set_pan_position(Vector2::<f64>::lerp(self.pan_position, target, t));
+    }
+
+    /// Clear the screen, which can be undone
+    fn clear_action(&mut self) {
+        if !self.living_cells.is_empty() || self.step_count > 0 {
+            self.remember_board();
+        }
+        self.reset_board();
+    }
+
+    /// Clear the screen and everything tracking the simulation's progress.
+    fn reset_board(&mut self) {
+        self.living_cells.clear();
+        self.forget_history();
+        self.follow = None;
+        self.step_count = 0;
+        self.living_count_history = vec![0];
+        self.living_cell_count = 0;
+
+        self.changes.cells = Some(Vec::new());
+        self.toggle_record.clear();
+        self.centroid_trail.clear();
+    }
+
+    /// Resolve the input queue (`self.input_queue`)
+    fn resolve_queue(&mut self) {
+        while let Some(i) = self.input_queue.pop_front() {
+            match i {
+                QueueAction::Toggle(cell) => {
+                    self.left_action(cell);
+                }
+                QueueAction::Set(cell, alive) => {
+                    self.set_action(cell, alive);
+                }
+                QueueAction::Paint(cells, new_stroke) => {
+                    self.paint_action(cells, new_stroke);
+                }
+                QueueAction::Stamp(cells, at) => {
+                    self.stamp_action(cells, at);
+                }
+                QueueAction::UndoToggle => {
+                    self.undo_toggle_action();
+                }
+                QueueAction::Undo => {
+                    self.undo_action();
+                }
+                QueueAction::Redo => {
+                    self.redo_action();
+                }
+                QueueAction::Erase(min, max) => {
+                    self.erase_action(min, max);
+                }
+                QueueAction::Invert(min, max) => {
+                    self.invert_action(min, max);
+                }
+                QueueAction::Scale(factor) => {
+                    self.scale_action(factor);
+                }
+                QueueAction::SetRules(rules) => {
+                    self.set_rules_action(rules);
+                }
+                QueueAction::SetBounds(bounds) => {
+                    self.set_bounds_action(bounds);
+                }
+            }
+        }
+    }
+
+    /// Handle a left click by toggling the cell under the mouse. This should
+    /// not be called if the click was on the GUI.
+    fn handle_left(&mut self, mouse_position: Vector2<f64>) {
         let size = self.window.inner_size();
-        let cell_pos = find_cell_num(size, mouse_position, self.pan_position, self.grid_size);
-        if self
-            .thread_data
-            .shared
-            .computing
-            .load(atomic::Ordering::Relaxed)
+        let cell_pos = self.camera().screen_to_cell(mouse_position, size);
+        self.toggle_cell(cell_pos);
+    }
+
+    /// Toggle a particular cell.
+    fn left_action(&mut self, cell_pos: Vector2<i32>) {
+        let alive = self.get_cell(cell_pos);
+        self.set_action(cell_pos, !alive);
+    }
+
+    /// Bring cells to life along a brush stroke. The board is remembered for
+    /// undoing once per stroke, when the stroke first changes it.
+    fn paint_action(&mut self, cells: Vec<Vector2<i32>>, new_stroke: bool) {
+        if new_stroke {
+            self.stroke_remembered = false;
+        }
+        let mut changed = false;
+        for cell in cells {
+            if !self.bounds.contains(cell) || self.living_cells.contains(&cell) {
+                continue;
+            }
+            if !self.stroke_remembered {
+                self.remember_board();
+                self.stroke_remembered = true;
+            }
+            self.living_cells.insert(cell);
+            self.toggle_record.push(Toggle {
+                step: self.step_count,
+                cell,
+                became_alive: true,
+            });
+            changed = true;
+        }
+        if !changed {
+            return;
+        }
+        self.forget_history();
+        self.living_cell_count = self.living_cells.len();
+        self.changes.cells = Some(self.get_cells());
+    }
+
+    /// Make a particular cell alive or dead.
+    fn set_action(&mut self, cell_pos: Vector2<i32>, alive: bool) {
+        if !self.bounds.contains(cell_pos) {
+            return;
+        }
+        if self.get_cell(cell_pos) != alive {
+            self.remember_board();
+        }
+        self.forget_history();
+        let changed = if alive {
+            self.living_cells.insert(cell_pos)
+        } else {
+            self.living_cells.remove(&cell_pos)
+        };
+        self.living_cell_count = self.living_cells.len();
+
+        let cells = self.get_cells();
+        if changed {
+            self.toggle_record.push(Toggle {
+                step: self.step_count,
+                cell: cell_pos,
+                became_alive: alive,
+            });
+        }
+        self.changes.cells = Some(cells);
+    }
+
+    /// Merge a pattern into the living cells, translated by `at`. Cells that
+    /// land outside the world are left out.
+    fn stamp_action(&mut self, cells: LivingList, at: Vector2<i32>) {
+        self.remember_board();
+        for cell in stamp_cells(&mut self.living_cells, cells, at, &self.bounds) {
+            self.toggle_record.push(Toggle {
+                step: self.step_count,
+                cell,
+                became_alive: true,
+            });
+        }
+        self.forget_history();
+        self.living_cell_count = self.living_cells.len();
+
+        self.changes.cells = Some(self.get_cells());
+    }
+
+    /// Flip every cell from `min` to `max`, inclusive, unless that would put
+    /// the population over the cap.
+    fn invert_action(&mut self, min: Vector2<i32>, max: Vector2<i32>) {
+        let Some((min, max)) = self.bounds.clip(min, max) else {
+            return;
+        };
+        let population = inverted_population(&self.living_cells, min, max);
+        if let Some(cap) = self.population_cap
+            && population > cap
         {
-            self.input_queue.push_back(QueueAction::Toggle(cell_pos));
+            log::warn!("Not inverting the view, which would bring the population over {cap}");
+            return;
+        }
+        self.remember_board();
+
+        let toggles = invert_region(&mut self.living_cells, min, max, self.step_count);
+        self.toggle_record.extend(toggles);
+        self.forget_history();
+        self.living_cell_count = self.living_cells.len();
+
+        self.changes.cells = Some(self.get_cells());
+    }
+
+    /// Kill every living cell between two corners, inclusive.
+    fn erase_action(&mut self, min: Vector2<i32>, max: Vector2<i32>) {
+        let inside =
+            |c: &&Vector2<i32>| c.x >= min.x && c.x <= max.x && c.y >= min.y && c.y <= max.y;
+        let erased: Vec<_> = self.living_cells.iter().filter(inside).copied().collect();
+        if erased.is_empty() {
+            return;
+        }
+        self.remember_board();
+
+        for cell in erased {
+            self.living_cells.remove(&cell);
+            self.toggle_record.push(Toggle {
+                step: self.step_count,
+                cell,
+                became_alive: false,
+            });
+        }
+        self.forget_history();
+        self.living_cell_count = self.living_cells.len();
+
+        self.changes.cells = Some(self.get_cells());
+    }
+
+    /// Switch rules. Cycles found under the old rules may not repeat under
+    /// the new ones, so the history is forgotten.
+    fn set_rules_action(&mut self, rules: Rules) {
+        if rules != self.rules {
+            self.remember_board();
+            self.rules = rules;
+            self.forget_history();
+        }
+    }
+
+    fn set_bounds_action(&mut self, bounds: WorldBounds) {
+        if bounds == self.bounds {
+            return;
+        }
+        self.remember_board();
+        self.bounds = bounds;
+        self.clip_to_bounds();
+        self.forget_history();
+        self.refresh_overlay();
+        self.refresh_wrap_ghosts();
+    }
+
+    /// Remove the living cells and edits that are outside the world.
+    fn clip_to_bounds(&mut self) {
+        let bounds = self.bounds;
+        if bounds == WorldBounds::Infinite {
+            return;
+        }
+        self.living_cells.retain(|c| bounds.contains(*c));
+        self.toggle_record.retain(|t| bounds.contains(t.cell));
+        self.living_cell_count = self.living_cells.len();
+        self.changes.cells = Some(self.get_cells());
+    }
+
+    /// Stretch the board by `factor`, keeping the top-left of its bounding box
+    /// in place.
+    fn scale_action(&mut self, factor: u32) {
+        let Some((min, _)) = patterns::bounding_box(&self.living_cells) else {
+            return;
+        };
+        let population = self.living_cells.len() * (factor as usize).pow(2);
+        if let Some(cap) = self.population_cap
+            && population > cap
+        {
+            log::warn!("Not scaling the board, which would bring the population over {cap}");
+            return;
+        }
+        self.remember_board();
+
+        let shifted = self.living_cells.iter().map(|c| *c - min).collect();
+        self.living_cells = patterns::scale_pattern(&shifted, factor)
+            .into_iter()
+            .map(|c| c + min)
+            .collect();
+        self.clip_to_bounds();
+        self.forget_history();
+        self.living_cell_count = self.living_cells.len();
+
+        self.changes.cells = Some(self.get_cells());
+    }
+
+    /// Flip the most recently edited cell back and forget the edit.
+    fn undo_toggle_action(&mut self) {
+        let Some(toggle) = self.toggle_record.pop() else {
+            return;
+        };
+        self.remember_board();
+        if toggle.step < self.step_count {
+            log::warn!(
+                "Undoing an edit from {} generations ago",
+                self.step_count - toggle.step
+            );
+        }
+        toggle.revert(&mut self.living_cells);
+        self.forget_history();
+        self.living_cell_count = self.living_cells.len();
+
+        self.changes.cells = Some(self.get_cells());
+    }
+
+    /// Save the board before an edit so that the edit can be undone. This
+    /// forgets anything that was undone, which can't be redone after a new
+    /// edit.
+    fn remember_board(&mut self) {
+        if self.undo_stack.len() == UNDO_LIMIT {
+            self.undo_stack.pop_front();
+        }
+        self.undo_stack.push_back(self.snapshot());
+        self.redo_stack.clear();
+    }
+
+    /// Put the board back as it was before the last edit.
+    fn undo_action(&mut self) {
+        let Some(snapshot) = self.undo_stack.pop_back() else {
+            return;
+        };
+        self.redo_stack.push(self.snapshot());
+        self.restore_edit(&snapshot);
+    }
+
+    /// Make the last undone edit again.
+    fn redo_action(&mut self) {
+        let Some(snapshot) = self.redo_stack.pop() else {
+            return;
+        };
+        self.undo_stack.push_back(self.snapshot());
+        self.restore_edit(&snapshot);
+    }
+
+    /// Go back to a board from the undo or redo stack. Unlike loading a save,
+    /// the population history up to its generation is kept.
+    fn restore_edit(&mut self, snapshot: &SimulationSnapshot) {
+        self.restore_world(snapshot);
+        self.living_cells = snapshot.cells();
+        self.clip_to_bounds();
+        self.living_cell_count = self.living_cells.len();
+        rewind_history(
+            &mut self.living_count_history,
+            self.step_count,
+            snapshot.generation,
+            self.living_cell_count,
+        );
+        self.step_count = snapshot.generation;
+        self.toggle_record.retain(|t| t.step <= snapshot.generation);
+        self.forget_history();
+        self.changes.cells = Some(self.get_cells());
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// The most recent manual edit that hasn't been undone, if any.
+    pub fn last_toggle(&self) -> Option<&Toggle> {
+        self.toggle_record.last()
+    }
+
+    /// The cell at the center of the window.
+    pub fn view_center_cell(&self) -> Vector2<i32> {
+        let size = self.window.inner_size();
+        let center = Vector2::new(size.width as f64 / 2.0, size.height as f64 / 2.0);
+        self.camera().screen_to_cell(center, size)
+    }
+
+    /// Center the view on the living cells, zoomed so that they all fit in
+    /// the window with some room around them. With no living cells, the view
+    /// goes back to the origin at the starting zoom.
+    pub fn center_on_living(&mut self) {
+        /// How much of the window the cells may fill along their tighter axis
+        const FIT_FRACTION: f64 = 0.8;
+
+        self.glide = None;
+        self.drag_state = DragState::NotDragging;
+        let Some((min, max)) = patterns::bounding_box(&self.living_cells) else {
+            self.set_grid_size(self.default_grid_size);
+            self.set_pan_position(Vector2::new(0.0, 0.0));
+            return;
+        };
+        // The window is one world unit tall
+        let size = self.window.inner_size();
+        let aspect = size.width as f64 / size.height.max(1) as f64;
+        let width = (max.x - min.x + 1) as f64;
+        let height = (max.y - min.y + 1) as f64;
+        self.set_grid_size((FIT_FRACTION * (aspect / width).min(height.recip())) as f32);
+
+        let center = Vector2::new((min.x + max.x + 1) as f64, (min.y + max.y + 1) as f64);
+        self.look_at(center / 2.0);
+    }
+
+    /// Pan so that a point on the grid is in the middle of the window, where
+    /// cell `(x, y)` covers the points from `(x, y)` to `(x + 1, y + 1)`.
+    pub fn look_at(&mut self, point: Vector2<f64>) {
+        self.glide = None;
+        let world = point * self.grid_size as f64;
+        self.set_pan_position(world - Vector2::new(0.5, 0.5));
+    }
+
+    /// Put the board and view back as they were in the saved session, if
+    /// keeping it is enabled and there is one that can be read.
+    #[cfg(target_arch = "wasm32")]
+    pub fn restore_session(&mut self) {
+        if !self.session.is_enabled() {
+            return;
+        }
+        let Some(session) = Session::load() else {
+            return;
+        };
+        self.restore_action(&session.simulation);
+        self.set_pan_position(session.pan_position);
+        self.set_grid_size(session.grid_size);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn is_keeping_session(&self) -> bool {
+        self.session.is_enabled()
+    }
+
+    /// Set whether the board and view are kept across page reloads.
+    #[cfg(target_arch = "wasm32")]
+    pub fn set_keeping_session(&mut self, keep: bool) {
+        self.session.set_enabled(keep);
+    }
+
+    /// Save the session if the board or view changed and the last save was
+    /// long enough ago.
+    #[cfg(target_arch = "wasm32")]
+    fn autosave_session(&mut self) {
+        let changes = &self.changes;
+        if changes.cells.is_some() || changes.offset.is_some() || changes.grid_size.is_some() {
+            self.session.mark_changed();
+        }
+        let now = self.clock.now();
+        if self.session.is_due(now) {
+            let session = Session {
+                simulation: self.snapshot(),
+                pan_position: self.pan_position,
+                grid_size: self.grid_size,
+            };
+            self.session.save(&session, now);
+        }
+    }
+
+    /// Capture the board and generation, leaving out the view.
+    pub fn snapshot(&self) -> SimulationSnapshot {
+        SimulationSnapshot::new(&self.living_cells, self.step_count, self.rules, self.bounds)
+    }
+
+    /// Switch to the rules and world bounds a snapshot was taken with.
+    fn restore_world(&mut self, snapshot: &SimulationSnapshot) {
+        self.rules = snapshot.rules;
+        if snapshot.bounds != self.bounds {
+            self.bounds = snapshot.bounds;
+            self.refresh_overlay();
+            self.refresh_wrap_ghosts();
+        }
+    }
+
+    /// Replace the board, generation, rules, and world bounds with a
+    /// snapshot's.
+    #[cfg_attr(not(any(feature = "saving", target_arch = "wasm32")), allow(dead_code))]
+    fn restore_action(&mut self, snapshot: &SimulationSnapshot) {
+        self.reset_board();
+        self.restore_world(snapshot);
+        self.living_cells = snapshot.cells();
+        self.clip_to_bounds();
+        self.living_cell_count = self.living_cells.len();
+        self.step_count = snapshot.generation;
+        self.changes.cells = Some(self.get_cells());
+    }
+
+    #[cfg(feature = "saving")]
+    fn load_action(&mut self, save: SaveGame, adopt_settings: bool) {
+        self.remember_board();
+        let simulation = save.simulation().for_load(self.rules, self.bounds, adopt_settings);
+        self.restore_action(&simulation);
+        self.set_pan_position(save.pan_position());
+        self.set_grid_size(save.grid_size());
+    }
+}
+
+#[cfg(feature = "native_threads")]
+impl GameState {
+    pub fn new(window: Arc<Window>, grid_size: f32) -> Self {
+        Self::with_worker_capacity(window, grid_size, DEFAULT_WORKER_CAPACITY)
+    }
+
+    /// Create a new `GameState` whose worker thread may compute up to
+    /// `worker_capacity` generations ahead of the board while auto-playing.
+    ///
+    /// A larger capacity smooths out auto-play when some generations are slow
+    /// to compute, but every generation computed ahead is thrown away when the
+    /// board is edited, so edits during auto-play take longer to show up. A
+    /// capacity of 0 only computes a generation when it is needed.
+    pub fn with_worker_capacity(
+        window: Arc<Window>,
+        grid_size: f32,
+        worker_capacity: usize,
+    ) -> Self {
+        let (tx, rx) = mpsc::sync_channel(worker_capacity);
+        let condvar = Condvar::new();
+        let notification = Mutex::new(StepThreadNotification::Waiting);
+        let shared_thread_data = Arc::new(SharedThreadData {
+            condvar,
+            notification,
+            computing: AtomicBool::new(false),
+            epoch: AtomicU64::new(0),
+            computed: AtomicU64::new(0),
+        });
+        let join_handle = spawn_worker(Arc::clone(&shared_thread_data), tx);
+
+        let local_thread_data = LocalThreadData {
+            join_handle,
+            rx,
+            capacity: worker_capacity,
+            wanted: 0,
+        };
+
+        let thread_data = ThreadData {
+            local: local_thread_data,
+            shared: shared_thread_data,
+        };
+
+        #[cfg(feature = "saving")]
+        let save_file = SaveFile::new("./save.json".into()).unwrap();
+
+        let clock = Clock::default();
+        let last_input = clock.now();
+        Self {
+            pan_position: [0.0, 0.0].into(),
+            living_cells: FxHashSet::default(),
+            loop_state: LoopState::new(),
+            clock,
+            compute_backend: ComputeBackend::default(),
+            gpu_stepper: None,
+            rules: Rules::default(),
+            bounds: WorldBounds::default(),
+            interval: default_interval(WORKER_DEGRADED),
+            window,
+            mouse_position: None,
+            grid_size,
+            default_grid_size: grid_size,
+            drag_state: DragState::NotDragging,
+            thread_data,
+            input_queue: VecDeque::new(),
+            living_cell_count: 0,
+            cell_padding: 0.0,
+            clear_color: CLEAR_COLOR,
+            cell_color: CELL_COLOR,
+            cell_shape: CellShape::default(),
+            follow: None,
+            paste_buffer: None,
+            show_neighbor_counts: false,
+            show_centroid: false,
+            show_grid: false,
+            show_wrap_ghosts: false,
+            centroid_trail: VecDeque::new(),
+            step_times: VecDeque::with_capacity(STEP_RATE_SAMPLES),
+            trail_length: DEFAULT_TRAIL_LENGTH,
+            pan_inertia: false,
+            camera_locked: false,
+            edit_while_locked: true,
+            paint_mode: false,
+            brush: None,
+            stroke_remembered: false,
+            selection: None,
+            selection_anchor: None,
+            clipboard: None,
+            glide: None,
+            pan_keys: FxHashSet::default(),
+            key_pan_at: None,
+            #[cfg(target_arch = "wasm32")]
+            session: SessionAutosave::new(),
+            speed_factor: INTERVAL_P,
+            speed_changed_at: None,
+            catch_up: false,
+            pending_steps: 0,
+            target_generation: None,
+            held_step_due: None,
+            ticks: TickPacer::default(),
+            stability: StabilityDetector::default(),
+            spaceships: None,
+            last_step: None,
+            population_cap: None,
+            pause_at_phase_zero: false,
+            idle_timeout: None,
+            power_save_after: Some(DEFAULT_POWER_SAVE_AFTER),
+            last_input,
+            idle_paused: false,
+            #[cfg(feature = "saving")]
+            current_save: None,
+            #[cfg(feature = "saving")]
+            wrap_saves: true,
+            #[cfg(feature = "saving")]
+            snapshot_on_stable: false,
+            #[cfg(feature = "saving")]
+            last_snapshot: None,
+            benchmark: None,
+            benchmark_summary: None,
+            step_count: 0,
+            living_count_history: vec![0],
+            start_pattern: None,
+            start_seed: None,
+            changes: StateChanges::default(),
+            toggle_record: Vec::new(),
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            modifiers: ModifiersState::empty(),
+            #[cfg(feature = "saving")]
+            save_file: Some(save_file),
+            #[cfg(target_arch = "wasm32")]
+            scroll_mode: Default::default(),
+        }
+    }
+
+    /// Whether a generation has been requested from the worker but hasn't
+    /// replaced the board yet. Edits made while this is true are queued.
+    fn worker_busy(&self) -> bool {
+        self.thread_data.local.wanted > 0
+    }
+
+    /// Throw away any generations the worker has computed or is computing, for
+    /// when the board changes under it.
+    fn invalidate_worker(&mut self) {
+        self.thread_data
+            .shared
+            .epoch
+            .fetch_add(1, atomic::Ordering::Relaxed);
+        // Make room in the channel in case the worker is blocked on sending
+        while self.thread_data.local.rx.try_recv().is_ok() {}
+    }
+
+    /// Ask the worker for the next generation if one is wanted and it's idle.
+    fn request_step(&mut self) {
+        let shared = &self.thread_data.shared;
+        if self.thread_data.local.wanted == 0 || shared.computing.load(atomic::Ordering::Relaxed) {
+            return;
+        }
+        shared.computing.store(true, atomic::Ordering::Relaxed);
+        let mut noti_lock = shared.notification.lock().unwrap();
+        *noti_lock = StepThreadNotification::Compute {
+            cells: self.living_cells.clone(),
+            epoch: shared.epoch.load(atomic::Ordering::Relaxed),
+            ahead: self.loop_state.is_playing() && self.thread_data.local.capacity > 0,
+            gpu: self.active_gpu().cloned(),
+            rules: self.rules,
+            bounds: self.bounds,
+        };
+        shared.condvar.notify_all();
+    }
+
+    /// Stop waiting for the generations the worker is computing, throwing
+    /// them away, and apply the edits that were waiting on them. This is for
+    /// changes that replace the whole board, which don't need to wait.
+    fn cancel_steps(&mut self) {
+        self.thread_data.local.wanted = 0;
+        self.invalidate_worker();
+        self.resolve_queue();
+    }
+
+    /// Load a save right away, even if a generation is being computed. Its
+    /// rules and world bounds are only used if `adopt_settings` is set.
+    #[cfg(feature = "saving")]
+    pub fn load_save(&mut self, save: &SaveGame, adopt_settings: bool) {
+        self.cancel_steps();
+        self.load_action(save.clone(), adopt_settings);
+    }
+
+    pub fn step(&mut self) {
+        if self.worker_busy() {
+            return;
+        }
+        self.thread_data.local.wanted += 1;
+        self.request_step();
+    }
+
+    /// Advance `n` generations right away on this thread, bypassing the
+    /// worker. Edits waiting on the worker are applied first, and anything it
+    /// was computing is thrown away.
+    // Not used by the app itself, which steps through the worker
+    #[allow(dead_code)]
+    pub fn step_n(&mut self, n: u64) {
+        self.cancel_steps();
+        self.step_n_action(n);
+    }
+
+    /// Empty the board right away, even if a generation is being computed.
+    pub fn clear(&mut self) {
+        self.forget_start();
+        self.cancel_steps();
+        self.clear_action();
+    }
+
+    /// Merge a pattern into the board with every cell translated by `at`.
+    pub fn stamp_pattern(&mut self, cells: LivingList, at: Vector2<i32>) {
+        if self.worker_busy() {
+            self.input_queue.push_back(QueueAction::Stamp(cells, at));
+        } else {
+            self.invalidate_worker();
+            self.stamp_action(cells, at);
+        }
+    }
+
+    /// Kill the living cells in the selection, which stays selected.
+    pub fn delete_selection(&mut self) {
+        let Some((min, max)) = self.selection else {
+            return;
+        };
+        if self.worker_busy() {
+            self.input_queue.push_back(QueueAction::Erase(min, max));
+        } else {
+            self.invalidate_worker();
+            self.erase_action(min, max);
+        }
+    }
+
+    /// Flip every cell in view, bringing dead ones to life and killing living
+    /// ones. Nothing happens if the population would end up over the cap.
+    pub fn invert_visible(&mut self) {
+        let (min, max) = self.visible_cell_bounds();
+        if self.worker_busy() {
+            self.input_queue.push_back(QueueAction::Invert(min, max));
+        } else {
+            self.invalidate_worker();
+            self.invert_action(min, max);
+        }
+    }
+
+    /// Stretch the whole board by an integer factor, replacing each cell with
+    /// a block. Nothing happens if the population would end up over the cap.
+    pub fn scale_board(&mut self, factor: u32) {
+        if self.worker_busy() {
+            self.input_queue.push_back(QueueAction::Scale(factor));
+        } else {
+            self.invalidate_worker();
+            self.scale_action(factor);
+        }
+    }
+
+    /// Run the simulation with different rules from the next step on.
+    pub fn set_rules(&mut self, rules: Rules) {
+        if self.worker_busy() {
+            self.input_queue.push_back(QueueAction::SetRules(rules));
+        } else {
+            self.invalidate_worker();
+            self.set_rules_action(rules);
+        }
+    }
+
+    /// Change the shape of the world. Cells outside the new bounds are
+    /// removed.
+    pub fn set_world_bounds(&mut self, bounds: WorldBounds) {
+        if self.worker_busy() {
+            self.input_queue.push_back(QueueAction::SetBounds(bounds));
+        } else {
+            self.invalidate_worker();
+            self.set_bounds_action(bounds);
+        }
+    }
+
+    /// Put the board back as it was before the last edit.
+    pub fn undo(&mut self) {
+        if self.worker_busy() {
+            self.input_queue.push_back(QueueAction::Undo);
+        } else {
+            self.invalidate_worker();
+            self.undo_action();
+        }
+    }
+
+    /// Make the last undone edit again.
+    pub fn redo(&mut self) {
+        if self.worker_busy() {
+            self.input_queue.push_back(QueueAction::Redo);
+        } else {
+            self.invalidate_worker();
+            self.redo_action();
+        }
+    }
+
+    /// Undo the last manual edit by flipping its cell back, even if
+    /// generations have passed since it was made.
+    pub fn undo_toggle(&mut self) {
+        if self.worker_busy() {
+            self.input_queue.push_back(QueueAction::UndoToggle);
+        } else {
+            self.invalidate_worker();
+            self.undo_toggle_action();
+        }
+    }
+
+    /// Make `cell` alive or dead.
+    // Not used by the app itself, which only toggles cells
+    #[allow(dead_code)]
+    pub fn set_cell(&mut self, cell: Vector2<i32>, alive: bool) {
+        if self.worker_busy() {
+            self.input_queue.push_back(QueueAction::Set(cell, alive));
+        } else {
+            self.invalidate_worker();
+            self.set_action(cell, alive);
+        }
+    }
+
+    /// Bring cells to life as part of a brush stroke, starting a new one if
+    /// `new_stroke` is set. Each stroke is undone as a whole.
+    pub fn paint_cells(&mut self, cells: Vec<Vector2<i32>>, new_stroke: bool) {
+        if self.worker_busy() {
+            self.input_queue.push_back(QueueAction::Paint(cells, new_stroke));
+        } else {
+            self.invalidate_worker();
+            self.paint_action(cells, new_stroke);
+        }
+    }
+
+    /// Flip whether `cell` is alive.
+    pub fn toggle_cell(&mut self, cell: Vector2<i32>) {
+        if self.worker_busy() {
+            self.input_queue.push_back(QueueAction::Toggle(cell));
         } else {
-            self.left_action(cell_pos);
+            self.invalidate_worker();
+            self.left_action(cell);
         }
     }
 
     pub fn update(&mut self) -> StateChanges {
-        let should_step = self.loop_state.update(&self.interval);
-
-        if should_step
-            && !self
-                .thread_data
-                .shared
-                .computing
-                .load(atomic::Ordering::Relaxed)
-        {
+        self.drive_benchmark();
+        self.drive_target();
+        self.drive_held_step();
+        self.check_idle();
+
+        self.schedule_steps();
+        while self.take_due_step() {
             self.step();
         }
 
-        if let Ok(v) = self.thread_data.local.rx.try_recv() {
-            self.living_cells = v;
-            self.changes.cells = Some(self.get_cells());
-            self.thread_data
-                .shared
-                .computing
-                .store(false, atomic::Ordering::Relaxed);
-            let mut lock = self.thread_data.shared.notification.lock().unwrap();
-            *lock = StepThreadNotification::Waiting;
-            self.step_count += 1;
-            self.living_cell_count = self.living_cells.len();
-            self.living_count_history.push(self.living_cell_count);
-            drop(lock);
-            self.resolve_queue();
+        // Take the wanted generation if it's ready, skipping any that were
+        // computed before the board last changed
+        let epoch = self
+            .thread_data
+            .shared
+            .epoch
+            .load(atomic::Ordering::Relaxed);
+        while self.worker_busy()
+            && let Ok(v) = self.thread_data.local.rx.try_recv()
+        {
+            if v.epoch != epoch {
+                continue;
+            }
+            self.thread_data.local.wanted -= 1;
+            self.finish_step(v.cells);
+            if !self.input_queue.is_empty() {
+                self.invalidate_worker();
+                self.resolve_queue();
+            }
+            break;
         }
+        self.request_step();
+
+        self.update_glide();
+        self.update_key_pan();
+        self.update_follow();
+        self.update_board_overlays();
 
         std::mem::take(&mut self.changes)
     }
@@ -524,20 +2910,86 @@ impl GameState {
         #[cfg(not(target_arch = "wasm32"))]
         #[cfg(feature = "saving")]
         let save_file = SaveFile::new("./save.json".into()).unwrap();
+        let clock = Clock::default();
+        let last_input = clock.now();
         Self {
             pan_position: [0.0, 0.0].into(),
             living_cells: FxHashSet::default(),
             loop_state: LoopState::new(),
-            interval: DEFAULT_INTERVAL,
+            clock,
+            compute_backend: ComputeBackend::default(),
+            gpu_stepper: None,
+            rules: Rules::default(),
+            bounds: WorldBounds::default(),
+            interval: default_interval(WORKER_DEGRADED),
             window,
             mouse_position: None,
             grid_size,
+            default_grid_size: grid_size,
             drag_state: DragState::NotDragging,
             input_queue: VecDeque::new(),
             living_cell_count: 0,
+            cell_padding: 0.0,
+            clear_color: CLEAR_COLOR,
+            cell_color: CELL_COLOR,
+            cell_shape: CellShape::default(),
+            follow: None,
+            paste_buffer: None,
+            show_neighbor_counts: false,
+            show_centroid: false,
+            show_grid: false,
+            show_wrap_ghosts: false,
+            centroid_trail: VecDeque::new(),
+            step_times: VecDeque::with_capacity(STEP_RATE_SAMPLES),
+            trail_length: DEFAULT_TRAIL_LENGTH,
+            pan_inertia: false,
+            camera_locked: false,
+            edit_while_locked: true,
+            paint_mode: false,
+            brush: None,
+            stroke_remembered: false,
+            selection: None,
+            selection_anchor: None,
+            clipboard: None,
+            glide: None,
+            pan_keys: FxHashSet::default(),
+            key_pan_at: None,
+            #[cfg(target_arch = "wasm32")]
+            session: SessionAutosave::new(),
+            speed_factor: INTERVAL_P,
+            speed_changed_at: None,
+            catch_up: false,
+            pending_steps: 0,
+            target_generation: None,
+            held_step_due: None,
+            ticks: TickPacer::default(),
+            stability: StabilityDetector::default(),
+            spaceships: None,
+            last_step: None,
+            population_cap: None,
+            pause_at_phase_zero: false,
+            idle_timeout: None,
+            power_save_after: Some(DEFAULT_POWER_SAVE_AFTER),
+            last_input,
+            idle_paused: false,
+            #[cfg(feature = "saving")]
+            current_save: None,
+            #[cfg(feature = "saving")]
+            wrap_saves: true,
+            #[cfg(feature = "saving")]
+            snapshot_on_stable: false,
+            #[cfg(feature = "saving")]
+            last_snapshot: None,
+            benchmark: None,
+            benchmark_summary: None,
             step_count: 0,
             living_count_history: vec![0],
+            start_pattern: None,
+            start_seed: None,
             toggle_record: Vec::new(),
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            modifiers: ModifiersState::empty(),
             changes: StateChanges::default(),
             #[cfg(feature = "saving")]
             save_file: Some(save_file),
@@ -545,218 +2997,924 @@ impl GameState {
     }
 
     pub fn step(&mut self) {
-        self.living_cells = compute_step(&self.living_cells);
-        self.changes.cells = Some(self.get_cells());
-        self.step_count += 1;
-        self.living_cell_count = self.living_cells.len();
-        self.living_count_history.push(self.living_cell_count);
+        let gpu = self.active_gpu().map(Arc::as_ref);
+        let cells = &self.living_cells;
+        let next = compute::step_on(gpu, cells, &self.rules, &self.bounds, &Cancel::NEVER)
+            .expect("The step can't be cancelled");
+        self.finish_step(next);
+    }
+
+    /// Advance `n` generations right away.
+    // Not used by the app itself
+    #[allow(dead_code)]
+    pub fn step_n(&mut self, n: u64) {
+        self.step_n_action(n);
+    }
+
+    /// Steps are computed immediately, so there's never one in progress.
+    fn worker_busy(&self) -> bool {
+        false
     }
 
     pub fn clear(&mut self) {
-        self.living_cells.clear();
-        self.changes.cells = Some(Vec::new());
+        self.forget_start();
+        self.clear_action();
     }
 
     #[cfg(feature = "saving")]
-    pub fn load_save(&mut self, save: &SaveGame) {
-        self.load_action(save.clone());
+    pub fn load_save(&mut self, save: &SaveGame, adopt_settings: bool) {
+        self.load_action(save.clone(), adopt_settings);
+    }
+
+    /// Merge a pattern into the board with every cell translated by `at`.
+    pub fn stamp_pattern(&mut self, cells: LivingList, at: Vector2<i32>) {
+        self.stamp_action(cells, at);
+    }
+
+    /// Kill the living cells in the selection, which stays selected.
+    pub fn delete_selection(&mut self) {
+        if let Some((min, max)) = self.selection {
+            self.erase_action(min, max);
+        }
+    }
+
+    /// Flip every cell in view, bringing dead ones to life and killing living
+    /// ones. Nothing happens if the population would end up over the cap.
+    pub fn invert_visible(&mut self) {
+        let (min, max) = self.visible_cell_bounds();
+        self.invert_action(min, max);
+    }
+
+    /// Stretch the whole board by an integer factor, replacing each cell with
+    /// a block. Nothing happens if the population would end up over the cap.
+    pub fn scale_board(&mut self, factor: u32) {
+        self.scale_action(factor);
+    }
+
+    /// Run the simulation with different rules from the next step on.
+    pub fn set_rules(&mut self, rules: Rules) {
+        self.set_rules_action(rules);
+    }
+
+    /// Change the shape of the world. Cells outside the new bounds are
+    /// removed.
+    pub fn set_world_bounds(&mut self, bounds: WorldBounds) {
+        self.set_bounds_action(bounds);
+    }
+
+    /// Put the board back as it was before the last edit.
+    pub fn undo(&mut self) {
+        self.undo_action();
+    }
+
+    /// Make the last undone edit again.
+    pub fn redo(&mut self) {
+        self.redo_action();
+    }
+
+    /// Undo the last manual edit by flipping its cell back, even if
+    /// generations have passed since it was made.
+    pub fn undo_toggle(&mut self) {
+        self.undo_toggle_action();
+    }
+
+    /// Make `cell` alive or dead.
+    // Not used by the app itself, which only toggles cells
+    #[allow(dead_code)]
+    pub fn set_cell(&mut self, cell: Vector2<i32>, alive: bool) {
+        self.set_action(cell, alive);
+    }
+
+    /// Bring cells to life as part of a brush stroke, starting a new one if
+    /// `new_stroke` is set. Each stroke is undone as a whole.
+    pub fn paint_cells(&mut self, cells: Vec<Vector2<i32>>, new_stroke: bool) {
+        self.paint_action(cells, new_stroke);
+    }
+
+    /// Flip whether `cell` is alive.
+    pub fn toggle_cell(&mut self, cell: Vector2<i32>) {
+        self.left_action(cell);
+    }
+
+    pub fn update(&mut self) -> StateChanges {
+        self.drive_benchmark();
+        self.drive_target();
+        self.drive_held_step();
+        self.check_idle();
+
+        self.schedule_steps();
+        while self.take_due_step() {
+            self.step();
+        }
+
+        self.resolve_queue();
+
+        self.update_glide();
+        self.update_key_pan();
+        self.update_follow();
+        self.update_board_overlays();
+        #[cfg(target_arch = "wasm32")]
+        self.autosave_session();
+
+        std::mem::take(&mut self.changes)
+    }
+}
+
+#[cfg(feature = "native_threads")]
+enum StepThreadNotification {
+    Exit,
+    Waiting,
+    Compute {
+        cells: LivingList,
+        /// The epoch that the results will be tagged with
+        epoch: u64,
+        /// Whether to keep computing later generations after this one
+        ahead: bool,
+        /// What to step on instead of the CPU, if anything
+        gpu: Option<Arc<GpuStepper>>,
+        rules: Rules,
+        bounds: WorldBounds,
+    },
+}
+
+/// Start the thread that computes generations for `GameState`. It waits for
+/// `SharedThreadData::notification` to ask for a step and sends the results
+/// through `tx`, running ahead until `tx` is full if asked to.
+#[cfg(feature = "native_threads")]
+fn spawn_worker(
+    thread_data: Arc<SharedThreadData>,
+    tx: mpsc::SyncSender<StepResult>,
+) -> JoinHandle<()> {
+    use StepThreadNotification as STN;
+    std::thread::spawn(move || loop {
+        let cvar = &thread_data.condvar;
+        let lock = &thread_data.notification;
+
+        // Wait for something to do
+        let (data, epoch, ahead, gpu, rules, bounds) = {
+            let mut data_guard = lock.lock().unwrap();
+            loop {
+                match std::mem::replace(&mut *data_guard, STN::Waiting) {
+                    STN::Exit => return,
+                    STN::Waiting => data_guard = cvar.wait(data_guard).unwrap(),
+                    STN::Compute {
+                        cells,
+                        epoch,
+                        ahead,
+                        gpu,
+                        rules,
+                        bounds,
+                    } => break (cells, epoch, ahead, gpu, rules, bounds),
+                }
+            }
+        };
+
+        // Compute the requested generation, then keep going while
+        // running ahead until the results are no longer wanted. `send`
+        // blocks while the channel is full.
+        // The board changing under a step makes it worthless, so it's
+        // abandoned rather than finished
+        let cancel = Cancel::when_changed(&thread_data.epoch, epoch);
+        let mut next = compute::step_on(gpu.as_deref(), &data, &rules, &bounds, &cancel);
+        while let Some(cells) = next {
+            thread_data.computed.fetch_add(1, atomic::Ordering::Relaxed);
+            let result = StepResult {
+                epoch,
+                cells: cells.clone(),
+            };
+            if tx.send(result).is_err() {
+                return;
+            }
+            if !ahead || cancel.is_cancelled() {
+                break;
+            }
+            next = compute::step_on(gpu.as_deref(), &cells, &rules, &bounds, &cancel);
+        }
+        thread_data
+            .computing
+            .store(false, sync::atomic::Ordering::Relaxed);
+    })
+}
+
+/// A generation computed by the worker thread.
+#[cfg(feature = "native_threads")]
+struct StepResult {
+    /// The value of `SharedThreadData::epoch` when the step was requested
+    epoch: u64,
+    cells: LivingList,
+}
+
+#[cfg(feature = "native_threads")]
+struct SharedThreadData {
+    notification: Mutex<StepThreadNotification>,
+    condvar: Condvar,
+    computing: AtomicBool,
+    /// Incremented whenever the board changes, so that results computed from
+    /// an outdated board can be recognized and discarded
+    epoch: AtomicU64,
+    /// How many generations the worker has finished computing, counted before
+    /// each is sent
+    computed: AtomicU64,
+}
+
+#[cfg(feature = "native_threads")]
+struct ThreadData {
+    shared: Arc<SharedThreadData>,
+    local: LocalThreadData,
+}
+
+#[cfg(feature = "native_threads")]
+struct LocalThreadData {
+    // The join handle is good to have around, so we'll keep it here even though
+    // it's unused.
+    #[allow(dead_code)]
+    join_handle: JoinHandle<()>,
+    rx: mpsc::Receiver<StepResult>,
+    /// How many generations the worker may compute ahead while auto-playing
+    capacity: usize,
+    /// How many generations have been requested but not yet applied
+    wanted: u64,
+}
+
+#[derive(Default)]
+pub struct StateChanges {
+    pub grid_size: Option<f32>,
+    pub cells: Option<Vec<Cell>>,
+    pub offset: Option<Vector2<f64>>,
+    pub cell_padding: Option<f32>,
+    pub clear_color: Option<[f32; 4]>,
+    pub cell_color: Option<[f32; 4]>,
+    pub cell_shape: Option<CellShape>,
+    /// The encoded image to draw living cells with
+    pub cell_texture: Option<Vec<u8>>,
+    pub grid_visible: Option<bool>,
+    /// The size of the torus whose edge cells are copied past the opposite
+    /// edges, or `None` to stop drawing the copies
+    pub wrap_ghosts: Option<Option<Vector2<i32>>>,
+    pub start_recording: Option<GifSettings>,
+    /// Whether a screenshot should be saved after the next frame is drawn
+    pub screenshot: bool,
+    pub overlay: Option<Vec<OverlayCell>>,
+    /// Notable things that happened, oldest first
+    pub events: Vec<GameEvent>,
+}
+
+impl std::ops::AddAssign<StateChanges> for StateChanges {
+    fn add_assign(&mut self, other: StateChanges) {
+        if other.grid_size.is_some() {
+            self.grid_size = other.grid_size
+        };
+        if other.cells.is_some() {
+            self.cells = other.cells
+        };
+        if other.offset.is_some() {
+            self.offset = other.offset
+        };
+        if other.cell_padding.is_some() {
+            self.cell_padding = other.cell_padding
+        };
+        if other.clear_color.is_some() {
+            self.clear_color = other.clear_color
+        };
+        if other.cell_color.is_some() {
+            self.cell_color = other.cell_color
+        };
+        if other.cell_shape.is_some() {
+            self.cell_shape = other.cell_shape
+        };
+        if other.cell_texture.is_some() {
+            self.cell_texture = other.cell_texture
+        };
+        if other.grid_visible.is_some() {
+            self.grid_visible = other.grid_visible
+        };
+        if other.wrap_ghosts.is_some() {
+            self.wrap_ghosts = other.wrap_ghosts
+        };
+        if other.start_recording.is_some() {
+            self.start_recording = other.start_recording
+        };
+        if other.screenshot {
+            self.screenshot = true
+        };
+        if other.overlay.is_some() {
+            self.overlay = other.overlay
+        };
+        self.events.extend(other.events);
+    }
+}
+
+/// A cell that the player changed by hand.
+#[derive(Debug, Clone, Copy)]
+pub struct Toggle {
+    /// The generation in which the change was made
+    pub step: u64,
+    pub cell: Vector2<i32>,
+    /// Whether the cell was brought to life, rather than killed
+    pub became_alive: bool,
+}
+
+impl Toggle {
+    /// Put the cell back the way it was before the edit.
+    fn revert(&self, cells: &mut LivingList) {
+        if self.became_alive {
+            cells.remove(&self.cell);
+        } else {
+            cells.insert(self.cell);
+        }
+    }
+}
+
+/// Something notable that happened in the game, which the player may want to
+/// be alerted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    /// The board settled into a still life (period 1) or an oscillator
+    Stabilized { period: u32 },
+    /// The population reached the cap, so auto-play was stopped
+    PopulationCapReached,
+    /// The last living cell died
+    BoardEmptied,
+}
+
+pub enum LoopState {
+    Playing { last_update: Instant },
+    Stopped,
+}
+
+impl LoopState {
+    fn new() -> Self {
+        Self::Stopped
+    }
+
+    #[allow(dead_code)]
+    fn should_step(&self, interval: &Duration, now: Instant) -> bool {
+        if let Self::Playing { last_update } = self {
+            now.saturating_duration_since(*last_update) >= *interval
+        } else {
+            false
+        }
+    }
+
+    /// Advances the `last_update` field by every interval that has passed by
+    /// `now` if playing, and returns how many did. Otherwise, this is a no-op
+    fn update(&mut self, interval: &Duration, now: Instant) -> u32 {
+        if let Self::Playing { last_update } = self {
+            let elapsed = now.saturating_duration_since(*last_update);
+            let due = (elapsed.as_secs_f64() / interval.as_secs_f64()) as u32;
+            if due > 0 {
+                *last_update += *interval * due;
+            }
+            due
+        } else {
+            0
+        }
+    }
+
+    fn is_playing(&self) -> bool {
+        match self {
+            Self::Stopped => false,
+            Self::Playing { .. } => true,
+        }
+    }
+}
+
+/// The camera's progress towards the centroid of the living cells
+struct FollowState {
+    /// The pan position that centers the centroid
+    target: Vector2<f64>,
+    last_update: Instant,
+}
+
+/// The view drifting on after a flick, slowing down as it goes
+struct Glide {
+    /// How fast the pan position is changing, per second
+    velocity: Vector2<f64>,
+    last_update: Instant,
+}
+
+impl Glide {
+    /// How quickly the glide slows down, in inverse seconds
+    const FRICTION: f64 = 4.0;
+    /// The speed below which the glide stops, in window heights per second
+    const MIN_SPEED: f64 = 0.01;
+
+    /// Slow down to `now`, returning how far the view moved since the last
+    /// update.
+    fn advance(&mut self, now: Instant) -> Vector2<f64> {
+        let dt = now.saturating_duration_since(self.last_update).as_secs_f64();
+        self.last_update = now;
+        // Integrating the exponential decay over the frame keeps the distance
+        // traveled independent of the frame rate
+        let decay = (-dt * Self::FRICTION).exp();
+        let moved = self.velocity * ((1.0 - decay) / Self::FRICTION);
+        self.velocity *= decay;
+        moved
+    }
+
+    fn has_stopped(&self) -> bool {
+        self.velocity.magnitude() < Self::MIN_SPEED
+    }
+}
+
+enum DragState {
+    Dragging {
+        prev_pos: Vector2<f64>,
+        /// How fast the pan position has been changing recently, per second
+        velocity: Vector2<f64>,
+        moved_at: Instant,
+    },
+    NotDragging,
+}
+
+#[cfg_attr(not(feature = "native_threads"), allow(dead_code))]
+enum QueueAction {
+    Toggle(Vector2<i32>),
+    Set(Vector2<i32>, bool),
+    /// Cells painted alive, and whether they start a new brush stroke
+    Paint(Vec<Vector2<i32>>, bool),
+    Stamp(LivingList, Vector2<i32>),
+    UndoToggle,
+    Undo,
+    Redo,
+    Invert(Vector2<i32>, Vector2<i32>),
+    /// Kill every cell in a rectangle, given by its inclusive corners
+    Erase(Vector2<i32>, Vector2<i32>),
+    Scale(u32),
+    SetRules(Rules),
+    SetBounds(WorldBounds),
+}
+
+/// The direction a movement key pans the view in, in world space, or `None` if
+/// it isn't one.
+fn pan_key_direction(key: KeyCode) -> Option<Vector2<f64>> {
+    match key {
+        KeyCode::KeyW => Some(Vector2::new(0.0, -1.0)),
+        KeyCode::KeyA => Some(Vector2::new(-1.0, 0.0)),
+        KeyCode::KeyS => Some(Vector2::new(0.0, 1.0)),
+        KeyCode::KeyD => Some(Vector2::new(1.0, 0.0)),
+        _ => None,
+    }
+}
+
+fn to_cell(cell: Vector2<i32>, camera: &Camera) -> Cell {
+    let world = camera.cell_to_world(cell);
+    Cell {
+        location: world.map(|c| c as f32).into(),
+    }
+}
+
+/// The overlay cell centered on a center of mass, which is in cell coordinates
+/// but not necessarily whole.
+fn centroid_to_cell(centroid: Vector2<f64>, camera: &Camera) -> Cell {
+    let world = (centroid + Vector2::new(0.5, 0.5)) * camera.grid_size as f64;
+    Cell {
+        location: world.map(|c| c as f32).into(),
+    }
+}
+
+/// The cells on a line from `from` to `to`, inclusive, each touching the last
+/// at least at a corner, by Bresenham's algorithm.
+fn line_cells(from: Vector2<i32>, to: Vector2<i32>) -> Vec<Vector2<i32>> {
+    let dx = (to.x - from.x).abs();
+    let dy = -(to.y - from.y).abs();
+    let step = (to - from).map(i32::signum);
+    let mut error = dx + dy;
+    let mut cell = from;
+    let mut cells = vec![from];
+    while cell != to {
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            cell.x += step.x;
+        }
+        if doubled <= dx {
+            error += dx;
+            cell.y += step.y;
+        }
+        cells.push(cell);
+    }
+    cells
+}
+
+fn get_adjacent(coords: &Vector2<i32>) -> [Vector2<i32>; 8] {
+    [
+        [coords.x - 1, coords.y - 1].into(),
+        [coords.x - 1, coords.y + 1].into(),
+        [coords.x - 1, coords.y].into(),
+        [coords.x, coords.y - 1].into(),
+        [coords.x, coords.y + 1].into(),
+        [coords.x + 1, coords.y].into(),
+        [coords.x + 1, coords.y - 1].into(),
+        [coords.x + 1, coords.y + 1].into(),
+    ]
+}
+
+/// The neighbors of a cell on a `width` by `height` torus, where the edges
+/// wrap around to the opposite side. On boards narrower than three cells the
+/// wrapped neighbors overlap, so each is only listed once, and the cell itself
+/// is never included. Results are within `0..width` and `0..height`.
+fn adjacent_wrapped(coords: &Vector2<i32>, width: i32, height: i32) -> Vec<Vector2<i32>> {
+    let wrap = |c: Vector2<i32>| Vector2::new(c.x.rem_euclid(width), c.y.rem_euclid(height));
+    let center = wrap(*coords);
+    let mut res = Vec::with_capacity(8);
+    for neighbor in get_adjacent(&center).map(wrap) {
+        if neighbor != center && !res.contains(&neighbor) {
+            res.push(neighbor);
+        }
+    }
+    res
+}
+
+/// The number of living cells adjacent to `cell`.
+fn living_neighbors(cells: &LivingList, cell: Vector2<i32>, bounds: &WorldBounds) -> u8 {
+    let alive = |c: &Vector2<i32>| cells.contains(c);
+    match *bounds {
+        WorldBounds::Infinite => get_adjacent(&cell).iter().filter(|c| alive(c)).count() as u8,
+        WorldBounds::Torus { width, height } => {
+            adjacent_wrapped(&cell, width, height).iter().filter(|c| alive(c)).count() as u8
+        }
     }
+}
+
+/// Make `next` the current generation of `cells`, counting it in `step_count`
+/// and its population in `living_cell_count` and `history`. Returns the
+/// population before it.
+fn record_generation(
+    cells: &mut LivingList,
+    living_cell_count: &mut usize,
+    history: &mut Vec<usize>,
+    step_count: &mut u64,
+    next: LivingList,
+) -> usize {
+    let previous_count = *living_cell_count;
+    *cells = next;
+    *step_count += 1;
+    *living_cell_count = cells.len();
+    history.push(*living_cell_count);
+    previous_count
+}
+
+/// Something stepped several generations at a time, which is the game itself
+/// outside of tests.
+trait Generations {
+    /// Compute and record the next generation, returning its population.
+    fn step_once(&mut self) -> usize;
+    /// Pass the current cells on to be drawn.
+    fn show_cells(&mut self);
+}
+
+impl Generations for GameState {
+    fn step_once(&mut self) -> usize {
+        let gpu = self.active_gpu().map(Arc::as_ref);
+        let cells = &self.living_cells;
+        let next = compute::step_on(gpu, cells, &self.rules, &self.bounds, &Cancel::NEVER)
+            .expect("The step can't be cancelled");
+        self.advance(next);
+        self.living_cell_count
+    }
+
+    fn show_cells(&mut self) {
+        self.changes.cells = Some(self.get_cells());
+    }
+}
+
+/// Step `n` generations, stopping early once the population reaches `cap`,
+/// and only show the cells once, at the end.
+fn step_n(generations: &mut impl Generations, n: u64, cap: Option<usize>) {
+    for _ in 0..n {
+        let population = generations.step_once();
+        if cap.is_some_and(|cap| population >= cap) {
+            break;
+        }
+    }
+    generations.show_cells();
+}
 
-    fn handle_left(&mut self, mouse_position: Vector2<f64>) {
-        let size = self.window.inner_size();
-        let cell_pos = find_cell_num(size, mouse_position, self.pan_position, self.grid_size);
+/// Compute the generation after `prev` under `rules`, in a world shaped by
+/// `bounds`, which `prev` should be inside. This doesn't touch any game state,
+/// so it can be used to run the simulation headlessly.
+pub fn compute_step(prev: &LivingList, rules: &Rules, bounds: &WorldBounds) -> LivingList {
+    try_compute_step(prev, rules, bounds, &Cancel::NEVER).expect("The step can't be cancelled")
+}
 
-        self.left_action(cell_pos);
+/// Like `compute_step`, but giving up with `None` once `cancel` says the
+/// result is no longer wanted.
+fn try_compute_step(
+    prev: &LivingList,
+    rules: &Rules,
+    bounds: &WorldBounds,
+    cancel: &Cancel,
+) -> Option<LivingList> {
+    match bounds {
+        WorldBounds::Infinite => chunks::step(prev, rules, cancel),
+        WorldBounds::Torus { .. } => step_by_counts(prev, rules, bounds, cancel),
     }
+}
 
-    pub fn update(&mut self) -> StateChanges {
-        let should_step = self.loop_state.update(&self.interval);
-
-        if should_step {
-            self.step();
-        }
+/// Checked while a generation is computed, so that computing one nobody wants
+/// anymore can stop partway through.
+#[derive(Debug, Clone, Copy)]
+pub struct Cancel<'a> {
+    /// A counter that is changed to cancel, and its value when the step
+    /// started
+    epoch: Option<(&'a std::sync::atomic::AtomicU64, u64)>,
+}
 
-        self.resolve_queue();
+impl<'a> Cancel<'a> {
+    /// For steps that always run to the end
+    pub const NEVER: Cancel<'static> = Cancel { epoch: None };
 
-        std::mem::take(&mut self.changes)
+    /// Cancel once `epoch` no longer holds `started`.
+    #[cfg_attr(not(feature = "native_threads"), allow(dead_code))]
+    pub fn when_changed(epoch: &'a std::sync::atomic::AtomicU64, started: u64) -> Self {
+        Self {
+            epoch: Some((epoch, started)),
+        }
     }
-}
 
-#[cfg(feature = "native_threads")]
-enum StepThreadNotification {
-    Exit,
-    Waiting,
-    Compute(LivingList),
+    pub fn is_cancelled(&self) -> bool {
+        self.epoch.is_some_and(|(epoch, started)| {
+            epoch.load(std::sync::atomic::Ordering::Relaxed) != started
+        })
+    }
 }
 
-#[cfg(feature = "native_threads")]
-struct SharedThreadData {
-    notification: Mutex<StepThreadNotification>,
-    condvar: Condvar,
-    computing: AtomicBool,
+/// The name of an automatic save of a board that has become stable, with a
+/// cycle of `period` generations, at `generation`.
+#[cfg(feature = "saving")]
+fn stable_save_name(period: u32, generation: u64) -> String {
+    match period {
+        1 => format!("Still life at generation {generation}"),
+        p => format!("Period {p} at generation {generation}"),
+    }
 }
 
-#[cfg(feature = "native_threads")]
-struct ThreadData {
-    shared: Arc<SharedThreadData>,
-    local: LocalThreadData,
+/// The index of the save after (or before, unless `forward`) `current` among
+/// `count` saves, going round past either end if `wrap` is set. With nothing
+/// loaded yet it's the first save.
+#[cfg(feature = "saving")]
+fn adjacent_save(current: Option<usize>, count: usize, forward: bool, wrap: bool) -> Option<usize> {
+    match current {
+        None => Some(0),
+        Some(i) if forward && i + 1 < count => Some(i + 1),
+        Some(_) if forward => wrap.then_some(0),
+        // The save may have been deleted since
+        Some(i) if i > 0 => Some((i - 1).min(count.saturating_sub(1))),
+        Some(_) => wrap.then(|| count.saturating_sub(1)),
+    }
 }
 
-#[cfg(feature = "native_threads")]
-struct LocalThreadData {
-    // The join handle is good to have around, so we'll keep it here even though
-    // it's unused.
-    #[allow(dead_code)]
-    join_handle: JoinHandle<()>,
-    rx: mpsc::Receiver<LivingList>,
+/// Whether `idle` without input is long enough for `timeout`, such as the idle
+/// timeout or the power save delay, to take effect. It never is while the
+/// timeout is off.
+fn idle_expired(timeout: Option<Duration>, idle: Duration) -> bool {
+    timeout.is_some_and(|timeout| idle >= timeout)
 }
 
-#[derive(Default)]
-pub struct StateChanges {
-    pub grid_size: Option<f32>,
-    pub cells: Option<Vec<Cell>>,
-    pub offset: Option<Vector2<f64>>,
+/// What keeps the window redrawing at full rate, with or without input.
+#[derive(Debug, Clone, Copy, Default)]
+struct Activity {
+    playing: bool,
+    /// The camera is still moving after being flung
+    gliding: bool,
+    /// A pan key is held down
+    key_panning: bool,
+    worker_busy: bool,
+    benchmarking: bool,
+    /// Edits are waiting for the worker to finish
+    input_queued: bool,
 }
 
-impl std::ops::AddAssign<StateChanges> for StateChanges {
-    fn add_assign(&mut self, other: StateChanges) {
-        if other.grid_size.is_some() {
-            self.grid_size = other.grid_size
-        };
-        if other.cells.is_some() {
-            self.cells = other.cells
-        };
-        if other.offset.is_some() {
-            self.offset = other.offset
-        };
-    }
+/// Whether the window only needs to redraw in response to events, because
+/// there is no `activity` and there has been no input for `timeout`.
+fn can_power_save(activity: Activity, timeout: Option<Duration>, idle: Duration) -> bool {
+    let Activity {
+        playing,
+        gliding,
+        key_panning,
+        worker_busy,
+        benchmarking,
+        input_queued,
+    } = activity;
+    let active = playing || gliding || key_panning || worker_busy || benchmarking || input_queued;
+    !active && idle_expired(timeout, idle)
 }
 
-pub enum LoopState {
-    Playing { last_update: Instant },
-    Stopped,
+/// Counts the ticks of deterministic mode toward each generation.
+#[derive(Debug, Default)]
+struct TickPacer {
+    /// How many ticks auto-play takes per generation. `None` paces auto-play
+    /// by the clock instead.
+    per_step: Option<u32>,
+    /// Ticks since the last generation
+    count: u32,
 }
 
-impl LoopState {
-    fn new() -> Self {
-        Self::Stopped
+impl TickPacer {
+    fn set_per_step(&mut self, per_step: Option<u32>) {
+        self.per_step = per_step.map(|t| t.max(1));
+        self.count = 0;
     }
 
-    #[allow(dead_code)]
-    fn should_step(&self, interval: &Duration) -> bool {
-        if let Self::Playing { last_update } = self {
-            last_update.elapsed() >= *interval
+    /// Count a tick, returning whether it completes a generation. Ticks only
+    /// count during auto-play in deterministic mode.
+    fn tick(&mut self, playing: bool) -> bool {
+        let Some(per_step) = self.per_step.filter(|_| playing) else {
+            return false;
+        };
+        self.count += 1;
+        if self.count >= per_step {
+            self.count = 0;
+            true
         } else {
             false
         }
     }
+}
 
-    /// Updates the `last_update` field if playing.
-    /// Otherwise, this is a no-op
-    fn update(&mut self, interval: &Duration) -> bool {
-        if let Self::Playing { last_update } = self {
-            if last_update.elapsed() >= *interval {
-                *self = Self::Playing {
-                    last_update: Instant::now(),
-                };
-                true
-            } else {
-                false
-            }
-        } else {
-            false
-        }
+/// When the next step is due while the step key is held, after one that was
+/// due at `due` was taken at `now`. Steps that came due while a slow one was
+/// computed are skipped.
+fn next_held_step(due: Instant, interval: Duration, now: Instant) -> Instant {
+    (due + interval).max(now)
+}
+
+/// Add a point to the end of a trail, dropping the oldest ones to keep it to
+/// `length` points.
+fn push_to_trail(trail: &mut VecDeque<Vector2<f64>>, point: Vector2<f64>, length: usize) {
+    trail.push_back(point);
+    while trail.len() > length {
+        trail.pop_front();
     }
+}
 
-    fn is_playing(&self) -> bool {
-        match self {
-            Self::Stopped => false,
-            Self::Playing { .. } => true,
-        }
+/// How many auto-play steps are waiting once `due` more have come due, when
+/// `pending` were waiting before. Unless catching up, at most one step waits
+/// and the rest are skipped.
+fn queue_due_steps(pending: u32, due: u32, catch_up: bool) -> u32 {
+    if catch_up {
+        (pending + due).min(MAX_CATCH_UP_STEPS)
+    } else {
+        due.min(1)
     }
 }
 
-enum DragState {
-    Dragging { prev_pos: Vector2<f64> },
-    NotDragging,
+/// The auto-play interval to start with, which is longer when steps are
+/// computed on the main thread.
+fn default_interval(worker_degraded: bool) -> Duration {
+    if worker_degraded {
+        DEGRADED_INTERVAL
+    } else {
+        DEFAULT_INTERVAL
+    }
 }
 
-#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
-enum QueueAction {
-    Clear,
-    Toggle(Vector2<i32>),
-    #[cfg(feature = "saving")]
-    Load(SaveGame),
+/// Raise an auto-play interval to at least `MIN_INTERVAL`, so that a zero
+/// interval can't make the loop step without end.
+pub fn clamp_interval(interval: Duration) -> Duration {
+    interval.max(MIN_INTERVAL)
 }
 
-fn to_cell(cell: Vector2<i32>, grid_size: f32) -> Cell {
-    let cell = Vector2::new(
-        cell.x as f32 * grid_size + grid_size / 2.0,
-        cell.y as f32 * grid_size + grid_size / 2.0,
-    );
-    Cell {
-        // location: [cell.x - pan.x as f32, cell.y - (pan.y as f32)],
-        location: [cell.x, cell.y],
-    }
+/// The auto-play interval after speeding up or slowing down by `factor` from
+/// `interval`, clamped like any other interval.
+fn changed_interval(interval: Duration, factor: f32, faster: bool) -> Duration {
+    clamp_interval(if faster {
+        interval.div_f32(factor)
+    } else {
+        interval.mul_f32(factor)
+    })
 }
 
-fn get_adjacent(coords: &Vector2<i32>) -> [Vector2<i32>; 8] {
-    [
-        [coords.x - 1, coords.y - 1].into(),
-        [coords.x - 1, coords.y + 1].into(),
-        [coords.x - 1, coords.y].into(),
-        [coords.x, coords.y - 1].into(),
-        [coords.x, coords.y + 1].into(),
-        [coords.x + 1, coords.y].into(),
-        [coords.x + 1, coords.y - 1].into(),
-        [coords.x + 1, coords.y + 1].into(),
-    ]
+/// The cells that would be born and the cells that would die if `cells` were
+/// stepped, as `(births, deaths)`.
+fn step_changes(
+    cells: &LivingList,
+    rules: &Rules,
+    bounds: &WorldBounds,
+) -> (LivingList, LivingList) {
+    let next = compute_step(cells, rules, bounds);
+    let births = next.difference(cells).cloned().collect();
+    let deaths = cells.difference(&next).cloned().collect();
+    (births, deaths)
 }
 
-fn find_cell_num(
-    size: PhysicalSize<u32>,
-    position: Vector2<f64>,
-    offset: Vector2<f64>,
-    grid_size: f32,
-) -> Vector2<i32> {
-    let aspect_ratio = size.width as f64 / size.height as f64;
-    let shift_amount = (size.width as f64 - size.height as f64) / 2.0;
-    let x_shifted = position.x - shift_amount;
-    let x_scaled = x_shifted * aspect_ratio;
-    let position_scaled = Vector2::<f64>::scale(
-        Vector2::new(x_scaled, position.y),
-        Vector2::new((size.width as f64).recip(), (size.height as f64).recip()),
-    );
-    let final_position = (position_scaled / grid_size.into()) + (offset / grid_size as f64);
-    Vector2::new(
-        final_position.x.floor() as i32,
-        final_position.y.floor() as i32,
-    )
-}
-
-fn compute_step(prev: &LivingList) -> LivingList {
+/// Compute the next generation by counting the neighbors of every cell next
+/// to a living one. This is slower than stepping in tiles, but handles any
+/// bounds.
+fn step_by_counts(
+    prev: &LivingList,
+    rules: &Rules,
+    bounds: &WorldBounds,
+    cancel: &Cancel,
+) -> Option<LivingList> {
+    let counts = neighbor_counts(prev, bounds);
+    // Counting is most of the work
+    if cancel.is_cancelled() {
+        return None;
+    }
+    let mut next: LivingList = counts
+        .iter()
+        .filter(|(coords, count)| rules.next_state(prev.contains(coords), **count))
+        .map(|(coords, _count)| *coords)
+        .collect();
+    // Cells without living neighbors aren't counted at all
+    if rules.survival[0] {
+        next.extend(prev.iter().filter(|cell| !counts.contains_key(cell)));
+    }
+    Some(next)
+}
+
+/// Count the living neighbors of every cell that has any, wrapping around the
+/// edges of a torus.
+fn neighbor_counts(cells: &LivingList, bounds: &WorldBounds) -> FxHashMap<Vector2<i32>, u32> {
     let mut adjacency_rec: FxHashMap<Vector2<i32>, u32> = FxHashMap::default();
+    let mut count = |j| *adjacency_rec.entry(j).or_insert(0) += 1;
 
-    for i in prev.iter() {
-        for j in get_adjacent(i) {
-            if let Some(c) = adjacency_rec.get(&j) {
-                adjacency_rec.insert(j, *c + 1);
-            } else {
-                adjacency_rec.insert(j, 1);
+    for i in cells.iter() {
+        match *bounds {
+            WorldBounds::Infinite => get_adjacent(i).into_iter().for_each(&mut count),
+            WorldBounds::Torus { width, height } => {
+                adjacent_wrapped(i, width, height).into_iter().for_each(&mut count)
             }
         }
     }
 
     adjacency_rec
+}
+
+/// The heatmap tint of every living cell and every cell next to one, by how
+/// many living neighbors it has. Living cells with none get the zero tint.
+fn neighbor_count_tints(cells: &LivingList, bounds: &WorldBounds) -> Vec<(Vector2<i32>, [f32; 4])> {
+    let counts = neighbor_counts(cells, bounds);
+    let isolated = cells.iter().filter(|c| !counts.contains_key(c)).map(|c| (*c, 0));
+    counts
+        .iter()
+        .map(|(c, n)| (*c, *n))
+        .chain(isolated)
+        .map(|(c, n)| (c, NEIGHBOR_COUNT_TINTS[n as usize]))
+        .collect()
+}
+
+/// Add `cells`, translated by `at`, to the board, leaving out any outside its
+/// bounds. Returns the cells that weren't already alive.
+fn stamp_cells(
+    board: &mut LivingList,
+    cells: LivingList,
+    at: Vector2<i32>,
+    bounds: &WorldBounds,
+) -> Vec<Vector2<i32>> {
+    cells
         .into_iter()
-        .filter(|(coords, count)| alive_rules(count, prev, coords))
-        .map(|(coords, _count)| coords)
+        .map(|c| c + at)
+        .filter(|c| bounds.contains(*c) && board.insert(*c))
         .collect()
 }
 
-#[inline(always)]
-fn alive_rules(count: &u32, prev: &LivingList, coords: &Vector2<i32>) -> bool {
-    3 == *count || (2 == *count && prev.contains(coords))
+/// The population after flipping every cell from `min` to `max`, inclusive.
+fn inverted_population(cells: &LivingList, min: Vector2<i32>, max: Vector2<i32>) -> usize {
+    let area = (max.x - min.x + 1) as usize * (max.y - min.y + 1) as usize;
+    let inside = |c: &&Vector2<i32>| c.x >= min.x && c.x <= max.x && c.y >= min.y && c.y <= max.y;
+    let alive_inside = cells.iter().filter(inside).count();
+    cells.len() - alive_inside + (area - alive_inside)
+}
+
+/// Flip every cell from `min` to `max`, inclusive, returning the edits as made
+/// in generation `step`.
+fn invert_region(
+    cells: &mut LivingList,
+    min: Vector2<i32>,
+    max: Vector2<i32>,
+    step: u64,
+) -> Vec<Toggle> {
+    let mut toggles = Vec::new();
+    for y in min.y..=max.y {
+        for x in min.x..=max.x {
+            let cell = Vector2::new(x, y);
+            let became_alive = !cells.remove(&cell);
+            if became_alive {
+                cells.insert(cell);
+            }
+            toggles.push(Toggle {
+                step,
+                cell,
+                became_alive,
+            });
+        }
+    }
+    toggles
+}
+
+/// Drop the population history after `generation`, given that its last entry
+/// is for `step_count`. The history doesn't have to start at generation 0, as
+/// after loading a save. If `generation` is from before the history starts,
+/// it's replaced by `population` alone.
+fn rewind_history(history: &mut Vec<usize>, step_count: u64, generation: u64, population: usize) {
+    let first = (step_count + 1).saturating_sub(history.len() as u64);
+    history.truncate((generation + 1).saturating_sub(first) as usize);
+    if history.is_empty() {
+        history.push(population);
+    }
 }
 
 impl Drop for GameState {
@@ -766,6 +3924,7 @@ impl Drop for GameState {
             // Terminate the processing thread
             let mut noti_lock = self.thread_data.shared.notification.lock().unwrap();
             *noti_lock = StepThreadNotification::Exit;
+            self.thread_data.shared.condvar.notify_all();
         }
 
         // Write the save file to the disk
@@ -775,3 +3934,661 @@ impl Drop for GameState {
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(list: &[(i32, i32)]) -> LivingList {
+        list.iter().map(|&c| c.into()).collect()
+    }
+
+    /// A glider heading toward +x and +y, one cell every four generations.
+    fn glider() -> LivingList {
+        patterns::parse_rle("bo$2bo$3o!").unwrap()
+    }
+
+    /// A board stepped without a window, counting how often its cells are
+    /// shown.
+    #[derive(Default)]
+    struct Board {
+        cells: LivingList,
+        living_cell_count: usize,
+        history: Vec<usize>,
+        step_count: u64,
+        shown: u32,
+    }
+
+    impl Generations for Board {
+        fn step_once(&mut self) -> usize {
+            let next = compute_step(&self.cells, &Rules::CONWAY, &WorldBounds::Infinite);
+            record_generation(
+                &mut self.cells,
+                &mut self.living_cell_count,
+                &mut self.history,
+                &mut self.step_count,
+                next,
+            );
+            self.living_cell_count
+        }
+
+        fn show_cells(&mut self) {
+            self.shown += 1;
+        }
+    }
+
+    #[test]
+    fn stepping_n_records_every_generation() {
+        let mut board = Board {
+            cells: glider(),
+            living_cell_count: 5,
+            history: vec![5],
+            ..Default::default()
+        };
+        step_n(&mut board, 8, None);
+        assert_eq!(board.step_count, 8);
+        assert_eq!(board.living_cell_count, 5);
+        assert_eq!(board.history, [5; 9]);
+        assert_eq!(board.cells, glider().iter().map(|c| *c + Vector2::new(2, 2)).collect());
+        assert_eq!(board.shown, 1);
+
+        // The R-pentomino grows to 7 cells in two generations
+        let mut board = Board {
+            cells: patterns::parse_rle("b2o$2o$bo!").unwrap(),
+            living_cell_count: 5,
+            history: vec![5],
+            ..Default::default()
+        };
+        step_n(&mut board, 100, Some(7));
+        assert_eq!(board.step_count, 2);
+        assert_eq!(board.history, [5, 6, 7]);
+        assert_eq!(board.shown, 1);
+    }
+
+    #[test]
+    fn glider_wraps_around_torus() {
+        let bounds = WorldBounds::Torus { width: 8, height: 8 };
+        let wrap = |c: Vector2<i32>| Vector2::new(c.x.rem_euclid(8), c.y.rem_euclid(8));
+        let start: LivingList = glider().iter().map(|c| *c + Vector2::new(5, 2)).collect();
+
+        let mut cells = start.clone();
+        for _ in 0..8 {
+            cells = compute_step(&cells, &Rules::CONWAY, &bounds);
+        }
+        // Moved two cells past its start, which takes it over the right edge
+        let expected: LivingList = start.iter().map(|c| wrap(*c + Vector2::new(2, 2))).collect();
+        assert_eq!(cells, expected);
+        assert!(cells.iter().any(|c| c.x == 0));
+        assert!(cells.iter().all(|c| bounds.contains(*c)));
+    }
+
+    #[test]
+    fn blinker_returns_after_even_steps() {
+        // What `step_n` does for each generation, without a window to build a
+        // `GameState` for
+        let blinker = patterns::parse_rle("3o!").unwrap();
+        for bounds in [WorldBounds::Infinite, WorldBounds::Torus { width: 5, height: 5 }] {
+            let mut cells = blinker.clone();
+            for step in 1..=100 {
+                cells = compute_step(&cells, &Rules::CONWAY, &bounds);
+                assert_eq!(cells.len(), 3);
+                assert_eq!(cells == blinker, step % 2 == 0);
+            }
+            assert_eq!(cells, blinker);
+        }
+    }
+
+    #[test]
+    fn rewind_history_from_start() {
+        let mut history = vec![0, 5, 6, 7];
+        rewind_history(&mut history, 3, 1, 5);
+        assert_eq!(history, [0, 5]);
+    }
+
+    #[test]
+    fn rewind_history_after_load() {
+        // Loaded at generation 500, edited, then stepped three times
+        let mut history = vec![12, 14, 13, 15];
+        rewind_history(&mut history, 503, 500, 12);
+        assert_eq!(history, [12]);
+
+        // Undoing the load itself goes back before the history starts
+        rewind_history(&mut history, 500, 40, 9);
+        assert_eq!(history, [9]);
+    }
+
+    #[test]
+    fn loop_state_steps_once_at_interval() {
+        let manual = clock::ManualClock::new();
+        let clock = Clock::Manual(manual.clone());
+        let interval = Duration::from_millis(100);
+        let mut loop_state = LoopState::Playing { last_update: clock.now() };
+
+        manual.advance(interval - Duration::from_nanos(1));
+        assert_eq!(loop_state.update(&interval, clock.now()), 0);
+        assert!(!loop_state.should_step(&interval, clock.now()));
+
+        manual.advance(Duration::from_nanos(1));
+        assert!(loop_state.should_step(&interval, clock.now()));
+        assert_eq!(loop_state.update(&interval, clock.now()), 1);
+        // The step is only counted once
+        assert_eq!(loop_state.update(&interval, clock.now()), 0);
+
+        let mut stopped = LoopState::Stopped;
+        manual.advance(interval * 3);
+        assert_eq!(stopped.update(&interval, clock.now()), 0);
+    }
+
+    #[test]
+    fn benchmark_times_steps_by_clock() {
+        let manual = clock::ManualClock::new();
+        let clock = Clock::Manual(manual.clone());
+        let mut benchmark = Benchmark::new(2);
+        for millis in [3, 5] {
+            assert!(benchmark.wants_step());
+            benchmark.start_step(clock.now());
+            manual.advance(Duration::from_millis(millis));
+            benchmark.finish_step(clock.now());
+        }
+        let summary = benchmark.summary(7).unwrap();
+        assert_eq!(summary.min, Duration::from_millis(3));
+        assert_eq!(summary.max, Duration::from_millis(5));
+        assert_eq!(summary.avg, Duration::from_millis(4));
+    }
+
+    #[test]
+    fn step_changes_of_blinker() {
+        let blinker = patterns::parse_rle("3o!").unwrap();
+        let before = blinker.clone();
+        let (births, deaths) = step_changes(&blinker, &Rules::CONWAY, &WorldBounds::Infinite);
+        assert_eq!(births, cells(&[(1, -1), (1, 1)]));
+        assert_eq!(deaths, cells(&[(0, 0), (2, 0)]));
+        assert_eq!(blinker, before);
+    }
+
+    #[test]
+    fn living_neighbors_around_block() {
+        let block = cells(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+        let count = |x, y| living_neighbors(&block, Vector2::new(x, y), &WorldBounds::Infinite);
+        assert_eq!(count(0, 0), 3);
+        // Diagonal to one corner
+        assert_eq!(count(-1, -1), 1);
+        // Beside one side
+        assert_eq!(count(2, 0), 2);
+        assert_eq!(count(5, 5), 0);
+
+        let lone = cells(&[(3, 3)]);
+        assert_eq!(living_neighbors(&lone, Vector2::new(3, 3), &WorldBounds::Infinite), 0);
+        // Across the edge of a torus
+        let torus = WorldBounds::Torus { width: 4, height: 4 };
+        assert_eq!(living_neighbors(&lone, Vector2::new(0, 0), &torus), 1);
+    }
+
+    #[cfg(feature = "native_threads")]
+    #[test]
+    fn worker_runs_ahead_until_channel_is_full() {
+        for capacity in [0, 2] {
+            let (tx, rx) = mpsc::sync_channel(capacity);
+            let shared = Arc::new(SharedThreadData {
+                notification: Mutex::new(StepThreadNotification::Compute {
+                    cells: patterns::parse_rle("3o!").unwrap(),
+                    epoch: 0,
+                    ahead: true,
+                    gpu: None,
+                    rules: Rules::CONWAY,
+                    bounds: WorldBounds::Infinite,
+                }),
+                condvar: Condvar::new(),
+                computing: AtomicBool::new(true),
+                epoch: AtomicU64::new(0),
+                computed: AtomicU64::new(0),
+            });
+            let worker = spawn_worker(Arc::clone(&shared), tx);
+
+            // Wait for the channel to fill and the worker to be holding one
+            // more generation, then outdate the board so it stops after the
+            // send it's blocked on
+            let held = capacity as u64 + 1;
+            while shared.computed.load(atomic::Ordering::Relaxed) < held {
+                std::thread::yield_now();
+            }
+            shared.epoch.store(1, atomic::Ordering::Relaxed);
+            let mut results = Vec::new();
+            while results.len() < capacity + 1 {
+                results.push(rx.recv().unwrap());
+            }
+            while shared.computing.load(atomic::Ordering::Relaxed) {
+                std::thread::yield_now();
+            }
+            assert!(rx.try_recv().is_err(), "capacity {capacity}");
+            assert_eq!(shared.computed.load(atomic::Ordering::Relaxed), held);
+            assert!(results.iter().all(|r| r.epoch == 0 && r.cells.len() == 3));
+
+            *shared.notification.lock().unwrap() = StepThreadNotification::Exit;
+            shared.condvar.notify_all();
+            worker.join().unwrap();
+        }
+    }
+
+    #[cfg(feature = "native_threads")]
+    #[test]
+    fn clearing_abandons_a_step_in_flight() {
+        // Big enough that a step takes far longer than cancelling it
+        let soup = benchmark::random_board_seeded(1500, 0.4, 1521);
+        let started = Instant::now();
+        compute_step(&soup, &Rules::CONWAY, &WorldBounds::Infinite);
+        let full_step = started.elapsed();
+
+        let (tx, rx) = mpsc::sync_channel(1);
+        let shared = Arc::new(SharedThreadData {
+            notification: Mutex::new(StepThreadNotification::Compute {
+                cells: soup,
+                epoch: 0,
+                ahead: false,
+                gpu: None,
+                rules: Rules::CONWAY,
+                bounds: WorldBounds::Infinite,
+            }),
+            condvar: Condvar::new(),
+            computing: AtomicBool::new(true),
+            epoch: AtomicU64::new(0),
+            computed: AtomicU64::new(0),
+        });
+        let worker = spawn_worker(Arc::clone(&shared), tx);
+
+        // Wait for the worker to pick up the step, then clear the board the
+        // way `clear` does
+        while matches!(
+            *shared.notification.lock().unwrap(),
+            StepThreadNotification::Compute { .. }
+        ) {
+            std::thread::yield_now();
+        }
+        let cleared = Instant::now();
+        shared.epoch.fetch_add(1, atomic::Ordering::Relaxed);
+
+        // The worker gives up partway instead of finishing and sending the
+        // stale generation
+        while shared.computing.load(atomic::Ordering::Relaxed) {
+            std::thread::yield_now();
+        }
+        let stopped = cleared.elapsed();
+        assert!(stopped < full_step / 2, "took {stopped:?} of a {full_step:?} step");
+        assert!(rx.try_recv().is_err());
+
+        *shared.notification.lock().unwrap() = StepThreadNotification::Exit;
+        shared.condvar.notify_all();
+        worker.join().unwrap();
+    }
+
+    /// Auto-play for one second at 10 steps per second, checking every 10ms,
+    /// with the worker too busy to start a step for the first half. Returns
+    /// the number of steps taken.
+    fn steps_with_slow_worker(catch_up: bool) -> u32 {
+        let manual = clock::ManualClock::new();
+        let clock = Clock::Manual(manual.clone());
+        let interval = Duration::from_millis(100);
+        let frame = Duration::from_millis(10);
+        let mut loop_state = LoopState::Playing { last_update: clock.now() };
+        let mut pending = 0;
+        let mut taken = 0;
+        for i in 1..=100 {
+            manual.advance(frame);
+            let due = loop_state.update(&interval, clock.now());
+            pending = queue_due_steps(pending, due, catch_up);
+            let worker_busy = i <= 50;
+            // The worker is fast once it's free, so it finishes within a frame
+            if pending > 0 && !worker_busy {
+                pending -= 1;
+                taken += 1;
+            }
+        }
+        taken
+    }
+
+    #[test]
+    fn catching_up_after_slow_worker() {
+        assert_eq!(steps_with_slow_worker(true), 10);
+        // The steps that came due while the worker was busy are skipped
+        assert_eq!(steps_with_slow_worker(false), 5);
+    }
+
+    #[cfg(feature = "saving")]
+    #[test]
+    fn stable_save_names() {
+        assert_eq!(stable_save_name(1, 40), "Still life at generation 40");
+        assert_eq!(stable_save_name(2, 7), "Period 2 at generation 7");
+    }
+
+    #[test]
+    fn speed_keys_multiply_rate() {
+        let mut interval = Duration::from_secs(1);
+        for expected in [500, 250, 125] {
+            interval = changed_interval(interval, 2.0, true);
+            assert_eq!(interval, Duration::from_millis(expected));
+        }
+        interval = changed_interval(interval, 2.0, false);
+        assert_eq!(interval, Duration::from_millis(250));
+
+        // Speeding up can't go past the fastest interval, however many presses
+        for _ in 0..100 {
+            interval = changed_interval(interval, 4.0, true);
+        }
+        assert_eq!(interval, MIN_INTERVAL);
+    }
+
+    #[cfg(feature = "saving")]
+    #[test]
+    fn save_navigation_wraps_when_enabled() {
+        let mut current = None;
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            current = adjacent_save(current, 3, true, true);
+            seen.extend(current);
+        }
+        assert_eq!(seen, [0, 1, 2, 0]);
+        assert_eq!(adjacent_save(Some(0), 3, false, true), Some(2));
+
+        assert_eq!(adjacent_save(Some(2), 3, true, false), None);
+        assert_eq!(adjacent_save(Some(0), 3, false, false), None);
+        assert_eq!(adjacent_save(Some(2), 3, false, false), Some(1));
+        // The last loaded save was deleted
+        assert_eq!(adjacent_save(Some(5), 3, false, false), Some(2));
+    }
+
+    #[test]
+    fn wrapped_neighbors_stay_on_the_torus() {
+        for x in 0..5 {
+            for y in 0..5 {
+                let neighbors = adjacent_wrapped(&Vector2::new(x, y), 5, 5);
+                assert_eq!(neighbors.len(), 8);
+                for n in &neighbors {
+                    assert!((0..5).contains(&n.x) && (0..5).contains(&n.y));
+                    let dx = (n.x - x).rem_euclid(5);
+                    let dy = (n.y - y).rem_euclid(5);
+                    assert!(matches!(dx, 0 | 1 | 4) && matches!(dy, 0 | 1 | 4));
+                }
+            }
+        }
+        let corner = adjacent_wrapped(&Vector2::new(0, 0), 5, 5);
+        assert!(corner.contains(&Vector2::new(4, 4)));
+        assert!(corner.contains(&Vector2::new(1, 4)));
+
+        assert!(adjacent_wrapped(&Vector2::new(0, 0), 1, 1).is_empty());
+        let mut small = adjacent_wrapped(&Vector2::new(0, 0), 2, 2);
+        small.sort_by_key(|c| (c.x, c.y));
+        assert_eq!(small, [Vector2::new(0, 1), Vector2::new(1, 0), Vector2::new(1, 1)]);
+    }
+
+    #[test]
+    fn idle_timeout_follows_last_input() {
+        let manual = clock::ManualClock::new();
+        let clock = Clock::Manual(manual.clone());
+        let timeout = Some(Duration::from_secs(60));
+        let mut last_input = clock.now();
+
+        manual.advance(Duration::from_secs(59));
+        assert!(!idle_expired(timeout, clock.since(last_input)));
+        // Input restarts the timer
+        last_input = clock.now();
+        manual.advance(Duration::from_secs(59));
+        assert!(!idle_expired(timeout, clock.since(last_input)));
+        manual.advance(Duration::from_secs(1));
+        assert!(idle_expired(timeout, clock.since(last_input)));
+
+        manual.advance(Duration::from_secs(3600));
+        assert!(!idle_expired(None, clock.since(last_input)));
+    }
+
+    #[test]
+    fn neighbor_heatmap_around_blinker() {
+        let blinker = cells(&[(-1, 0), (0, 0), (1, 0)]);
+        let mut alone = blinker.clone();
+        alone.insert(Vector2::new(10, 10));
+        let tints: FxHashMap<_, _> =
+            neighbor_count_tints(&alone, &WorldBounds::Infinite).into_iter().collect();
+        for ((x, y), count) in [
+            ((0, 0), 2),
+            ((-1, 0), 1),
+            ((1, 0), 1),
+            ((0, 1), 3),
+            ((0, -1), 3),
+            ((-1, 1), 2),
+            ((2, 0), 1),
+            ((2, 1), 1),
+            ((10, 10), 0),
+        ] {
+            assert_eq!(tints[&Vector2::new(x, y)], NEIGHBOR_COUNT_TINTS[count], "({x}, {y})");
+        }
+        assert!(!tints.contains_key(&Vector2::new(3, 0)));
+        // The 15 cells around the blinker, and the lone cell with its 8
+        assert_eq!(tints.len(), 24);
+    }
+
+    #[test]
+    fn glide_decelerates_to_rest() {
+        let manual = clock::ManualClock::new();
+        let clock = Clock::Manual(manual.clone());
+        let mut glide = Glide {
+            velocity: Vector2::new(2.0, 0.0),
+            last_update: clock.now(),
+        };
+        let mut traveled = 0.0;
+        let mut last_moved = f64::INFINITY;
+        let mut frames = 0;
+        while !glide.has_stopped() {
+            manual.advance(Duration::from_millis(100));
+            let moved = glide.advance(clock.now());
+            assert_eq!(moved.y, 0.0);
+            assert!(moved.x > 0.0 && moved.x < last_moved);
+            last_moved = moved.x;
+            traveled += moved.x;
+            frames += 1;
+            assert!(frames < 100, "the glide should stop");
+        }
+        // It comes to rest close to velocity / friction
+        assert!((traveled - 2.0 / Glide::FRICTION).abs() < 0.01, "{traveled}");
+
+        // The distance doesn't depend on the frame rate
+        let mut once = Glide {
+            velocity: Vector2::new(2.0, 0.0),
+            last_update: clock.now(),
+        };
+        let mut twice = Glide {
+            velocity: Vector2::new(2.0, 0.0),
+            last_update: clock.now(),
+        };
+        let start = clock.now();
+        let whole = once.advance(start + Duration::from_millis(200)).x;
+        let halves = twice.advance(start + Duration::from_millis(100)).x
+            + twice.advance(start + Duration::from_millis(200)).x;
+        assert!((whole - halves).abs() < 1e-9);
+    }
+
+    #[test]
+    fn merging_a_save_keeps_the_board() {
+        let block = cells(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+        let mut board = block.clone();
+        let at = Vector2::new(0, -1);
+        let added = stamp_cells(&mut board, glider(), at, &WorldBounds::Infinite);
+        let moved: LivingList = glider().iter().map(|c| *c + at).collect();
+        assert_eq!(board, block.union(&moved).copied().collect());
+        // Two of the glider's cells land on the block
+        assert_eq!(added.len(), 3);
+        assert!(!added.contains(&Vector2::new(1, 1)));
+
+        let torus = WorldBounds::Torus { width: 3, height: 3 };
+        let mut board = block.clone();
+        stamp_cells(&mut board, glider(), Vector2::new(1, 1), &torus);
+        assert!(board.iter().all(|c| torus.contains(*c)));
+    }
+
+    #[test]
+    fn inverting_fills_and_empties_the_view() {
+        let (min, max) = (Vector2::new(-1, -1), Vector2::new(1, 1));
+        let offscreen = Vector2::new(5, 0);
+        let mut board = cells(&[(5, 0)]);
+        assert_eq!(inverted_population(&board, min, max), 10);
+
+        let toggles = invert_region(&mut board, min, max, 7);
+        assert_eq!(board.len(), 10);
+        assert!(board.contains(&offscreen));
+        assert_eq!(toggles.len(), 9);
+        assert!(toggles.iter().all(|t| t.became_alive && t.step == 7));
+
+        assert_eq!(inverted_population(&board, min, max), 1);
+        let toggles = invert_region(&mut board, min, max, 8);
+        assert_eq!(board, cells(&[(5, 0)]));
+        assert!(toggles.iter().all(|t| !t.became_alive));
+
+        // A partly filled view swaps its living and dead cells
+        let mut board = cells(&[(0, 0), (1, 1)]);
+        invert_region(&mut board, min, max, 0);
+        assert_eq!(board.len(), 7);
+        assert!(!board.contains(&Vector2::new(0, 0)) && board.contains(&Vector2::new(1, 0)));
+    }
+
+    #[test]
+    fn power_save_starts_after_delay_and_stops_on_input() {
+        let manual = clock::ManualClock::new();
+        let clock = Clock::Manual(manual.clone());
+        let after = Some(DEFAULT_POWER_SAVE_AFTER);
+        let mut last_input = clock.now();
+        // Redraws are requested every frame until the delay has passed
+        let mut frames = 0;
+        while !can_power_save(Activity::default(), after, clock.since(last_input)) {
+            manual.advance(Duration::from_millis(16));
+            frames += 1;
+        }
+        assert_eq!(frames, DEFAULT_POWER_SAVE_AFTER.as_millis().div_ceil(16));
+
+        last_input = clock.now();
+        assert!(!can_power_save(Activity::default(), after, clock.since(last_input)));
+        assert!(!can_power_save(Activity::default(), None, Duration::MAX));
+    }
+
+    #[test]
+    fn any_activity_keeps_full_rate_redraws() {
+        let after = Some(DEFAULT_POWER_SAVE_AFTER);
+        let idle = DEFAULT_POWER_SAVE_AFTER * 10;
+        assert!(can_power_save(Activity::default(), after, idle));
+
+        let busy: [fn(&mut Activity); 6] = [
+            |a| a.playing = true,
+            |a| a.gliding = true,
+            |a| a.key_panning = true,
+            |a| a.worker_busy = true,
+            |a| a.benchmarking = true,
+            |a| a.input_queued = true,
+        ];
+        for (i, set) in busy.iter().enumerate() {
+            let mut activity = Activity::default();
+            set(&mut activity);
+            assert!(!can_power_save(activity, after, idle), "{activity:?} ({i})");
+        }
+    }
+
+    #[test]
+    fn deterministic_runs_are_identical() {
+        let run = || {
+            let mut cells = benchmark::random_board_seeded(32, 0.4, 7);
+            let mut ticks = TickPacer::default();
+            ticks.set_per_step(Some(3));
+            let mut generation = 0;
+            for frame in 0..70 {
+                // Pausing for a while stops the count
+                let playing = !(30..40).contains(&frame);
+                if ticks.tick(playing) {
+                    cells = compute_step(&cells, &Rules::CONWAY, &WorldBounds::Infinite);
+                    generation += 1;
+                }
+            }
+            // Clock pacing never counts ticks
+            ticks.set_per_step(None);
+            assert!(!ticks.tick(true));
+            let snapshot =
+                SimulationSnapshot::new(&cells, generation, Rules::CONWAY, WorldBounds::Infinite);
+            serde_json::to_string(&snapshot).unwrap()
+        };
+        let first = run();
+        assert_eq!(run(), first);
+        assert!(first.contains(r#""generation":20,"#), "{first}");
+    }
+
+    #[test]
+    fn held_step_repeats_at_interval() {
+        let manual = clock::ManualClock::new();
+        let clock = Clock::Manual(manual.clone());
+        let interval = Duration::from_millis(100);
+        let pressed = clock.now();
+        let mut due = pressed + HOLD_STEP_DELAY;
+        let mut steps = Vec::new();
+        // Check every 10ms for two seconds, like frames
+        for _ in 0..200 {
+            manual.advance(Duration::from_millis(10));
+            let now = clock.now();
+            if now >= due {
+                steps.push((now - pressed).as_millis());
+                due = next_held_step(due, interval, now);
+            }
+        }
+        let expected: Vec<u128> = (0..17).map(|i| 400 + i * 100).collect();
+        assert_eq!(steps, expected);
+
+        // A slow step doesn't cause a burst to catch up
+        let late = pressed + Duration::from_millis(950);
+        assert_eq!(next_held_step(pressed + HOLD_STEP_DELAY, interval, late), late);
+    }
+
+    #[test]
+    fn centroid_trail_is_bounded() {
+        // A plus sign is centered on its middle cell
+        let plus = cells(&[(4, 2), (3, 3), (4, 3), (5, 3), (4, 4)]);
+        assert_eq!(patterns::centroid(&plus), Some(Vector2::new(4.0, 3.0)));
+        let block = cells(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+        assert_eq!(patterns::centroid(&block), Some(Vector2::new(0.5, 0.5)));
+        assert_eq!(patterns::centroid(&LivingList::default()), None);
+
+        let mut trail = VecDeque::new();
+        let mut glider = glider();
+        for _ in 0..10 {
+            push_to_trail(&mut trail, patterns::centroid(&glider).unwrap(), 5);
+            assert!(trail.len() <= 5);
+            glider = compute_step(&glider, &Rules::CONWAY, &WorldBounds::Infinite);
+        }
+        assert_eq!(trail.len(), 5);
+        // The newest centroid is a full period ahead of the oldest
+        let moved = trail[4] - trail[0];
+        assert!((moved - Vector2::new(1.0, 1.0)).magnitude() < 1e-9, "{moved:?}");
+    }
+
+    #[test]
+    fn reverting_toggles_restores_cells() {
+        let start = cells(&[(0, 0), (2, 0)]);
+        let mut board = start.clone();
+        let mut record = invert_region(&mut board, Vector2::new(0, 0), Vector2::new(1, 0), 0);
+        assert_eq!(board, cells(&[(1, 0), (2, 0)]));
+
+        // Undo the most recent edit first
+        record.pop().unwrap().revert(&mut board);
+        assert_eq!(board, cells(&[(2, 0)]));
+        record.pop().unwrap().revert(&mut board);
+        assert_eq!(board, start);
+    }
+
+    #[test]
+    fn main_thread_starts_slower() {
+        assert!(default_interval(true) > default_interval(false));
+        for degraded in [false, true] {
+            let interval = default_interval(degraded);
+            assert_eq!(clamp_interval(interval), interval);
+        }
+        // What the constructor in this build starts with
+        let expected = if cfg!(feature = "native_threads") {
+            DEFAULT_INTERVAL
+        } else {
+            DEGRADED_INTERVAL
+        };
+        assert_eq!(default_interval(WORKER_DEGRADED), expected);
+    }
+}
@@ -0,0 +1,805 @@
+use std::{fmt, io::Read};
+
+use rustc_hash::FxHashSet;
+use vec2::Vector2;
+
+use super::{rules::Rules, LivingList};
+
+pub mod apgcode;
+use apgcode::{looks_like_apgcode, parse_apgcode};
+
+/// An error produced while parsing a pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternError {
+    /// The input contained no pattern data at all.
+    Empty,
+    /// A character that isn't part of the pattern format's alphabet was found.
+    UnexpectedChar { ch: char, line: usize },
+    /// A run count wasn't followed by a tag for it to apply to.
+    DanglingCount { line: usize },
+    /// A run count was too large to be reasonable, or moved past the largest
+    /// coordinate.
+    CountTooLarge { line: usize },
+    /// The pattern had more living cells than `MAX_RLE_CELLS`.
+    TooManyCells { line: usize },
+    /// A line of a Life 1.06 pattern wasn't a pair of integers.
+    BadCoordinates { line: usize },
+    /// A JSON pattern wasn't an array of `[x, y]` pairs.
+    BadJson(String),
+    /// A pattern file couldn't be read or decompressed.
+    Unreadable(String),
+    /// A pattern file wasn't UTF-8 text.
+    NotText,
+    /// An apgcode was malformed or of an unsupported kind.
+    BadApgcode(String),
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "no pattern data found"),
+            Self::UnexpectedChar { ch, line } => {
+                write!(f, "unexpected character '{ch}' on line {line}")
+            }
+            Self::DanglingCount { line } => {
+                write!(f, "run count without a cell on line {line}")
+            }
+            Self::CountTooLarge { line } => write!(f, "run count too large on line {line}"),
+            Self::TooManyCells { line } => {
+                write!(f, "more than {MAX_RLE_CELLS} living cells by line {line}")
+            }
+            Self::BadCoordinates { line } => {
+                write!(f, "expected a pair of coordinates on line {line}")
+            }
+            Self::BadJson(e) => write!(f, "expected an array of [x, y] pairs: {e}"),
+            Self::Unreadable(e) => write!(f, "couldn't read the file: {e}"),
+            Self::NotText => write!(f, "the file isn't a text pattern"),
+            Self::BadApgcode(e) => write!(f, "bad apgcode: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+/// The most living cells an RLE pattern can have, so that a short file with
+/// huge run counts can't use up all the memory
+pub const MAX_RLE_CELLS: usize = 10_000_000;
+
+/// The header line that identifies the Life 1.06 format.
+const LIFE106_HEADER: &str = "#Life 1.06";
+/// How long lines of RLE data can be, as recommended by the format
+const RLE_LINE_LENGTH: usize = 70;
+/// The bytes every gzip file starts with.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Parse a pattern in the RLE, Life 1.06, plaintext, or JSON format, or an
+/// apgcode. JSON patterns start with `[`, Life 1.06 patterns with a Life 1.06
+/// header, plaintext patterns with a `!` comment or a row of `.` and `O`, and
+/// apgcodes are a single word with an underscore.
+pub fn parse_pattern(input: &str) -> Result<LivingList, PatternError> {
+    let first_line = input.lines().map(str::trim).find(|l| !l.is_empty());
+    if looks_like_apgcode(input) {
+        parse_apgcode(input)
+    } else if first_line.is_some_and(|l| l.starts_with('[')) {
+        parse_json(input)
+    } else if first_line.is_some_and(|l| l.starts_with(LIFE106_HEADER)) {
+        parse_life106(input)
+    } else if first_line.is_some_and(looks_like_plaintext) {
+        parse_plaintext(input)
+    } else {
+        parse_rle(input)
+    }
+}
+
+/// Parse a pattern in the Life 1.06 format, which lists the `x y` coordinates
+/// of each living cell on its own line. Unlike with RLE, the coordinates are
+/// kept as they are.
+///
+/// Lines starting with `#`, including the header, are skipped.
+pub fn parse_life106(input: &str) -> Result<LivingList, PatternError> {
+    let mut cells = FxHashSet::default();
+    let mut found_data = false;
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            found_data |= line.starts_with(LIFE106_HEADER);
+            continue;
+        }
+        if line.is_empty() {
+            continue;
+        }
+
+        let bad_coordinates = PatternError::BadCoordinates { line: i + 1 };
+        let mut coords = line.split_whitespace().map(str::parse::<i32>);
+        let (Some(Ok(x)), Some(Ok(y)), None) = (coords.next(), coords.next(), coords.next()) else {
+            return Err(bad_coordinates);
+        };
+        cells.insert(Vector2::new(x, y));
+        found_data = true;
+    }
+
+    if found_data {
+        Ok(cells)
+    } else {
+        Err(PatternError::Empty)
+    }
+}
+
+/// Write a pattern in the Life 1.06 format, with the cells sorted by row and
+/// then column.
+pub fn to_life106(cells: &LivingList) -> String {
+    let mut sorted: Vec<_> = cells.iter().collect();
+    sorted.sort_unstable_by_key(|c| (c.y, c.x));
+
+    let mut out = format!("{LIFE106_HEADER}\n");
+    for c in sorted {
+        out.push_str(&format!("{} {}\n", c.x, c.y));
+    }
+    out
+}
+
+/// Parse the contents of a pattern file in any supported format. Files that
+/// are gzipped, going by either their `.gz` extension or their first bytes,
+/// are decompressed first.
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub fn parse_pattern_file(name: &str, bytes: &[u8]) -> Result<LivingList, PatternError> {
+    let mut decompressed = Vec::new();
+    let bytes = if name.ends_with(".gz") || bytes.starts_with(&GZIP_MAGIC) {
+        flate2::read::GzDecoder::new(bytes)
+            .read_to_end(&mut decompressed)
+            .map_err(|e| PatternError::Unreadable(e.to_string()))?;
+        &decompressed
+    } else {
+        bytes
+    };
+    let text = std::str::from_utf8(bytes).map_err(|_| PatternError::NotText)?;
+    parse_pattern(text)
+}
+
+/// Whether the first line of a pattern looks like the plaintext format. RLE
+/// never starts with `!` and writes living cells as a lowercase `o`.
+fn looks_like_plaintext(first_line: &str) -> bool {
+    first_line.starts_with('!') || first_line.chars().all(|c| matches!(c, '.' | 'O' | '*'))
+}
+
+/// Parse a pattern in the plaintext (`.cells`) format, which draws each row
+/// with `.` for dead cells and `O` for living ones. The first row is at y = 0
+/// and each row starts at x = 0. `*` is accepted for living cells too.
+///
+/// Lines starting with `!` are comments. Empty lines after the first row are
+/// blank rows.
+pub fn parse_plaintext(input: &str) -> Result<LivingList, PatternError> {
+    let mut cells = FxHashSet::default();
+    let mut found_data = false;
+    let mut y = 0;
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim_end();
+        if line.starts_with('!') || (line.is_empty() && !found_data) {
+            continue;
+        }
+        for (x, ch) in line.chars().enumerate() {
+            match ch {
+                '.' => (),
+                'O' | '*' => {
+                    cells.insert(Vector2::new(x as i32, y));
+                }
+                c => return Err(PatternError::UnexpectedChar { ch: c, line: i + 1 }),
+            }
+        }
+        found_data |= !line.is_empty();
+        y += 1;
+    }
+
+    if found_data {
+        Ok(cells)
+    } else {
+        Err(PatternError::Empty)
+    }
+}
+
+/// Parse a JSON array of `[x, y]` pairs, one for each living cell. The
+/// coordinates are kept as they are.
+pub fn parse_json(input: &str) -> Result<LivingList, PatternError> {
+    let pairs: Vec<[i32; 2]> =
+        serde_json::from_str(input).map_err(|e| PatternError::BadJson(e.to_string()))?;
+    Ok(pairs.into_iter().map(|[x, y]| Vector2::new(x, y)).collect())
+}
+
+/// Write the cells as a JSON array of `[x, y]` pairs, for use in scripts.
+pub fn to_json(cells: &LivingList) -> String {
+    let mut sorted: Vec<_> = cells.iter().map(|c| [c.x, c.y]).collect();
+    sorted.sort_unstable_by_key(|[x, y]| (*y, *x));
+    serde_json::to_string(&sorted).expect("integer pairs should always serialize")
+}
+
+/// Parse a pattern in the RLE format used by most Life software. The top-left
+/// of the pattern's bounding box will be at (0, 0).
+///
+/// Comment lines (starting with `#`) and the `x = .., y = ..` header are
+/// skipped. Parsing stops at the first `!`.
+pub fn parse_rle(input: &str) -> Result<LivingList, PatternError> {
+    let mut cells = FxHashSet::default();
+    let mut x: i32 = 0;
+    let mut y: i32 = 0;
+    let mut found_data = false;
+
+    'lines: for (i, line) in input.lines().enumerate() {
+        let line_num = i + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('x') {
+            continue;
+        }
+
+        let too_large = PatternError::CountTooLarge { line: line_num };
+        let mut count: Option<i32> = None;
+        for ch in line.chars() {
+            match ch {
+                '0'..='9' => {
+                    let digit = ch.to_digit(10).unwrap() as i32;
+                    count = Some(
+                        count
+                            .unwrap_or(0)
+                            .checked_mul(10)
+                            .and_then(|v| v.checked_add(digit))
+                            .ok_or(too_large.clone())?,
+                    );
+                }
+                'b' | '.' => {
+                    x = x
+                        .checked_add(count.take().unwrap_or(1))
+                        .ok_or(too_large.clone())?;
+                    found_data = true;
+                }
+                'o' | 'A'..='X' => {
+                    let run = count.take().unwrap_or(1);
+                    // Checked before inserting so a huge run fails right away
+                    if cells.len().saturating_add(run as usize) > MAX_RLE_CELLS {
+                        return Err(PatternError::TooManyCells { line: line_num });
+                    }
+                    let end = x.checked_add(run).ok_or(too_large.clone())?;
+                    cells.extend((x..end).map(|x| Vector2::new(x, y)));
+                    x = end;
+                    found_data = true;
+                }
+                '$' => {
+                    y = y
+                        .checked_add(count.take().unwrap_or(1))
+                        .ok_or(too_large.clone())?;
+                    x = 0;
+                    found_data = true;
+                }
+                '!' => {
+                    if count.is_some() {
+                        return Err(PatternError::DanglingCount { line: line_num });
+                    }
+                    found_data = true;
+                    break 'lines;
+                }
+                c if c.is_whitespace() => (),
+                c => {
+                    return Err(PatternError::UnexpectedChar {
+                        ch: c,
+                        line: line_num,
+                    })
+                }
+            }
+        }
+        if count.is_some() {
+            return Err(PatternError::DanglingCount { line: line_num });
+        }
+    }
+
+    if found_data {
+        Ok(cells)
+    } else {
+        Err(PatternError::Empty)
+    }
+}
+
+/// Write a pattern in the RLE format, with a header giving its size and the
+/// rules it runs under. The top-left of its bounding box becomes the origin,
+/// as `parse_rle` expects.
+pub fn to_rle(cells: &LivingList, rules: &Rules) -> String {
+    let Some((min, max)) = bounding_box(cells) else {
+        return format!("x = 0, y = 0, rule = {rules}\n!\n");
+    };
+    let mut out = format!(
+        "x = {}, y = {}, rule = {rules}\n",
+        max.x - min.x + 1,
+        max.y - min.y + 1
+    );
+
+    // Each run is a count and a tag. Dead cells at the end of a row are left
+    // out, and row ends are merged with the blank rows after them.
+    let mut runs: Vec<(i32, char)> = Vec::new();
+    let mut push = |count: i32, tag: char| match runs.last_mut() {
+        Some((last, last_tag)) if *last_tag == tag => *last += count,
+        _ => runs.push((count, tag)),
+    };
+    let mut sorted: Vec<_> = cells.iter().collect();
+    sorted.sort_unstable_by_key(|c| (c.y, c.x));
+    let mut at = min;
+    for cell in sorted {
+        if cell.y > at.y {
+            push(cell.y - at.y, '$');
+            at = Vector2::new(min.x, cell.y);
+        }
+        if cell.x > at.x {
+            push(cell.x - at.x, 'b');
+        }
+        push(1, 'o');
+        at.x = cell.x + 1;
+    }
+    runs.push((1, '!'));
+
+    let mut line = String::new();
+    for (count, tag) in runs {
+        let run = match count {
+            1 => tag.to_string(),
+            _ => format!("{count}{tag}"),
+        };
+        if line.len() + run.len() > RLE_LINE_LENGTH {
+            out.push_str(&line);
+            out.push('\n');
+            line.clear();
+        }
+        line.push_str(&run);
+    }
+    out.push_str(&line);
+    out.push('\n');
+    out
+}
+
+/// Stretch a pattern by replacing each cell with a `factor` x `factor` block.
+/// Cell (x, y) becomes the block whose top-left is (x * factor, y * factor),
+/// so the population grows by `factor` squared.
+pub fn scale_pattern(cells: &LivingList, factor: u32) -> LivingList {
+    let factor = factor as i32;
+    cells
+        .iter()
+        .flat_map(|c| {
+            (0..factor).flat_map(move |dy| {
+                (0..factor).map(move |dx| Vector2::new(c.x * factor + dx, c.y * factor + dy))
+            })
+        })
+        .collect()
+}
+
+/// A function that turns or mirrors a pattern, such as `rotate_cw`
+pub type Transform = fn(&LivingList) -> LivingList;
+
+/// Rotate a pattern a quarter turn clockwise, as seen on screen, around the
+/// center of its bounding box.
+pub fn rotate_cw(cells: &LivingList) -> LivingList {
+    transform_in_place(cells, true, |rel, extent| {
+        Vector2::new(extent.y - rel.y, rel.x)
+    })
+}
+
+/// Rotate a pattern a quarter turn counter-clockwise, as seen on screen,
+/// around the center of its bounding box.
+pub fn rotate_ccw(cells: &LivingList) -> LivingList {
+    transform_in_place(cells, true, |rel, extent| {
+        Vector2::new(rel.y, extent.x - rel.x)
+    })
+}
+
+/// Mirror a pattern left to right within its bounding box.
+pub fn flip_horizontal(cells: &LivingList) -> LivingList {
+    transform_in_place(cells, false, |rel, extent| {
+        Vector2::new(extent.x - rel.x, rel.y)
+    })
+}
+
+/// Mirror a pattern top to bottom within its bounding box.
+pub fn flip_vertical(cells: &LivingList) -> LivingList {
+    transform_in_place(cells, false, |rel, extent| {
+        Vector2::new(rel.x, extent.y - rel.y)
+    })
+}
+
+/// Move each cell by `f`, which is given the cell's offset from the bounding
+/// box's top-left and the box's extent, and returns the new offset. If the
+/// box's sides are swapped by the transform, it is moved so its center stays
+/// put. An odd difference between the sides can't be split evenly, and is
+/// rounded toward zero so that undoing the transform lands in the same place.
+fn transform_in_place(
+    cells: &LivingList,
+    swaps_sides: bool,
+    f: impl Fn(Vector2<i32>, Vector2<i32>) -> Vector2<i32>,
+) -> LivingList {
+    let Some((min, max)) = bounding_box(cells) else {
+        return LivingList::default();
+    };
+    let extent = max - min;
+    let origin = if swaps_sides {
+        min + Vector2::new((extent.x - extent.y) / 2, (extent.y - extent.x) / 2)
+    } else {
+        min
+    };
+    cells.iter().map(|c| origin + f(*c - min, extent)).collect()
+}
+
+/// Get the inclusive bounding box of a set of cells as `(min, max)`, or `None`
+/// if there are no cells.
+pub fn bounding_box(cells: &LivingList) -> Option<(Vector2<i32>, Vector2<i32>)> {
+    let mut iter = cells.iter();
+    let first = *iter.next()?;
+    Some(iter.fold((first, first), |(min, max), c| {
+        (
+            Vector2::new(min.x.min(c.x), min.y.min(c.y)),
+            Vector2::new(max.x.max(c.x), max.y.max(c.y)),
+        )
+    }))
+}
+
+/// Get the average position of a set of cells, or `None` if there are none.
+pub fn centroid(cells: &LivingList) -> Option<Vector2<f64>> {
+    if cells.is_empty() {
+        return None;
+    }
+    let sum: Vector2<f64> = cells.iter().map(|c| c.map(|c| c as f64)).sum();
+    Some(sum / cells.len() as f64)
+}
+
+/// Count the groups of living cells that touch each other, including
+/// diagonally.
+pub fn count_clusters(cells: &LivingList) -> usize {
+    clusters(cells).len()
+}
+
+/// Split a set of cells into groups that touch each other, including
+/// diagonally.
+pub fn clusters(cells: &LivingList) -> Vec<LivingList> {
+    let mut unvisited = cells.clone();
+    let mut stack = Vec::new();
+    let mut clusters = Vec::new();
+    while let Some(&start) = unvisited.iter().next() {
+        unvisited.remove(&start);
+        stack.push(start);
+        let mut cluster = FxHashSet::default();
+        while let Some(cell) = stack.pop() {
+            cluster.insert(cell);
+            for neighbor in super::get_adjacent(&cell) {
+                if unvisited.remove(&neighbor) {
+                    stack.push(neighbor);
+                }
+            }
+        }
+        clusters.push(cluster);
+    }
+    clusters
+}
+
+/// Turn an image into a pattern with living cells where its pixels are
+/// darker than `threshold`, from 0 to 255. Transparent pixels are never alive.
+/// Images larger than `max_size` pixels in either direction are scaled down
+/// first, and each remaining pixel becomes one cell.
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub fn from_image(image: &image::DynamicImage, threshold: u8, max_size: u32) -> LivingList {
+    let image = if image.width() > max_size || image.height() > max_size {
+        image.resize(max_size, max_size, image::imageops::FilterType::Triangle)
+    } else {
+        image.clone()
+    };
+
+    image
+        .to_luma_alpha8()
+        .enumerate_pixels()
+        .filter(|(_, _, p)| is_dark(**p, threshold))
+        .map(|(x, y, _)| Vector2::new(x as i32, y as i32))
+        .collect()
+}
+
+/// Turn the dead cell texture into living cells across the inclusive range of
+/// cells from `min` to `max`. The texture is repeated with one texel per cell,
+/// and dark texels become living cells like with `from_image`.
+pub fn etch_background(threshold: u8, min: Vector2<i32>, max: Vector2<i32>) -> LivingList {
+    let texture = image::load_from_memory(include_bytes!("../../rsc/dead.png"))
+        .expect("the dead cell texture should be a valid image")
+        .to_luma_alpha8();
+    let (width, height) = (texture.width() as i32, texture.height() as i32);
+
+    let mut cells = FxHashSet::default();
+    for y in min.y..=max.y {
+        for x in min.x..=max.x {
+            let texel = *texture.get_pixel(x.rem_euclid(width) as u32, y.rem_euclid(height) as u32);
+            if is_dark(texel, threshold) {
+                cells.insert(Vector2::new(x, y));
+            }
+        }
+    }
+    cells
+}
+
+/// Whether a pixel is opaque enough and darker than `threshold`.
+fn is_dark(pixel: image::LumaA<u8>, threshold: u8) -> bool {
+    let [luma, alpha] = pixel.0;
+    alpha >= 128 && luma < threshold
+}
+
+/// Rasterize a set of cells into a square grid of booleans, in row-major
+/// order, for drawing thumbnails. The grid is at most `max_size` pixels wide;
+/// larger patterns are scaled down so that several cells may share one pixel.
+/// Returns the side length of the grid along with the pixels.
+pub fn rasterize(cells: &LivingList, max_size: usize) -> (usize, Vec<bool>) {
+    let Some((min, max)) = bounding_box(cells) else {
+        return (0, Vec::new());
+    };
+
+    let span = (max.x - min.x + 1).max(max.y - min.y + 1) as usize;
+    let size = span.min(max_size).max(1);
+    let scale = size as f64 / span as f64;
+    let mut pixels = vec![false; size * size];
+    for c in cells {
+        let px = (((c.x - min.x) as f64 * scale) as usize).min(size - 1);
+        let py = (((c.y - min.y) as f64 * scale) as usize).min(size - 1);
+        pixels[py * size + px] = true;
+    }
+    (size, pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use vec2::Vector2;
+
+    use super::*;
+
+    const ACORN_CELLS: &str = "\
+!Name: Acorn
+!A methuselah that takes 5206 generations to stabilize.
+.O.....
+...O...
+OO..OOO
+";
+
+    #[test]
+    fn plaintext_acorn() {
+        let cells = parse_plaintext(ACORN_CELLS).unwrap();
+        assert_eq!(cells.len(), 7);
+        assert!(cells.contains(&Vector2::new(1, 0)));
+        assert!(cells.contains(&Vector2::new(6, 2)));
+        assert_eq!(parse_pattern(ACORN_CELLS).unwrap(), cells);
+    }
+
+    #[test]
+    fn plaintext_without_comments() {
+        let cells = parse_pattern(".O\n..O\nOOO").unwrap();
+        assert_eq!(cells.len(), 5);
+        assert!(matches!(
+            parse_plaintext("!only a comment"),
+            Err(PatternError::Empty)
+        ));
+        assert!(matches!(
+            parse_plaintext(".O.\n.x."),
+            Err(PatternError::UnexpectedChar { ch: 'x', line: 2 })
+        ));
+    }
+
+    fn glider() -> LivingList {
+        parse_rle("bo$2bo$3o!").unwrap()
+    }
+
+    #[test]
+    fn glider_rotated_four_times() {
+        let glider = glider();
+        let mut rotated = glider.clone();
+        for _ in 0..4 {
+            rotated = rotate_cw(&rotated);
+            assert_eq!(rotated.len(), glider.len());
+        }
+        assert_eq!(rotated, glider);
+        assert_ne!(rotate_cw(&glider), glider);
+    }
+
+    #[test]
+    fn transforms_round_trip() {
+        // Wider than it is tall, so that rotating moves the bounding box
+        let cells = parse_rle("5o$o!").unwrap();
+        assert_eq!(rotate_ccw(&rotate_cw(&cells)), cells);
+        assert_eq!(rotate_cw(&rotate_ccw(&cells)), cells);
+        assert_eq!(flip_horizontal(&flip_horizontal(&cells)), cells);
+        assert_eq!(flip_vertical(&flip_vertical(&cells)), cells);
+        assert_eq!(
+            rotate_cw(&rotate_cw(&cells)),
+            flip_horizontal(&flip_vertical(&cells))
+        );
+    }
+
+    #[test]
+    fn rle_population() {
+        let cells = parse_pattern("#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!").unwrap();
+        assert_eq!(cells.len(), 5);
+        assert_eq!(cells, glider());
+    }
+
+    #[test]
+    fn invalid_rle() {
+        assert_eq!(
+            parse_rle("bo$2bq!"),
+            Err(PatternError::UnexpectedChar { ch: 'q', line: 1 })
+        );
+        assert_eq!(parse_rle("x = 0, y = 0\n"), Err(PatternError::Empty));
+        assert_eq!(
+            parse_rle("3o$2!"),
+            Err(PatternError::DanglingCount { line: 1 })
+        );
+        assert_eq!(
+            parse_rle("99999999999o!"),
+            Err(PatternError::CountTooLarge { line: 1 })
+        );
+        // Runs that are each in range but add up past the largest coordinate
+        assert_eq!(
+            parse_rle("2147483647b2b!"),
+            Err(PatternError::CountTooLarge { line: 1 })
+        );
+        assert_eq!(
+            parse_rle("o$\n2147483647$2o!"),
+            Err(PatternError::CountTooLarge { line: 2 })
+        );
+        // A short line that would need gigabytes of cells
+        assert_eq!(
+            parse_rle("2000000000o!"),
+            Err(PatternError::TooManyCells { line: 1 })
+        );
+    }
+
+    #[test]
+    fn life106_round_trip() {
+        let cells: LivingList = [(-5, -3), (0, 0), (1_000_000, -2_000_000), (7, 42)]
+            .into_iter()
+            .map(Vector2::from)
+            .collect();
+        let text = to_life106(&cells);
+        assert!(text.starts_with(LIFE106_HEADER));
+        assert_eq!(parse_life106(&text).unwrap(), cells);
+        // The coordinates are kept as they are, unlike with RLE
+        assert_eq!(parse_pattern(&text).unwrap(), cells);
+    }
+
+    #[test]
+    fn life106_detection() {
+        // Coordinates that are also valid RLE digits must not be read as RLE
+        let text = format!("{LIFE106_HEADER}\n1 2\n");
+        assert_eq!(
+            parse_pattern(&text).unwrap(),
+            [Vector2::new(1, 2)].into_iter().collect()
+        );
+        assert_eq!(
+            parse_pattern("#N Not Life 1.06\nbo$2bo$3o!").unwrap(),
+            glider()
+        );
+        assert_eq!(
+            parse_life106(&format!("{LIFE106_HEADER}\n1 2 3\n")),
+            Err(PatternError::BadCoordinates { line: 2 })
+        );
+    }
+
+    #[test]
+    fn image_dark_pixels_become_cells() {
+        let dark = image::Rgba([10, 10, 10, 255]);
+        let light = image::Rgba([240, 240, 240, 255]);
+        let mut picture = image::RgbaImage::from_pixel(4, 3, light);
+        for (x, y) in [(0, 0), (3, 1), (1, 2)] {
+            picture.put_pixel(x, y, dark);
+        }
+        // Dark but see-through pixels don't count
+        picture.put_pixel(2, 2, image::Rgba([0, 0, 0, 0]));
+
+        let cells = from_image(&image::DynamicImage::ImageRgba8(picture), 128, 64);
+        let expected: LivingList = [(0, 0), (3, 1), (1, 2)]
+            .into_iter()
+            .map(Vector2::from)
+            .collect();
+        assert_eq!(cells, expected);
+    }
+
+    #[test]
+    fn luminance_to_cells() {
+        assert!(is_dark(image::LumaA([127, 255]), 128));
+        assert!(!is_dark(image::LumaA([128, 255]), 128));
+        assert!(!is_dark(image::LumaA([0, 127]), 128));
+        assert!(is_dark(image::LumaA([0, 128]), 1));
+    }
+
+    #[test]
+    fn etching_repeats_texture() {
+        let texture = image::load_from_memory(include_bytes!("../../rsc/dead.png")).unwrap();
+        let (width, height) = (texture.width() as i32, texture.height() as i32);
+        let max = Vector2::new(width - 1, height - 1);
+
+        let cells = etch_background(128, Vector2::new(0, 0), max);
+        assert!(!cells.is_empty());
+        let expected: LivingList = texture
+            .to_luma_alpha8()
+            .enumerate_pixels()
+            .filter(|(_, _, p)| is_dark(**p, 128))
+            .map(|(x, y, _)| Vector2::new(x as i32, y as i32))
+            .collect();
+        assert_eq!(cells, expected);
+
+        // The next tile over, including negative coordinates, is the same
+        let offset = Vector2::new(-width, height);
+        let moved = etch_background(128, offset, offset + max);
+        assert_eq!(moved, cells.iter().map(|c| *c + offset).collect());
+
+        assert!(etch_background(0, Vector2::new(0, 0), max).is_empty());
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let cells: LivingList = [
+            Vector2::new(-3, 7),
+            Vector2::new(0, 0),
+            Vector2::new(12, -1),
+        ]
+        .into_iter()
+        .collect();
+        let json = to_json(&cells);
+        assert_eq!(json, "[[12,-1],[0,0],[-3,7]]");
+        assert_eq!(parse_json(&json).unwrap(), cells);
+        assert_eq!(parse_pattern(&json).unwrap(), cells);
+        assert_eq!(parse_json("[]").unwrap(), LivingList::default());
+    }
+
+    #[test]
+    fn malformed_json() {
+        assert!(matches!(
+            parse_json("[[1, 2], [3]]"),
+            Err(PatternError::BadJson(_))
+        ));
+        assert!(matches!(
+            parse_json("[[1, 2]"),
+            Err(PatternError::BadJson(_))
+        ));
+        assert!(matches!(
+            parse_pattern("[{\"x\": 1}]"),
+            Err(PatternError::BadJson(_))
+        ));
+    }
+
+    #[test]
+    fn gzipped_rle_imports_like_plain() {
+        use std::io::Write;
+
+        let rle = "x = 3, y = 3\nbo$2bo$3o!\n";
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(rle.as_bytes()).unwrap();
+        let gzipped = encoder.finish().unwrap();
+
+        let plain = parse_pattern_file("glider.rle", rle.as_bytes()).unwrap();
+        assert_eq!(
+            parse_pattern_file("glider.rle.gz", &gzipped).unwrap(),
+            plain
+        );
+        // Detected by the magic bytes without the extension
+        assert_eq!(parse_pattern_file("glider.rle", &gzipped).unwrap(), plain);
+
+        assert!(matches!(
+            parse_pattern_file("glider.rle.gz", rle.as_bytes()),
+            Err(PatternError::Unreadable(_))
+        ));
+    }
+
+    #[test]
+    fn scaling_makes_blocks() {
+        let one: LivingList = [Vector2::new(0, 0)].into_iter().collect();
+        let scaled = scale_pattern(&one, 3);
+        assert_eq!(scaled.len(), 9);
+        assert_eq!(
+            bounding_box(&scaled),
+            Some((Vector2::new(0, 0), Vector2::new(2, 2)))
+        );
+
+        assert_eq!(scale_pattern(&glider(), 2).len(), 4 * glider().len());
+        assert_eq!(scale_pattern(&glider(), 1), glider());
+        // Negative cells scale away from the origin too
+        let left: LivingList = [Vector2::new(-1, 0)].into_iter().collect();
+        assert_eq!(
+            bounding_box(&scale_pattern(&left, 2)),
+            Some((Vector2::new(-2, 0), Vector2::new(-1, 1)))
+        );
+    }
+}
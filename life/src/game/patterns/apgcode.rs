@@ -0,0 +1,142 @@
+//! Decoding apgcodes, the pattern names used by Catagolue and the LifeWiki,
+//! such as `xs4_33` for the block.
+//!
+//! Only still lifes (`xs`), oscillators (`xp`), and spaceships (`xq`) are
+//! supported. Their codes are a prefix, the population or period, an
+//! underscore, and the cells in the extended Wechsler format. Other kinds of
+//! codes, such as linear growth patterns (`yl`) and methuselahs, describe
+//! patterns by something other than their cells, so they're rejected.
+
+use rustc_hash::FxHashSet;
+use vec2::Vector2;
+
+use super::{LivingList, PatternError};
+
+/// The height of each strip of cells in the extended Wechsler format
+const STRIP_HEIGHT: i32 = 5;
+
+/// Whether some text looks like it's meant to be an apgcode rather than
+/// another pattern format.
+pub fn looks_like_apgcode(input: &str) -> bool {
+    let input = input.trim();
+    input
+        .split_once('_')
+        .is_some_and(|(prefix, _)| !prefix.is_empty() && prefix.chars().all(char::is_alphanumeric))
+        && !input.contains(char::is_whitespace)
+}
+
+/// Decode an apgcode into its cells, with the top-left corner of the code's
+/// first strip at (0, 0).
+pub fn parse_apgcode(input: &str) -> Result<LivingList, PatternError> {
+    let bad = |reason: &str| PatternError::BadApgcode(reason.to_owned());
+    let input = input.trim();
+    let (prefix, wechsler) = input.split_once('_').ok_or_else(|| bad("missing '_'"))?;
+
+    let kind = prefix.get(..2).unwrap_or(prefix);
+    if !matches!(kind, "xs" | "xp" | "xq") {
+        return Err(bad(&format!(
+            "'{kind}' codes aren't supported, only xs, xp, and xq"
+        )));
+    }
+    let number: usize = prefix[2..]
+        .parse()
+        .map_err(|_| bad(&format!("'{}' isn't a number", &prefix[2..])))?;
+
+    let cells = parse_wechsler(wechsler)?;
+    if cells.is_empty() {
+        return Err(PatternError::Empty);
+    }
+    // Still lifes give their population, so it can be checked
+    if kind == "xs" && cells.len() != number {
+        return Err(bad(&format!(
+            "xs{number} should have {number} cells, but it has {}",
+            cells.len()
+        )));
+    }
+    Ok(cells)
+}
+
+/// Decode cells in the extended Wechsler format. Strips of five rows are
+/// separated by `z`, and within a strip each character from `0` to `v` is a
+/// column whose bits, lowest first, are the cells from top to bottom. `w` and
+/// `x` stand for two and three empty columns, and `y` followed by a character
+/// from `0` to `z` for four or more.
+fn parse_wechsler(input: &str) -> Result<LivingList, PatternError> {
+    let mut cells = FxHashSet::default();
+    let mut x = 0;
+    let mut y = 0;
+    let mut chars = input.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            'z' => {
+                x = 0;
+                y += STRIP_HEIGHT;
+            }
+            'w' => x += 2,
+            'x' => x += 3,
+            'y' => {
+                let run = chars
+                    .next()
+                    .and_then(|c| c.to_digit(36))
+                    .ok_or_else(|| {
+                        PatternError::BadApgcode("'y' must be followed by 0-9 or a-z".to_owned())
+                    })?;
+                x += 4 + run as i32;
+            }
+            '0'..='9' | 'a'..='v' => {
+                let column = ch.to_digit(32).unwrap();
+                for row in 0..STRIP_HEIGHT {
+                    if column & (1 << row) != 0 {
+                        cells.insert(Vector2::new(x, y + row));
+                    }
+                }
+                x += 1;
+            }
+            ch => {
+                return Err(PatternError::BadApgcode(format!(
+                    "unexpected character '{ch}'"
+                )))
+            }
+        }
+    }
+    Ok(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(coords: &[(i32, i32)]) -> LivingList {
+        coords.iter().map(|&(x, y)| Vector2::new(x, y)).collect()
+    }
+
+    #[test]
+    fn known_codes() {
+        assert_eq!(parse_apgcode("xs4_33").unwrap(), cells(&[(0, 0), (1, 0), (0, 1), (1, 1)]));
+        assert_eq!(parse_apgcode("xp2_7").unwrap(), cells(&[(0, 0), (0, 1), (0, 2)]));
+        assert_eq!(
+            parse_apgcode("xq4_153").unwrap(),
+            cells(&[(0, 0), (1, 0), (2, 0), (2, 1), (1, 2)])
+        );
+        assert!(looks_like_apgcode(" xp2_7\n"));
+        assert!(!looks_like_apgcode("bo$2bo$3o!"));
+    }
+
+    #[test]
+    fn gaps_and_strips() {
+        assert_eq!(parse_apgcode("xs2_1w1").unwrap(), cells(&[(0, 0), (3, 0)]));
+        assert_eq!(parse_apgcode("xs2_1y01").unwrap(), cells(&[(0, 0), (5, 0)]));
+        assert_eq!(parse_apgcode("xs2_1z1").unwrap(), cells(&[(0, 0), (0, 5)]));
+    }
+
+    #[test]
+    fn unsupported_codes() {
+        let bad = |code| matches!(parse_apgcode(code), Err(PatternError::BadApgcode(_)));
+        assert!(bad("yl144_1_16_afb5f3db909e60548f086e22ee3353ac"));
+        assert!(bad("xs5_33"));
+        assert!(bad("xp2_7!"));
+        assert!(bad("xs4_y"));
+        assert!(bad("xpq_7"));
+        assert!(matches!(parse_apgcode("xp2_0"), Err(PatternError::Empty)));
+    }
+}
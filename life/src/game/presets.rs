@@ -0,0 +1,109 @@
+//! Named rules that can be switched to, both built in and added by the player.
+
+use serde::{Deserialize, Serialize};
+
+use super::rules::Rules;
+use crate::storage::DataHandle;
+
+/// Where the player's presets are stored between sessions
+const USER_PRESETS_DATA: DataHandle = DataHandle::new("rule_presets");
+
+/// The presets that are always available, as names and rules in B/S notation
+pub const BUILT_IN: [(&str, &str); 4] = [
+    ("Conway", "B3/S23"),
+    ("HighLife", "B36/S23"),
+    ("Day & Night", "B3678/S34678"),
+    ("Seeds", "B2/S"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RulePreset {
+    pub name: String,
+    pub rules: Rules,
+}
+
+/// The built-in presets followed by the ones the player added.
+#[derive(Debug)]
+pub struct RulePresets {
+    handle: DataHandle,
+    user: Vec<RulePreset>,
+}
+
+impl RulePresets {
+    /// Start with the stored presets the player added, if there are any.
+    pub fn load() -> Self {
+        Self::load_from(USER_PRESETS_DATA)
+    }
+
+    fn load_from(handle: DataHandle) -> Self {
+        Self {
+            handle,
+            user: handle.load().unwrap_or_default(),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = RulePreset> + '_ {
+        let built_in = BUILT_IN.iter().map(|&(name, rules)| RulePreset {
+            name: name.to_owned(),
+            rules: Rules::from_bs_string(rules).expect("Built-in presets should parse"),
+        });
+        built_in.chain(self.user.iter().cloned())
+    }
+
+    /// Add a preset and store it, replacing any the player added with the same
+    /// name.
+    pub fn add(&mut self, name: String, rules: Rules) {
+        self.user.retain(|preset| preset.name != name);
+        self.user.push(RulePreset { name, rules });
+        self.handle.store(&self.user);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{chunks, Cancel, LivingList};
+    use vec2::Vector2;
+
+    #[test]
+    fn built_in_presets_parse() {
+        for (name, rules) in BUILT_IN {
+            let parsed = Rules::from_bs_string(rules).unwrap();
+            assert_eq!(parsed.to_string(), rules, "{name}");
+        }
+        assert_eq!(RulePresets::load_from(DataHandle::new("no_presets")).iter().count(), 4);
+    }
+
+    #[test]
+    fn user_presets_persist() {
+        let handle = DataHandle::new("rule_presets_test");
+        handle.remove();
+        let maze = Rules::from_bs_string("B3/S12345").unwrap();
+        RulePresets::load_from(handle).add("Maze".to_owned(), maze);
+
+        let reloaded = RulePresets::load_from(handle);
+        handle.remove();
+        let last = reloaded.iter().last().unwrap();
+        assert_eq!(last, RulePreset { name: "Maze".to_owned(), rules: maze });
+    }
+
+    #[test]
+    fn seeds_explodes() {
+        let seeds = RulePresets::load_from(DataHandle::new("no_presets"))
+            .iter()
+            .find(|preset| preset.name == "Seeds")
+            .unwrap()
+            .rules;
+        // Two neighbors are all it takes to be born, so three scattered cells
+        // that would die out under Conway's rules keep spreading
+        let seed: LivingList =
+            [Vector2::new(0, 0), Vector2::new(1, 0), Vector2::new(0, 2)].into_iter().collect();
+        let run = |rules| {
+            (0..30).fold(seed.clone(), |cells, _| {
+                chunks::step(&cells, rules, &Cancel::NEVER).unwrap()
+            })
+        };
+        assert!(run(&Rules::CONWAY).is_empty());
+        assert!(run(&seeds).len() > 100);
+    }
+}
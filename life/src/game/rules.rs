@@ -0,0 +1,156 @@
+//! Life-like rules in B/S notation, such as `B3/S23` for Conway's Game of Life.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Which neighbor counts bring a dead cell to life and which keep a living
+/// cell alive. Each array is indexed by the number of living neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Rules {
+    pub birth: [bool; 9],
+    pub survival: [bool; 9],
+}
+
+/// An error produced while parsing rules in B/S notation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RulesError {
+    /// The rules weren't a `B` part and an `S` part separated by a slash.
+    Malformed,
+    /// A neighbor count wasn't a digit from 0 to 8.
+    BadCount(char),
+    /// The same neighbor count was listed twice in one part.
+    RepeatedCount(char),
+    /// Dead cells with no living neighbors would be born, which fills the
+    /// whole unbounded board.
+    BirthWithoutNeighbors,
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed => write!(f, "rules should look like B3/S23"),
+            Self::BadCount(ch) => write!(f, "'{ch}' isn't a neighbor count from 0 to 8"),
+            Self::RepeatedCount(ch) => write!(f, "the count {ch} is listed twice"),
+            Self::BirthWithoutNeighbors => {
+                write!(f, "B0 would fill the whole board, so it isn't supported")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+impl Rules {
+    /// Conway's Game of Life, B3/S23
+    pub const CONWAY: Self = Self {
+        birth: counts(&[3]),
+        survival: counts(&[2, 3]),
+    };
+
+    /// Parse rules like `B36/S23`. The letters are case-insensitive, and
+    /// either part may list no counts, as in `B2/S`.
+    pub fn from_bs_string(text: &str) -> Result<Self, RulesError> {
+        let (birth, survival) = text.trim().split_once('/').ok_or(RulesError::Malformed)?;
+        let part = |part: &str, letter: char| {
+            let mut chars = part.chars();
+            if !chars.next().is_some_and(|c| c.eq_ignore_ascii_case(&letter)) {
+                return Err(RulesError::Malformed);
+            }
+            let mut counts = [false; 9];
+            for ch in chars {
+                let count = ch
+                    .to_digit(10)
+                    .filter(|&d| d <= 8)
+                    .ok_or(RulesError::BadCount(ch))?;
+                if std::mem::replace(&mut counts[count as usize], true) {
+                    return Err(RulesError::RepeatedCount(ch));
+                }
+            }
+            Ok(counts)
+        };
+        let rules = Self {
+            birth: part(birth, 'B')?,
+            survival: part(survival, 'S')?,
+        };
+        if rules.birth[0] {
+            return Err(RulesError::BirthWithoutNeighbors);
+        }
+        Ok(rules)
+    }
+
+    /// Whether a cell is alive in the next generation.
+    #[inline(always)]
+    pub fn next_state(&self, alive: bool, neighbors: u32) -> bool {
+        let counts = if alive { &self.survival } else { &self.birth };
+        counts.get(neighbors as usize).copied().unwrap_or(false)
+    }
+
+    /// The birth and survival counts as bitmasks, with bit `n` set if `n`
+    /// neighbors count, for passing to a shader.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn masks(&self) -> (u32, u32) {
+        let mask = |counts: &[bool; 9]| {
+            (0..9).filter(|&n| counts[n]).fold(0, |mask, n| mask | 1 << n)
+        };
+        (mask(&self.birth), mask(&self.survival))
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::CONWAY
+    }
+}
+
+impl fmt::Display for Rules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = |counts: &[bool; 9]| -> String {
+            (0..9u8)
+                .filter(|&n| counts[n as usize])
+                .map(|n| char::from(b'0' + n))
+                .collect()
+        };
+        write!(f, "B{}/S{}", digits(&self.birth), digits(&self.survival))
+    }
+}
+
+/// A table with only the given neighbor counts set.
+const fn counts(list: &[usize]) -> [bool; 9] {
+    let mut counts = [false; 9];
+    let mut i = 0;
+    while i < list.len() {
+        counts[list[i]] = true;
+        i += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_displays() {
+        let highlife = Rules::from_bs_string("B36/S23").unwrap();
+        assert_eq!(highlife.birth, counts(&[3, 6]));
+        assert_eq!(highlife.survival, counts(&[2, 3]));
+        assert_eq!(highlife.to_string(), "B36/S23");
+        assert_eq!(Rules::from_bs_string(&highlife.to_string()), Ok(highlife));
+
+        assert_eq!(Rules::from_bs_string("b3/s23"), Ok(Rules::CONWAY));
+        let seeds = Rules::from_bs_string("B2/S").unwrap();
+        assert_eq!(seeds.survival, [false; 9]);
+        assert_eq!(seeds.to_string(), "B2/S");
+    }
+
+    #[test]
+    fn rejects_bad_rules() {
+        let parse = Rules::from_bs_string;
+        assert_eq!(parse("B9/S"), Err(RulesError::BadCount('9')));
+        assert_eq!(parse("B33/S"), Err(RulesError::RepeatedCount('3')));
+        assert_eq!(parse("S23/B3"), Err(RulesError::Malformed));
+        assert_eq!(parse("B3S23"), Err(RulesError::Malformed));
+        assert_eq!(parse("B0/S"), Err(RulesError::BirthWithoutNeighbors));
+    }
+}
@@ -1,9 +1,12 @@
-use super::GameState;
-use rustc_hash::FxHashSet;
+use super::{snapshot::SimulationSnapshot, GameState};
 use serde::{Deserialize, Serialize};
 use std::{fs::File, io::Read, path::PathBuf};
 use vec2::Vector2;
 
+/// The most saves a file can hold. Saving manually is still allowed past this,
+/// but automatic saves are not.
+const MAX_SAVES: usize = 100;
+
 /// A representation of a game save file. The saves are stored in memory unless
 /// written to disk via `SaveFile::write_to_disk`.
 pub struct SaveFile {
@@ -74,6 +77,11 @@ impl SaveFile {
         self.saves.clone().into_iter()
     }
 
+    /// Whether the file has reached its limit for automatic saves.
+    pub fn is_full(&self) -> bool {
+        self.saves.len() >= MAX_SAVES
+    }
+
     /// Get the number of stored saves
     pub fn save_count(&self) -> usize {
         self.saves.len()
@@ -83,7 +91,8 @@ impl SaveFile {
 #[derive(Serialize, Deserialize, Clone)]
 /// A record of a game that can be restored.
 pub struct SaveGame {
-    living_cells: Vec<Vector2<i32>>,
+    #[serde(flatten)]
+    simulation: SimulationSnapshot,
     grid_size: f32,
     pan_position: Vector2<f64>,
     pub created: chrono::DateTime<chrono::Local>,
@@ -93,15 +102,15 @@ pub struct SaveGame {
 impl SaveGame {
     pub fn new(game_state: &GameState, name: String) -> Self {
         Self {
-            living_cells: game_state.living_cells.iter().cloned().collect(),
-            grid_size: game_state.grid_size,
-            pan_position: game_state.pan_position,
+            simulation: game_state.snapshot(),
+            grid_size: game_state.grid_size(),
+            pan_position: game_state.pan_position(),
             created: chrono::Local::now(),
             name,
         }
     }
-    pub fn living_cells(&self) -> FxHashSet<Vector2<i32>> {
-        self.living_cells.iter().cloned().collect()
+    pub fn simulation(&self) -> &SimulationSnapshot {
+        &self.simulation
     }
     pub fn pan_position(&self) -> Vector2<f64> {
         self.pan_position
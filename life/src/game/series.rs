@@ -0,0 +1,69 @@
+//! The population of every generation, for plotting outside the game.
+
+use serde::{Deserialize, Serialize};
+
+/// The population history of a run along with where the run started.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PopulationSeries {
+    /// The rule the run followed, in B/S notation
+    pub rule: String,
+    /// The name of the save the board was loaded from, if it was
+    pub start_pattern: Option<String>,
+    /// The seed of the random board the run started from, if it did
+    pub seed: Option<u64>,
+    /// One entry per generation, oldest first
+    pub generations: Vec<GenerationPopulation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenerationPopulation {
+    pub generation: u64,
+    pub population: usize,
+}
+
+impl PopulationSeries {
+    /// The series for a population history whose last entry is for generation
+    /// `last`, with no start recorded.
+    pub fn new(history: &[usize], last: u64, rule: String) -> Self {
+        let first = (last + 1).saturating_sub(history.len() as u64);
+        Self {
+            rule,
+            start_pattern: None,
+            seed: None,
+            generations: history
+                .iter()
+                .enumerate()
+                .map(|(i, population)| GenerationPopulation {
+                    generation: first + i as u64,
+                    population: *population,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a population series should serialize")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_every_generation_with_metadata() {
+        // A history that starts at generation 10, as after loading a save
+        let mut series = PopulationSeries::new(&[5, 6, 8, 7], 13, "B3/S23".to_owned());
+        series.start_pattern = Some("acorn".to_owned());
+        series.seed = Some(42);
+        let generations: Vec<_> = series.generations.iter().map(|g| g.generation).collect();
+        assert_eq!(generations, [10, 11, 12, 13]);
+        assert_eq!(series.generations[2].population, 8);
+
+        let json = series.to_json();
+        assert!(json.contains(r#""rule": "B3/S23""#), "{json}");
+        assert!(json.contains(r#""start_pattern": "acorn""#), "{json}");
+        let parsed: PopulationSeries = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, series);
+    }
+}
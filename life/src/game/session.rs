@@ -0,0 +1,155 @@
+//! Keeping the board and view in local storage on the web, so that reloading
+//! the page picks up where it left off.
+// Only used on the web, but also built natively to be tested
+#![cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use vec2::Vector2;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+use super::snapshot::SimulationSnapshot;
+use crate::storage::DataHandle;
+
+/// Where the session is kept
+const SESSION_DATA: DataHandle = DataHandle::new("session");
+/// Where the choice of whether to keep the session is stored
+const SESSION_ENABLED_DATA: DataHandle = DataHandle::new("session_enabled");
+/// The shortest time between saves of the session
+const SAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The board and view as they were when last saved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub simulation: SimulationSnapshot,
+    pub pan_position: Vector2<f64>,
+    pub grid_size: f32,
+}
+
+impl Session {
+    /// Load the saved session, or `None` if there isn't one or it couldn't be
+    /// read.
+    pub fn load() -> Option<Self> {
+        Self::load_from(SESSION_DATA)
+    }
+
+    fn load_from(handle: DataHandle) -> Option<Self> {
+        let session: Self = handle.load()?;
+        if !session.is_valid() {
+            log::warn!("Ignoring a saved session with an invalid view");
+            return None;
+        }
+        Some(session)
+    }
+
+    /// Whether the view can be restored: the zoom is positive and everything
+    /// is finite.
+    fn is_valid(&self) -> bool {
+        self.grid_size.is_finite()
+            && self.grid_size > 0.0
+            && self.pan_position.x.is_finite()
+            && self.pan_position.y.is_finite()
+    }
+}
+
+/// Saves the session whenever it has changed, at most once every
+/// `SAVE_INTERVAL`.
+#[derive(Debug)]
+pub struct SessionAutosave {
+    enabled: bool,
+    /// Whether the board or view has changed since the session was last saved
+    changed: bool,
+    last_saved: Option<Instant>,
+}
+
+impl SessionAutosave {
+    /// Start with the stored choice of whether to keep the session, which is
+    /// to keep it if there isn't one.
+    pub fn new() -> Self {
+        Self {
+            enabled: SESSION_ENABLED_DATA.load().unwrap_or(true),
+            changed: false,
+            last_saved: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Set and store whether the session is kept. Once disabled, the session
+    /// that was saved is no longer restored.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        // Save as soon as it's turned back on
+        self.changed = true;
+        self.last_saved = None;
+        SESSION_ENABLED_DATA.store(&enabled);
+    }
+
+    /// Note that the board or view has changed.
+    pub fn mark_changed(&mut self) {
+        self.changed = true;
+    }
+
+    /// Whether the session should be saved now.
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.enabled
+            && self.changed
+            && self
+                .last_saved
+                .map_or(true, |t| now.saturating_duration_since(t) >= SAVE_INTERVAL)
+    }
+
+    pub fn save(&mut self, session: &Session, now: Instant) {
+        SESSION_DATA.store(session);
+        self.changed = false;
+        self.last_saved = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{bounds::WorldBounds, rules::Rules, LivingList};
+
+    fn session(grid_size: f32, pan_position: Vector2<f64>) -> Session {
+        let cells: LivingList = [Vector2::new(0, 0), Vector2::new(1, 0), Vector2::new(2, 0)]
+            .into_iter()
+            .collect();
+        Session {
+            simulation: SimulationSnapshot::new(&cells, 4, Rules::CONWAY, WorldBounds::Infinite),
+            pan_position,
+            grid_size,
+        }
+    }
+
+    #[test]
+    fn invalid_views_are_rejected() {
+        let origin = Vector2::new(0.0, 0.0);
+        assert!(session(0.1, origin).is_valid());
+        for grid_size in [f32::NAN, -0.1, 0.0, f32::INFINITY] {
+            assert!(!session(grid_size, origin).is_valid(), "{grid_size}");
+        }
+        for pan in [Vector2::new(f64::INFINITY, 0.0), Vector2::new(3.0, f64::NAN)] {
+            assert!(!session(0.1, pan).is_valid(), "{pan:?}");
+        }
+    }
+
+    #[test]
+    fn truncated_sessions_are_ignored() {
+        let handle = DataHandle::new("session_test");
+        handle.store(&session(0.1, Vector2::new(5.0, -2.0)));
+        assert!(Session::load_from(handle).is_some());
+
+        let json = serde_json::to_string(&session(0.1, Vector2::new(5.0, -2.0))).unwrap();
+        let cut = json.find("living_cells").unwrap() + 20;
+        handle.store_raw(&json[..cut]);
+        assert!(Session::load_from(handle).is_none());
+        handle.remove();
+    }
+}
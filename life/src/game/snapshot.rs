@@ -0,0 +1,99 @@
+// Only saves are made from snapshots so far
+#![cfg_attr(not(feature = "saving"), allow(dead_code))]
+
+use serde::{Deserialize, Serialize};
+use vec2::Vector2;
+
+use super::{bounds::WorldBounds, rules::Rules, LivingList};
+
+/// The state of the simulation itself, without anything about how it is being
+/// viewed, such as the camera position or zoom.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationSnapshot {
+    /// The living cells, sorted by row and then by column
+    pub living_cells: Vec<Vector2<i32>>,
+    /// How many generations have passed since the board was last cleared
+    #[serde(default)]
+    pub generation: u64,
+    /// The rules the board was running under. Older saves are Conway's.
+    #[serde(default)]
+    pub rules: Rules,
+    /// The shape of the world. Older saves are unbounded.
+    #[serde(default)]
+    pub bounds: WorldBounds,
+}
+
+impl SimulationSnapshot {
+    pub fn new(cells: &LivingList, generation: u64, rules: Rules, bounds: WorldBounds) -> Self {
+        let mut living_cells: Vec<_> = cells.iter().copied().collect();
+        // Sorting makes equal boards produce equal snapshots
+        living_cells.sort_unstable_by_key(|c| (c.y, c.x));
+        Self {
+            living_cells,
+            generation,
+            rules,
+            bounds,
+        }
+    }
+
+    pub fn cells(&self) -> LivingList {
+        self.living_cells.iter().copied().collect()
+    }
+
+    /// Whether the snapshot was taken under other rules or world bounds than
+    /// these.
+    pub fn has_other_settings(&self, rules: Rules, bounds: WorldBounds) -> bool {
+        self.rules != rules || self.bounds != bounds
+    }
+
+    /// The snapshot to restore when loading it while `rules` and `bounds` are
+    /// in use. They're kept unless `adopt_settings` is set, in which case the
+    /// snapshot's own are used.
+    pub fn for_load(&self, rules: Rules, bounds: WorldBounds, adopt_settings: bool) -> Self {
+        if adopt_settings {
+            return self.clone();
+        }
+        Self {
+            rules,
+            bounds,
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_serde() {
+        let cells: LivingList =
+            [Vector2::new(2, 1), Vector2::new(-1, 0), Vector2::new(0, 1)].into_iter().collect();
+        let highlife = Rules::from_bs_string("B36/S23").unwrap();
+        let torus = WorldBounds::Torus {
+            width: 12,
+            height: 7,
+        };
+        let snapshot = SimulationSnapshot::new(&cells, 42, highlife, torus);
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let restored: SimulationSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, snapshot);
+        assert_eq!(restored.cells(), cells);
+        assert_eq!(restored.generation, 42);
+        assert_eq!(restored.rules, highlife);
+        assert_eq!(restored.bounds, torus);
+    }
+
+    #[test]
+    fn equal_boards_give_equal_snapshots() {
+        let a: LivingList = (0..20).map(|i| Vector2::new(i * 7 % 5, i)).collect();
+        let b: LivingList = (0..20).rev().map(|i| Vector2::new(i * 7 % 5, i)).collect();
+        let new = |cells| SimulationSnapshot::new(cells, 3, Rules::CONWAY, WorldBounds::Infinite);
+        assert_eq!(new(&a), new(&b));
+        // Older saves have no generation, rules, or bounds
+        let old: SimulationSnapshot = serde_json::from_str(r#"{"living_cells":[]}"#).unwrap();
+        assert_eq!(old.generation, 0);
+        assert_eq!(old.rules, Rules::CONWAY);
+        assert_eq!(old.bounds, WorldBounds::Infinite);
+    }
+}
@@ -0,0 +1,156 @@
+//! Finding spaceships, clusters of cells that repeat their shape somewhere
+//! else after a few generations.
+
+use std::collections::VecDeque;
+
+use vec2::Vector2;
+
+use super::{patterns, LivingList};
+
+/// The longest spaceship period that will be detected.
+const MAX_PERIOD: usize = 8;
+/// Clusters larger than this aren't checked, which keeps detection quick on
+/// busy boards. Most known spaceships are far smaller.
+const MAX_CLUSTER_SIZE: usize = 64;
+
+/// A cluster's cells relative to the top-left of its bounding box, sorted, so
+/// that two clusters have the same shape exactly when their shapes are equal.
+#[derive(PartialEq, Eq)]
+struct Shape {
+    origin: Vector2<i32>,
+    cells: Vec<Vector2<i32>>,
+}
+
+impl Shape {
+    fn new(cluster: &LivingList) -> Option<Self> {
+        let (origin, _) = patterns::bounding_box(cluster)?;
+        let mut cells: Vec<_> = cluster.iter().map(|c| *c - origin).collect();
+        cells.sort_by_key(|c| (c.y, c.x));
+        Some(Self { origin, cells })
+    }
+}
+
+/// A cluster that moved without changing shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Spaceship {
+    /// The top-left corner of the spaceship's bounding box
+    pub position: Vector2<i32>,
+    /// How far the spaceship moves each period
+    pub displacement: Vector2<i32>,
+    /// How many generations it takes to repeat its shape
+    pub period: u32,
+}
+
+impl Spaceship {
+    /// The velocity in the usual notation, such as `(1, 1)/4` for a glider.
+    pub fn velocity(&self) -> String {
+        format!(
+            "({}, {})/{}",
+            self.displacement.x, self.displacement.y, self.period
+        )
+    }
+}
+
+/// Tracks the clusters of recent generations to find the ones that have moved.
+#[derive(Default)]
+pub struct SpaceshipDetector {
+    /// The small clusters of the most recent generations, newest last
+    history: VecDeque<Vec<Shape>>,
+    /// The spaceships found in the latest generation
+    spaceships: Vec<Spaceship>,
+}
+
+impl SpaceshipDetector {
+    /// Record the next generation and find its spaceships.
+    pub fn record(&mut self, cells: &LivingList) {
+        let shapes: Vec<Shape> = patterns::clusters(cells)
+            .iter()
+            .filter(|c| c.len() <= MAX_CLUSTER_SIZE)
+            .filter_map(Shape::new)
+            .collect();
+
+        self.spaceships = shapes.iter().filter_map(|s| self.find_motion(s)).collect();
+
+        if self.history.len() == MAX_PERIOD {
+            self.history.pop_front();
+        }
+        self.history.push_back(shapes);
+    }
+
+    /// Look back for the most recent generation with a cluster of the same
+    /// shape nearby. The shape is a spaceship if that cluster is somewhere
+    /// else, and a still life or oscillator if it's in the same place.
+    fn find_motion(&self, shape: &Shape) -> Option<Spaceship> {
+        for (i, generation) in self.history.iter().rev().enumerate() {
+            let period = i as u32 + 1;
+            let mut displacements = generation
+                .iter()
+                .filter(|s| s.cells == shape.cells)
+                .map(|s| shape.origin - s.origin)
+                // Nothing moves faster than one cell per generation
+                .filter(|d| d.x.unsigned_abs() <= period && d.y.unsigned_abs() <= period)
+                .peekable();
+            if displacements.peek().is_none() {
+                continue;
+            }
+            let displacement = displacements
+                .min_by_key(|d| Vector2::chebyshev_distance(*d, Vector2::new(0, 0)))?;
+            return (displacement != Vector2::new(0, 0)).then_some(Spaceship {
+                position: shape.origin,
+                displacement,
+                period,
+            });
+        }
+        None
+    }
+
+    /// The spaceships found in the latest generation.
+    pub fn spaceships(&self) -> &[Spaceship] {
+        &self.spaceships
+    }
+
+    /// Forget the history, for when the board is changed by something other
+    /// than a step.
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.spaceships.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{bounds::WorldBounds, compute_step, rules::Rules};
+
+    /// Record `generations` generations starting from `cells`, returning the
+    /// detector and the last generation.
+    fn run(mut cells: LivingList, generations: usize) -> (SpaceshipDetector, LivingList) {
+        let mut detector = SpaceshipDetector::default();
+        for _ in 0..generations {
+            detector.record(&cells);
+            cells = compute_step(&cells, &Rules::CONWAY, &WorldBounds::Infinite);
+        }
+        (detector, cells)
+    }
+
+    #[test]
+    fn glider_is_one_spaceship() {
+        let (detector, _) = run(patterns::parse_rle("bo$2bo$3o!").unwrap(), 9);
+        let spaceships = detector.spaceships();
+        assert_eq!(spaceships.len(), 1);
+        assert_eq!(spaceships[0].displacement, Vector2::new(1, 1));
+        assert_eq!(spaceships[0].period, 4);
+        assert_eq!(spaceships[0].velocity(), "(1, 1)/4");
+    }
+
+    #[test]
+    fn still_lifes_and_oscillators_are_not_spaceships() {
+        // A block and a blinker far enough apart not to interact
+        let cells = patterns::parse_rle("2o$2o8$10b3o!").unwrap();
+        let (mut detector, last) = run(cells, 9);
+        assert!(detector.spaceships().is_empty());
+        detector.reset();
+        detector.record(&last);
+        assert!(detector.spaceships().is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+use std::{
+    collections::VecDeque,
+    hash::{Hash, Hasher},
+};
+
+use rustc_hash::FxHasher;
+
+use super::LivingList;
+
+/// The longest oscillator period that will be detected.
+const MAX_PERIOD: usize = 32;
+
+/// Detects when the board settles into a still life or an oscillator by
+/// comparing each generation against the ones before it.
+#[derive(Default)]
+pub struct StabilityDetector {
+    /// Hashes of the most recent generations, newest last
+    history: VecDeque<u64>,
+    /// The period of the cycle the board is in, if it's in one
+    period: Option<u32>,
+    /// The hash of the cycle's phase zero, the first generation of it that
+    /// was seen, if the board is in a cycle
+    phase_zero: Option<u64>,
+}
+
+/// A change in the board's stability.
+#[cfg_attr(not(feature = "saving"), allow(dead_code))]
+pub struct Stabilized {
+    /// How many generations the cycle takes. Still lifes have a period of 1.
+    pub period: u32,
+    /// A hash that is the same for every generation of the cycle
+    pub cycle_hash: u64,
+}
+
+impl StabilityDetector {
+    /// Record the next generation. Returns the cycle if the board has just
+    /// started repeating itself, and `None` if it isn't repeating or already
+    /// was.
+    pub fn record(&mut self, cells: &LivingList) -> Option<Stabilized> {
+        let hash = hash_cells(cells);
+        let period = self
+            .history
+            .iter()
+            .rev()
+            .position(|h| *h == hash)
+            .map(|i| i as u32 + 1);
+
+        if self.history.len() == MAX_PERIOD {
+            self.history.pop_front();
+        }
+        self.history.push_back(hash);
+
+        let newly_stable = self.period.is_none() && period.is_some();
+        self.period = period;
+        if period.is_none() {
+            self.phase_zero = None;
+        }
+        if !newly_stable {
+            return None;
+        }
+        let period = period?;
+        // The generation this one repeats started the cycle
+        self.phase_zero = Some(hash);
+        let cycle = self.history.iter().rev().take(period as usize);
+        Some(Stabilized {
+            period,
+            cycle_hash: cycle.copied().min()?,
+        })
+    }
+
+    /// The period of the cycle the board is in, if it's in one.
+    pub fn period(&self) -> Option<u32> {
+        self.period
+    }
+
+    /// Whether the board is in a cycle and has just returned to the cycle's
+    /// phase zero.
+    pub fn is_at_phase_zero(&self) -> bool {
+        self.phase_zero.is_some() && self.history.back() == self.phase_zero.as_ref()
+    }
+
+    /// Forget the history, for when the board is changed by something other
+    /// than a step.
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.period = None;
+        self.phase_zero = None;
+    }
+}
+
+/// Hash a set of cells in a way that doesn't depend on iteration order.
+fn hash_cells(cells: &LivingList) -> u64 {
+    cells
+        .iter()
+        .map(|c| {
+            let mut hasher = FxHasher::default();
+            c.hash(&mut hasher);
+            // Mix the bits so that nearby cells don't cancel out in the sum
+            let mut h = hasher.finish();
+            h ^= h >> 33;
+            h = h.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+            h ^= h >> 33;
+            h
+        })
+        .fold(cells.len() as u64, u64::wrapping_add)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{bounds::WorldBounds, compute_step, patterns, rules::Rules};
+
+    fn step(cells: &LivingList) -> LivingList {
+        compute_step(cells, &Rules::CONWAY, &WorldBounds::Infinite)
+    }
+
+    /// Record `generations` generations starting from `cells`, and return
+    /// what was reported after each one.
+    fn run(mut cells: LivingList, generations: usize) -> Vec<Option<Stabilized>> {
+        let mut detector = StabilityDetector::default();
+        (0..generations)
+            .map(|_| {
+                let stabilized = detector.record(&cells);
+                cells = step(&cells);
+                stabilized
+            })
+            .collect()
+    }
+
+    fn first_cycle_hash(reports: &[Option<Stabilized>]) -> u64 {
+        reports.iter().flatten().next().unwrap().cycle_hash
+    }
+
+    #[test]
+    fn period_two_reported_once() {
+        let reports = run(patterns::parse_rle("3o!").unwrap(), 20);
+        let stable: Vec<_> = reports.iter().flatten().collect();
+        assert_eq!(stable.len(), 1);
+        assert_eq!(stable[0].period, 2);
+        // The blinker repeats its first phase at generation 2
+        assert!(reports[2].is_some());
+    }
+
+    #[test]
+    fn cycle_hash_ignores_phase() {
+        let blinker = patterns::parse_rle("3o!").unwrap();
+        let other_phase = step(&blinker);
+        assert_eq!(
+            first_cycle_hash(&run(blinker, 3)),
+            first_cycle_hash(&run(other_phase, 3))
+        );
+    }
+
+    #[test]
+    fn still_life_and_reset() {
+        let mut detector = StabilityDetector::default();
+        let block = patterns::parse_rle("2o$2o!").unwrap();
+        assert!(detector.record(&block).is_none());
+        assert_eq!(detector.record(&block).map(|s| s.period), Some(1));
+        assert!(detector.is_at_phase_zero());
+        detector.reset();
+        assert_eq!(detector.period(), None);
+        assert!(detector.record(&block).is_none());
+    }
+
+
+    #[test]
+    fn pauses_at_phase_zero_of_period_three() {
+        let pulsar = patterns::parse_rle(
+            "2b3o3b3o2b2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2b2$2b3o3b3o2b$\
+             o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+        )
+        .unwrap();
+        // Start part way through the cycle, so that phase zero isn't the
+        // pattern as written
+        let first = step(&pulsar);
+        let mut cells = first.clone();
+        let mut detector = StabilityDetector::default();
+        let mut at_phase_zero = Vec::new();
+        for generation in 0..12 {
+            if let Some(stabilized) = detector.record(&cells) {
+                assert_eq!(stabilized.period, 3);
+            }
+            if detector.is_at_phase_zero() {
+                assert_eq!(cells, first, "generation {generation}");
+                at_phase_zero.push(generation);
+            }
+            cells = step(&cells);
+        }
+        assert_eq!(detector.period(), Some(3));
+        assert_eq!(at_phase_zero, [3, 6, 9]);
+    }
+}
@@ -0,0 +1,57 @@
+// One generation of a Life-like cellular automaton on a dense grid, where cells
+// outside the grid are dead, or the edges wrap around to the opposite side.
+
+struct Params {
+    width: u32,
+    height: u32,
+    // Bit n is set if n neighbors bring a dead cell to life
+    birth: u32,
+    // Bit n is set if n neighbors keep a living cell alive
+    survival: u32,
+    // 1 if the grid is a torus, and 0 if it's surrounded by dead cells
+    wrap: u32,
+}
+
+@group(0) @binding(0)
+var<uniform> params: Params;
+@group(0) @binding(1)
+var<storage, read> src: array<u32>;
+@group(0) @binding(2)
+var<storage, read_write> dst: array<u32>;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= params.width || id.y >= params.height {
+        return;
+    }
+    let x = i32(id.x);
+    let y = i32(id.y);
+    let width = i32(params.width);
+    let height = i32(params.height);
+    // On a torus narrower than three cells the offsets on either side reach
+    // the same cells, which are only counted once, and never the cell itself
+    let wrap = params.wrap == 1u;
+    let first_x = select(-1, 0, wrap && width < 3);
+    let last_x = select(1, 0, wrap && width < 2);
+    let first_y = select(-1, 0, wrap && height < 3);
+    let last_y = select(1, 0, wrap && height < 2);
+    var count = 0u;
+    for (var dy = first_y; dy <= last_y; dy++) {
+        for (var dx = first_x; dx <= last_x; dx++) {
+            var nx = x + dx;
+            var ny = y + dy;
+            if wrap {
+                nx = (nx + width) % width;
+                ny = (ny + height) % height;
+            }
+            if (dx == 0 && dy == 0) || nx < 0 || ny < 0 || nx >= width || ny >= height {
+                continue;
+            }
+            count += src[u32(ny) * params.width + u32(nx)];
+        }
+    }
+    let index = id.y * params.width + id.x;
+    let rule = select(params.birth, params.survival, src[index] == 1u);
+    let alive = ((rule >> count) & 1u) == 1u;
+    dst[index] = select(0u, 1u, alive);
+}
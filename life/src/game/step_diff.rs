@@ -0,0 +1,60 @@
+//! The cells a single step changed, for checking a step by hand.
+
+use serde::{Deserialize, Serialize};
+use vec2::Vector2;
+
+use super::LivingList;
+
+/// The cells born and the cells that died in one step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepDiff {
+    /// The generation the step produced
+    pub generation: u64,
+    /// Cells that were dead and are now alive, sorted by row then column
+    pub births: Vec<Vector2<i32>>,
+    /// Cells that were alive and are now dead, sorted by row then column
+    pub deaths: Vec<Vector2<i32>>,
+}
+
+impl StepDiff {
+    /// Compare a generation with the one after it.
+    pub fn between(prev: &LivingList, next: &LivingList, generation: u64) -> Self {
+        let sorted = |cells: Vec<Vector2<i32>>| {
+            let mut cells = cells;
+            cells.sort_by_key(|c| (c.y, c.x));
+            cells
+        };
+        Self {
+            generation,
+            births: sorted(next.difference(prev).copied().collect()),
+            deaths: sorted(prev.difference(next).copied().collect()),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a step diff should serialize")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{bounds::WorldBounds, compute_step, rules::Rules};
+
+    #[test]
+    fn blinker_births_and_deaths() {
+        let horizontal: LivingList =
+            [Vector2::new(-1, 0), Vector2::new(0, 0), Vector2::new(1, 0)].into_iter().collect();
+        let vertical = compute_step(&horizontal, &Rules::CONWAY, &WorldBounds::Infinite);
+        let diff = StepDiff::between(&horizontal, &vertical, 1);
+        assert_eq!(diff.births, [Vector2::new(0, -1), Vector2::new(0, 1)]);
+        assert_eq!(diff.deaths, [Vector2::new(-1, 0), Vector2::new(1, 0)]);
+
+        let back = StepDiff::between(&vertical, &horizontal, 2);
+        assert_eq!(back.births, diff.deaths);
+        assert_eq!(back.deaths, diff.births);
+
+        let parsed: StepDiff = serde_json::from_str(&diff.to_json()).unwrap();
+        assert_eq!(parsed, diff);
+    }
+}
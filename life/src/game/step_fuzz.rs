@@ -0,0 +1,219 @@
+//! Checks every way of stepping the simulation against a slow but obviously
+//! correct reference, on seeded random boards. Set `LIFE_FUZZ_ITERATIONS` to
+//! run more (or fewer) boards than the default.
+
+use vec2::Vector2;
+
+use super::{
+    benchmark::{random_board_seeded, BOARD_SEED},
+    bounds::WorldBounds,
+    compute_step,
+    rules::Rules,
+    Cancel, LivingList,
+};
+
+/// How many boards each test runs when `LIFE_FUZZ_ITERATIONS` isn't set.
+const DEFAULT_ITERATIONS: u64 = 20;
+
+/// How many generations each board is stepped for.
+const GENERATIONS: u32 = 8;
+
+/// A spread of rules, including ones with no survival and ones where nothing
+/// dies, so that every neighbor count matters somewhere.
+const RULES: [&str; 5] = ["B3/S23", "B36/S23", "B3678/S34678", "B2/S", "B3/S012345678"];
+
+/// The number of boards to check, from `LIFE_FUZZ_ITERATIONS`.
+pub(super) fn iterations() -> u64 {
+    std::env::var("LIFE_FUZZ_ITERATIONS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_ITERATIONS)
+}
+
+/// A random board for iteration `i`, between 8 and 47 cells across.
+pub(super) fn soup(i: u64) -> LivingList {
+    random_board_seeded(8 + (i % 40) as u32, 0.25 + (i % 4) as f32 * 0.1, BOARD_SEED ^ i)
+}
+
+/// The rules for iteration `i`.
+pub(super) fn rules(i: u64) -> Rules {
+    Rules::from_bs_string(RULES[i as usize % RULES.len()]).unwrap()
+}
+
+/// Move every cell by `offset`.
+pub(super) fn translate(cells: &LivingList, offset: Vector2<i32>) -> LivingList {
+    cells.iter().map(|cell| *cell + offset).collect()
+}
+
+/// Compute the next generation by looking at all eight neighbors of every
+/// cell that could change. On a torus, neighbors that wrap to the same cell
+/// are only counted once, and a cell is never its own neighbor.
+pub(super) fn reference_step(
+    prev: &LivingList,
+    rules: &Rules,
+    bounds: &WorldBounds,
+) -> LivingList {
+    let wrap = |cell: Vector2<i32>| match *bounds {
+        WorldBounds::Infinite => cell,
+        WorldBounds::Torus { width, height } => {
+            Vector2::new(cell.x.rem_euclid(width), cell.y.rem_euclid(height))
+        }
+    };
+    let around = |cell: Vector2<i32>| {
+        let mut cells = Vec::with_capacity(9);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let neighbor = wrap(cell + Vector2::new(dx, dy));
+                if !cells.contains(&neighbor) {
+                    cells.push(neighbor);
+                }
+            }
+        }
+        cells
+    };
+
+    let candidates: LivingList = prev.iter().flat_map(|cell| around(*cell)).collect();
+    candidates
+        .into_iter()
+        .filter(|cell| {
+            let neighbors = around(*cell)
+                .into_iter()
+                .filter(|neighbor| neighbor != cell && prev.contains(neighbor))
+                .count();
+            rules.next_state(prev.contains(cell), neighbors as u32)
+        })
+        .collect()
+}
+
+/// Step `start` with `step` and the reference side by side, and fail on the
+/// first generation where they disagree.
+pub(super) fn assert_matches_reference(
+    name: &str,
+    i: u64,
+    start: &LivingList,
+    rules: &Rules,
+    bounds: &WorldBounds,
+    mut step: impl FnMut(&LivingList) -> LivingList,
+) {
+    let mut expected = start.clone();
+    let mut actual = start.clone();
+    for generation in 1..=GENERATIONS {
+        expected = reference_step(&expected, rules, bounds);
+        actual = step(&actual);
+        if actual != expected {
+            let missing = expected.difference(&actual).count();
+            let extra = actual.difference(&expected).count();
+            panic!(
+                "{name} differs from the reference on board {i} under {rules} at generation \
+                 {generation}: {missing} cells missing, {extra} extra"
+            );
+        }
+    }
+}
+
+#[test]
+fn unbounded_step_matches_reference() {
+    for i in 0..iterations() {
+        let rules = rules(i);
+        // Straddle the origin so that tiles at negative coordinates are
+        // covered too
+        let size = 8 + (i % 40) as i32;
+        let board = translate(&soup(i), Vector2::new(-size / 2, -size / 3));
+        let bounds = WorldBounds::Infinite;
+        assert_matches_reference("compute_step", i, &board, &rules, &bounds, |cells| {
+            compute_step(cells, &rules, &bounds)
+        });
+    }
+}
+
+#[test]
+fn torus_step_matches_reference() {
+    for i in 0..iterations() {
+        let rules = rules(i);
+        // Include boards too narrow for the neighbors to be distinct
+        let width = 1 + (i % 13) as i32;
+        let height = 1 + (i % 7) as i32 * 3;
+        let bounds = WorldBounds::Torus { width, height };
+        let board = soup(i).into_iter().filter(|cell| bounds.contains(*cell)).collect();
+        assert_matches_reference("compute_step", i, &board, &rules, &bounds, |cells| {
+            compute_step(cells, &rules, &bounds)
+        });
+    }
+}
+
+/// A GPU stepper on the default adapter, if there is one that can run compute
+/// shaders.
+#[cfg(not(target_arch = "wasm32"))]
+fn gpu_stepper() -> Option<super::compute::GpuStepper> {
+    use std::sync::Arc;
+
+    pollster::block_on(async {
+        let instance = wgpu::Instance::default();
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions::default())
+            .await?;
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor::default(), None)
+            .await
+            .ok()?;
+        super::compute::GpuStepper::new(&adapter, Arc::new(device), Arc::new(queue))
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn gpu_step_matches_reference() {
+    let Some(stepper) = gpu_stepper() else {
+        eprintln!("No adapter that can run compute shaders, skipping the GPU comparison");
+        return;
+    };
+
+    for i in 0..iterations() {
+        let rules = rules(i);
+        let board = translate(&soup(i), Vector2::new(-20, 5));
+        let bounds = WorldBounds::Infinite;
+        assert_matches_reference("GpuStepper", i, &board, &rules, &bounds, |cells| {
+            if cells.is_empty() {
+                return LivingList::default();
+            }
+            stepper.step(cells, &rules, &bounds).expect("the board should fit on the GPU")
+        });
+
+        // Including tori too thin for every neighbor to be a different cell
+        let bounds = WorldBounds::Torus {
+            width: [24, 2, 1][i as usize % 3],
+            height: 17,
+        };
+        let board = soup(i).into_iter().filter(|cell| bounds.contains(*cell)).collect();
+        assert_matches_reference("GpuStepper", i, &board, &rules, &bounds, |cells| {
+            stepper.step(cells, &rules, &bounds).expect("the torus should fit on the GPU")
+        });
+    }
+}
+
+/// Whichever backend is chosen, bounded boards are stepped correctly, falling
+/// back to the CPU where the GPU can't handle them.
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn chosen_backend_matches_reference() {
+    use super::compute::step_on;
+
+    let gpu = gpu_stepper();
+    for i in 0..iterations() {
+        let rules = rules(i);
+        let bounds = if i % 2 == 0 {
+            WorldBounds::Torus {
+                width: 24,
+                height: 17,
+            }
+        } else {
+            WorldBounds::Infinite
+        };
+        let board = soup(i).into_iter().filter(|cell| bounds.contains(*cell)).collect();
+        for stepper in [None, gpu.as_ref()] {
+            assert_matches_reference("step_on", i, &board, &rules, &bounds, |cells| {
+                step_on(stepper, cells, &rules, &bounds, &Cancel::NEVER).unwrap()
+            });
+        }
+    }
+}
@@ -5,7 +5,7 @@
 
 use winit::{
     event::*,
-    event_loop::EventLoop,
+    event_loop::{ControlFlow, EventLoop},
     keyboard::{Key, NamedKey},
     window::{Window, WindowBuilder},
 };
@@ -15,11 +15,26 @@ use wasm_bindgen::prelude::*;
 
 use std::sync::{Arc, Mutex};
 
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
 mod render;
-use render::RenderState;
+use render::{timing::Phase, RenderState};
 
 mod game;
 use game::GameState;
+pub use game::{bounds::WorldBounds, compute_step as next_generation, rules::Rules, LivingList};
+#[cfg(not(target_arch = "wasm32"))]
+pub use game::patterns::parse_pattern_file;
+
+mod export;
+
+mod storage;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod window_options;
 
 struct State<'a> {
     #[allow(dead_code)]
@@ -31,11 +46,21 @@ struct State<'a> {
 /// The number of cells that will fit across the height of the window by default
 const DEFAULT_GRID_SIZE: f32 = 10.0;
 
+/// How long to wait before checking for changes again when nothing needed to
+/// be redrawn
+const IDLE_REDRAW_DELAY: std::time::Duration = std::time::Duration::from_millis(16);
+
 impl<'a> State<'a> {
     /// Create a new state and get its accompanying event loop
     pub async fn new() -> (Self, EventLoop<()>) {
+        // Parsed first so that bad options are reported before anything opens
+        #[cfg(not(target_arch = "wasm32"))]
+        let window_options = window_options::WindowOptions::from_env();
         let event_loop = EventLoop::new().unwrap();
-        let window = WindowBuilder::new().build(&event_loop).unwrap();
+        let builder = WindowBuilder::new();
+        #[cfg(not(target_arch = "wasm32"))]
+        let builder = window_options.apply(builder);
+        let window = builder.build(&event_loop).unwrap();
         let window = Arc::new(window);
 
         #[cfg(target_arch = "wasm32")]
@@ -57,6 +82,8 @@ impl<'a> State<'a> {
             window.clone(),
             DEFAULT_GRID_SIZE.recip(),
         )));
+        #[cfg(target_arch = "wasm32")]
+        game_state.lock().unwrap().restore_session();
 
         let render_state = RenderState::new(
             window.clone(),
@@ -92,27 +119,78 @@ pub async fn run() {
     let (mut state, event_loop) = State::new().await;
 
     let mut surface_configured = false;
+    // Whether the player has been told to reload after losing the device
+    #[cfg(target_arch = "wasm32")]
+    let mut reported_device_loss = false;
 
     event_loop
         .run(move |event, control_flow| {
             // Update the game state. TODO: move this logic into rendering
             {
                 let mut game = state.game_state.lock().unwrap();
+                let update_start = Instant::now();
                 let game_changes = game.update();
-                if let Some(c) = game_changes.cells {
-                    state.render_state.update_cells(c);
-                }
+                state
+                    .render_state
+                    .add_time(Phase::Simulation, update_start.elapsed());
+                // Wrap ghosts are placed using the grid size, so it goes first
                 if let Some(v) = game_changes.grid_size {
                     state.render_state.change_grid_size(v);
                 }
+                if let Some(v) = game_changes.wrap_ghosts {
+                    state.render_state.set_wrap_ghosts(v);
+                }
+                if let Some(c) = game_changes.cells {
+                    state.render_state.update_cells(c);
+                }
                 if let Some(v) = game_changes.offset {
-                    let offset = vec2::Vector2::new(v.x as f32, v.y as f32);
+                    let offset = v.map(|c| c as f32);
                     state.render_state.update_offset(offset);
                 }
+                if let Some(v) = game_changes.cell_padding {
+                    state.render_state.set_cell_padding(v);
+                }
+                if let Some(v) = game_changes.clear_color {
+                    state.render_state.set_clear_color(v);
+                }
+                if let Some(v) = game_changes.cell_color {
+                    state.render_state.set_cell_color(v);
+                }
+                if let Some(v) = game_changes.cell_shape {
+                    state.render_state.set_cell_shape(v);
+                }
+                if let Some(v) = game_changes.grid_visible {
+                    state.render_state.set_grid_visible(v);
+                }
+                if let Some(v) = game_changes.overlay {
+                    state.render_state.update_overlay(v);
+                }
+                if let Some(v) = game_changes.start_recording {
+                    state.render_state.start_recording(v);
+                }
+                if game_changes.screenshot {
+                    state.render_state.save_screenshot();
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                if let Some(bytes) = game_changes.cell_texture
+                    && let Err(e) = state.render_state.set_cell_texture(&bytes)
+                {
+                    log::error!("Failed to load the cell texture: {e}");
+                    state.render_state.show_texture_error(e.to_string());
+                }
+                if !game_changes.events.is_empty() {
+                    state.render_state.handle_game_events(&game_changes.events);
+                }
             }
 
             let egui_captured = state.render_state.handle_event(&event);
 
+            // Check whether there's anything to draw once an idle wait is over
+            if let Event::NewEvents(StartCause::ResumeTimeReached { .. }) = event {
+                control_flow.set_control_flow(ControlFlow::Wait);
+                state.render_state.window().request_redraw();
+            }
+
             // Pass memory warnings to the log output
             if let Event::MemoryWarning = event {
                 log::warn!("Warning: low memory");
@@ -125,7 +203,8 @@ pub async fn run() {
                 && window_id == state.render_state.window().id()
             {
                 // If the gui didn't capture the event, then hand it to the game
-                // or, if it was the escape key, exit
+                // or, if it was the escape key, cancel whatever the game is
+                // doing or exit if there's nothing to cancel
                 if !egui_captured {
                     let mut game = state.game_state.lock().unwrap();
                     game.handle_window_event(event);
@@ -139,30 +218,88 @@ pub async fn run() {
                             },
                         ..
                     } = event
+                        && !game.cancel_pending()
                     {
                         #[cfg(not(target_arch = "wasm32"))]
                         control_flow.exit();
                     }
                 }
 
+                // Wake up from power saving on any input, including input to
+                // the GUI
+                if !matches!(event, WindowEvent::RedrawRequested) {
+                    state.render_state.window().request_redraw();
+                }
+
                 match event {
                     WindowEvent::CloseRequested => control_flow.exit(),
+                    // Dropped files are taken to be patterns to paste
+                    #[cfg(not(target_arch = "wasm32"))]
+                    WindowEvent::DroppedFile(path) => state.render_state.import_file(path),
                     WindowEvent::Resized(physical_size) => {
                         surface_configured = true;
                         state.render_state.resize(*physical_size);
                     }
                     WindowEvent::RedrawRequested => {
-                        // This tells winit that we want another frame after this one
-                        state.render_state.window().request_redraw();
-
                         // We can't draw if the surface is not properly configured
                         if !surface_configured {
+                            state.render_state.window().request_redraw();
                             return;
                         }
 
+                        // Reconfiguring won't help if the device itself is
+                        // gone, so start over with a new one
+                        if state.render_state.is_device_lost() {
+                            #[cfg(not(target_arch = "wasm32"))]
+                            pollster::block_on(state.render_state.recreate());
+                            // Getting a new device is asynchronous on the web,
+                            // so the page has to be reloaded instead. Say so
+                            // once, then stop drawing until it is.
+                            #[cfg(target_arch = "wasm32")]
+                            {
+                                if !reported_device_loss {
+                                    reported_device_loss = true;
+                                    log::error!(
+                                        "Graphics device lost; reload the page to continue"
+                                    );
+                                }
+                                control_flow.set_control_flow(ControlFlow::Wait);
+                                return;
+                            }
+                        }
+
+                        // Each chance to draw is a tick for deterministic
+                        // auto-play, whether or not anything is drawn
+                        state.game_state.lock().unwrap().tick();
+
+                        // If nothing changed, check again after a while
+                        // instead of drawing the same frame or spinning. When
+                        // nothing can change without input, stop checking
+                        // until an event arrives.
+                        if !state.render_state.needs_redraw() {
+                            let saving = state.game_state.lock().unwrap().is_power_saving();
+                            control_flow.set_control_flow(if saving {
+                                ControlFlow::Wait
+                            } else {
+                                ControlFlow::wait_duration(IDLE_REDRAW_DELAY)
+                            });
+                            return;
+                        }
+
+                        // This tells winit that we want another frame after this one
+                        state.render_state.window().request_redraw();
+
                         state.render_state.update();
                         match state.render_state.render() {
-                            Ok(_) => {}
+                            Ok(_) => {
+                                // Capture the generation that was just drawn
+                                // if a GIF is being recorded
+                                let mut game = state.game_state.lock().unwrap();
+                                let population = game.get_living_count();
+                                if state.render_state.record_frame(game.step_count, population) {
+                                    game.step();
+                                }
+                            }
                             // Reconfigure the surface if it's lost or outdated
                             Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
                                 state.render_state.reconfigure()
@@ -0,0 +1,71 @@
+// Lines along the cell boundaries, drawn over the background
+
+struct Offset {
+    data: vec2<f32>,
+    padding: vec2<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> offset: Offset;
+
+struct Size {
+    data: f32,
+    padding: f32,
+    padding2: vec2<f32>,
+}
+
+@group(1) @binding(0)
+var<uniform> grid_size: Size;
+
+struct Res {
+    data: vec2<f32>,
+    padding: vec2<f32>,
+}
+
+@group(2) @binding(0)
+var<uniform> res: Res;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(3) tex_coords: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.tex_coords = model.tex_coords;
+    out.clip_position = vec4<f32>(model.position, 1.0);
+    return out;
+}
+
+// Fragment shader
+
+const LINE_COLOR: vec4<f32> = vec4<f32>(0.5, 0.5, 0.5, 0.6);
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let res = res.data;
+    let x = in.tex_coords.x;
+    let y = in.tex_coords.y;
+    let a = res.x / res.y;
+    // The position in cells, the same way the background texture is placed
+    let cell = (vec2<f32>(0.5 + ((2 * x * a) - a) / 2, y) + offset.data) / grid_size.data;
+
+    // How far this pixel is from the nearest cell boundary in pixels, so the
+    // lines stay one pixel wide at any zoom
+    let per_pixel = fwidth(cell);
+    let to_edge = abs(fract(cell - 0.5) - 0.5) / per_pixel;
+    let line = 1.0 - clamp(min(to_edge.x, to_edge.y), 0.0, 1.0);
+
+    // Fade out the lines when cells are only a few pixels across, before they
+    // blur together
+    let fade = smoothstep(4.0, 8.0, 1.0 / per_pixel.y);
+    return vec4<f32>(LINE_COLOR.rgb, LINE_COLOR.a * line * fade);
+}
@@ -1,8 +1,7 @@
-use egui::{Color32, Context, Id, RichText, Slider, TexturesDelta, Ui};
+use egui::{Color32, Context, Id, RichText, Sense, Slider, TextEdit, TexturesDelta, Ui};
 
-#[cfg(feature = "saving")]
-use egui::TextEdit;
 use egui_commonmark::CommonMarkCache;
+use serde::{Deserialize, Serialize};
 #[cfg(feature = "saving")]
 use std::ops::DerefMut;
 
@@ -22,7 +21,17 @@ use winit::{
     event::{ElementState, Event},
 };
 
-use crate::game::GameState;
+use super::{
+    recording::{Corner, GifSettings},
+    timing::FrameTimings,
+};
+use vec2::Vector2;
+
+use crate::storage::DataHandle;
+use crate::game::{
+    benchmark::BenchmarkSettings, bounds::WorldBounds, compute::ComputeBackend, patterns,
+    presets::RulePresets, rules::Rules, step_diff::StepDiff, GameEvent, GameState, LivingList,
+};
 
 #[cfg(feature = "saving")]
 use crate::game::saving::SaveGame;
@@ -34,6 +43,10 @@ pub struct GuiState {
     device: Arc<Device>,
     start_time: Instant,
     window: Arc<winit::window::Window>,
+    /// Whether an event has been received since the last frame
+    received_event: bool,
+    /// When egui asked to be repainted next, if it did
+    repaint_at: Option<Instant>,
 }
 
 impl GuiState {
@@ -52,6 +65,9 @@ impl GuiState {
                 ..
             }
         );
+        if let Event::WindowEvent { .. } = event {
+            self.received_event = true;
+        }
         let captures = self.platform.captures_event(event);
         if !is_keyup {
             self.platform.handle_event(event);
@@ -84,9 +100,24 @@ impl GuiState {
             device,
             start_time: Instant::now(),
             window,
+            received_event: true,
+            repaint_at: None,
         }
     }
 
+    /// Get the game state that the interface controls.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn game_state(&self) -> Arc<Mutex<GameState>> {
+        Arc::clone(&self.app.game_state)
+    }
+
+    /// Take the interface's state from `old`, for when the renderer is
+    /// recreated.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn take_app(&mut self, old: &mut GuiState) {
+        std::mem::swap(&mut self.app, &mut old.app);
+    }
+
     pub fn render(
         &mut self,
         surface_config: &wgpu::SurfaceConfiguration,
@@ -105,6 +136,11 @@ impl GuiState {
         // End the UI frame. We could now handle the output and draw the UI with
         // the backend.
         let full_output = self.platform.end_frame(Some(&self.window));
+        self.received_event = false;
+        self.repaint_at = full_output
+            .viewport_output
+            .get(&egui::ViewportId::ROOT)
+            .and_then(|v| Instant::now().checked_add(v.repaint_delay));
         let paint_jobs = self
             .platform
             .context()
@@ -137,6 +173,59 @@ impl GuiState {
         (encoder, tdelta)
     }
 
+    /// Whether the GUI might look different if it were drawn again.
+    pub fn needs_repaint(&self) -> bool {
+        self.received_event || self.repaint_at.is_some_and(|t| t <= Instant::now())
+    }
+
+    /// Tell the GUI how far along the current GIF recording is, or `None` if
+    /// nothing is being recorded.
+    pub fn set_recording_progress(&mut self, progress: Option<(usize, usize)>) {
+        self.app.recording_progress = progress;
+    }
+
+    /// Show why the cell texture couldn't be loaded.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_texture_error(&mut self, error: String) {
+        self.app.texture_error = Some(error);
+    }
+
+    /// Play the cues for any game events that have them enabled.
+    pub fn handle_game_events(&mut self, events: &[GameEvent]) {
+        let settings = self.app.cue_settings;
+        if !events.iter().any(|e| settings.wants_cue(e)) {
+            return;
+        }
+        self.app.flash_started = Some(Instant::now());
+        // The terminal bell is the only sound available without an audio
+        // library
+        #[cfg(not(target_arch = "wasm32"))]
+        if settings.beep {
+            eprint!("\x07");
+        }
+    }
+
+    /// Load a pattern file, such as one dropped onto the window, into the
+    /// paste preview.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn import_file(&mut self, path: &std::path::Path) {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let result = std::fs::read(path)
+            .map_err(|e| patterns::PatternError::Unreadable(e.to_string()))
+            .and_then(|bytes| patterns::parse_pattern_file(&name, &bytes));
+        self.app.paste_preview = Some(result);
+        self.app.large_import_confirmed = false;
+        self.app.paste_text.clear();
+    }
+
+    /// Give the GUI the timings of the last frame to display.
+    pub fn set_frame_timings(&mut self, timings: FrameTimings) {
+        self.app.frame_timings = timings;
+    }
+
     pub fn remove_textures(&mut self, tdelta: TexturesDelta) {
         self.render_pass
             .remove_textures(tdelta)
@@ -150,8 +239,137 @@ impl From<Arc<Mutex<GameState>>> for Gui {
             game_state: from,
             #[cfg(feature = "saving")]
             new_save_name: String::new(),
+            #[cfg(feature = "saving")]
+            pending_load: None,
+            #[cfg(feature = "saving")]
+            adopt_save_settings: true,
             intro_text_open: true,
             commonmark_cache: CommonMarkCache::default(),
+            paste_text: String::new(),
+            paste_preview: None,
+            import_limit: DEFAULT_IMPORT_LIMIT,
+            large_import_confirmed: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            image_import: ImageImport::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            texture_error: None,
+            etch_threshold: 128,
+            scale_factor: 2,
+            go_to: GoTo::default(),
+            rules_text: Rules::default().to_string(),
+            shown_rules: Rules::default(),
+            rules_error: None,
+            rule_presets: RulePresets::load(),
+            new_preset_name: String::new(),
+            torus_size: DEFAULT_TORUS_SIZE,
+            gif_settings: GifSettings::default(),
+            recording_progress: None,
+            benchmark_settings: BenchmarkSettings::default(),
+            frame_timings: FrameTimings::default(),
+            stats_layout: STATS_LAYOUT_DATA.load().unwrap_or_default(),
+            cue_settings: CUE_SETTINGS_DATA.load().unwrap_or_default(),
+            flash_started: None,
+        }
+    }
+}
+
+/// Where the stats window's layout is stored between sessions.
+const STATS_LAYOUT_DATA: DataHandle = DataHandle::new("stats_layout");
+
+/// Which parts of the stats window are shown, and whether it can be moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+struct StatsLayout {
+    pinned: bool,
+    population: bool,
+    generation: bool,
+    bounding_box: bool,
+    clusters: bool,
+    spaceships: bool,
+    graph: bool,
+}
+
+impl Default for StatsLayout {
+    fn default() -> Self {
+        Self {
+            pinned: false,
+            population: true,
+            generation: true,
+            bounding_box: false,
+            clusters: false,
+            spaceships: false,
+            graph: true,
+        }
+    }
+}
+
+/// The options for turning an image into a pattern.
+#[cfg(not(target_arch = "wasm32"))]
+struct ImageImport {
+    path: String,
+    /// Pixels darker than this become living cells
+    threshold: u8,
+    /// The largest width or height of the pattern, in cells
+    max_size: u32,
+    /// Why the last image couldn't be imported, if it couldn't
+    error: Option<String>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for ImageImport {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            threshold: 128,
+            max_size: 200,
+            error: None,
+        }
+    }
+}
+
+/// The state of the dialog for running to a generation.
+#[derive(Default)]
+struct GoTo {
+    open: bool,
+    generation: u64,
+    /// The save to load before running, or `None` to run from the current
+    /// board
+    #[cfg(feature = "saving")]
+    save: Option<usize>,
+    /// Why the last attempt to go was rejected
+    error: Option<String>,
+}
+
+/// The size of a wrapping world until another is chosen
+const DEFAULT_TORUS_SIZE: [i32; 2] = [100, 100];
+
+/// How many cells a pattern can have before placing it has to be confirmed,
+/// unless that's changed
+const DEFAULT_IMPORT_LIMIT: usize = 1_000_000;
+
+/// Where the cue settings are stored between sessions.
+const CUE_SETTINGS_DATA: DataHandle = DataHandle::new("cues");
+
+/// How long a flash cue lasts.
+const FLASH_DURATION: std::time::Duration = std::time::Duration::from_millis(400);
+
+/// Which game events are called out with a cue. All are off by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+struct CueSettings {
+    stabilized: bool,
+    population_cap: bool,
+    board_emptied: bool,
+    /// Whether to beep as well as flash, on native
+    beep: bool,
+}
+
+impl CueSettings {
+    fn wants_cue(&self, event: &GameEvent) -> bool {
+        match event {
+            GameEvent::Stabilized { .. } => self.stabilized,
+            GameEvent::PopulationCapReached => self.population_cap,
+            GameEvent::BoardEmptied => self.board_emptied,
         }
     }
 }
@@ -162,8 +380,59 @@ struct Gui {
     game_state: Arc<Mutex<GameState>>,
     #[cfg(feature = "saving")]
     new_save_name: String,
+    /// The save waiting for the player to choose whether it replaces the
+    /// board or is merged into it
+    #[cfg(feature = "saving")]
+    pending_load: Option<usize>,
+    /// Whether the pending load switches to the rules and world bounds the
+    /// save was made with
+    #[cfg(feature = "saving")]
+    adopt_save_settings: bool,
     intro_text_open: bool,
     commonmark_cache: CommonMarkCache,
+    /// The contents of the pattern paste box
+    paste_text: String,
+    /// The pattern to be placed, either the result of parsing `paste_text`,
+    /// updated whenever it changes, or an imported image
+    paste_preview: Option<Result<LivingList, patterns::PatternError>>,
+    /// Patterns with more cells than this have to be confirmed before they
+    /// can be placed
+    import_limit: usize,
+    /// Whether placing the pattern in `paste_preview` was confirmed despite
+    /// it being over the import limit
+    large_import_confirmed: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    image_import: ImageImport,
+    /// Why the last cell texture couldn't be loaded, if it couldn't
+    #[cfg(not(target_arch = "wasm32"))]
+    texture_error: Option<String>,
+    /// Background texels darker than this become living cells when etching
+    etch_threshold: u8,
+    /// The factor to stretch patterns by
+    scale_factor: u32,
+    go_to: GoTo,
+    /// The rules being typed, in B/S notation
+    rules_text: String,
+    /// The game's rules when `rules_text` was last filled in from them
+    shown_rules: Rules,
+    /// Why the typed rules couldn't be applied
+    rules_error: Option<String>,
+    rule_presets: RulePresets,
+    /// The name to save the current rules under as a preset
+    new_preset_name: String,
+    /// The width and height to use when the world wraps around
+    torus_size: [i32; 2],
+    /// The options that the next GIF recording will use
+    gif_settings: GifSettings,
+    /// The captured and requested frame counts of the GIF being recorded
+    recording_progress: Option<(usize, usize)>,
+    benchmark_settings: BenchmarkSettings,
+    /// How long the parts of the last frame took
+    frame_timings: FrameTimings,
+    stats_layout: StatsLayout,
+    cue_settings: CueSettings,
+    /// When the current flash cue started, if one is showing
+    flash_started: Option<Instant>,
 }
 
 impl Gui {
@@ -194,14 +463,93 @@ impl Gui {
             if play_button.clicked() {
                 game.toggle_playing();
             }
-            // This is needed for two reasons:
+            let last_toggle = game.last_toggle().map(|t| t.step);
+            let undo_button = ui
+                .add_enabled(last_toggle.is_some(), egui::Button::new("Undo edit"))
+                .on_hover_text(match last_toggle {
+                    Some(step) if step < game.step_count => format!(
+                        "\u{26A0} {} generations have passed since this edit, so undoing it \
+                         won't restore the board as it was",
+                        game.step_count - step
+                    ),
+                    _ => "Flip the last cell you changed back".to_owned(),
+                });
+            if undo_button.clicked() {
+                game.undo_toggle();
+            }
+            if ui
+                .add_enabled(game.can_undo(), egui::Button::new("Undo"))
+                .on_hover_text("Put the board back as it was before the last edit (Ctrl+Z)")
+                .clicked()
+            {
+                game.undo();
+            }
+            if ui
+                .add_enabled(game.can_redo(), egui::Button::new("Redo"))
+                .on_hover_text("Make the last undone edit again (Ctrl+Y)")
+                .clicked()
+            {
+                game.redo();
+            }
+            if ui
+                .button("Go to...")
+                .on_hover_text("Run to a generation, optionally from a save")
+                .clicked()
+            {
+                self.go_to.open = true;
+            }
+            if ui
+                .button("Invert view")
+                .on_hover_text("Flip every cell in view (I)")
+                .clicked()
+            {
+                game.invert_visible();
+            }
+            if ui
+                .button("Fit to view")
+                .on_hover_text("Center on the living cells and zoom to fit them all (F)")
+                .clicked()
+            {
+                game.center_on_living();
+            }
+            if let Some((min, max)) = game.selection() {
+                ui.separator();
+                ui.label(format!("{}x{} selected", max.x - min.x + 1, max.y - min.y + 1));
+                if ui.button("Copy").on_hover_text("Copy the selected cells (Ctrl+C)").clicked() {
+                    game.copy_selection();
+                }
+                if ui
+                    .button("Cut")
+                    .on_hover_text("Copy the selected cells, then kill them (Ctrl+X)")
+                    .clicked()
+                {
+                    game.cut_selection();
+                }
+                if ui.button("Delete").on_hover_text("Kill the selected cells (Del)").clicked() {
+                    game.delete_selection();
+                }
+                if ui.button("Deselect").on_hover_text("Clear the selection (Esc)").clicked() {
+                    game.clear_selection();
+                }
+            }
+            if game.has_clipboard()
+                && ui
+                    .button("Paste")
+                    .on_hover_text("Place the copied cells with the mouse (Ctrl+V)")
+                    .clicked()
+            {
+                game.begin_paste_clipboard();
+            }
+            // This is needed for three reasons:
             // - We need to lie to the GUI slider for it to feel natural
             // - We can only set and get the interval through methods
+            // - `Duration::from_secs_f64` panics on NaN and negative values,
+            //   so those are ignored
             let speed_get_set = |set: Option<f64>| {
-                if let Some(v) = set {
-                    game.set_interval(std::time::Duration::from_secs_f64(v.powi(2)));
+                if let Some(interval) = set.and_then(slider_interval) {
+                    game.set_interval(interval);
                 }
-                game.get_interval().as_secs_f64().sqrt()
+                slider_position(game.get_interval())
             };
             ui.label("Speed: ");
             let speed_slider =
@@ -209,13 +557,353 @@ impl Gui {
                 .show_value(false)
                 .clamp_to_range(true);
             ui.add(speed_slider);
+
+            let mut speed_factor = game.get_speed_factor();
+            let speed_factor_drag = egui::DragValue::new(&mut speed_factor)
+                .clamp_range(1.01..=4.0)
+                .speed(0.01)
+                .prefix("\u{00D7}");
+            if ui
+                .add(speed_factor_drag)
+                .on_hover_text("How much each press of the up and down arrows changes the speed")
+                .changed()
+            {
+                game.set_speed_factor(speed_factor);
+            }
+
+            let mut following = game.is_following();
+            if ui
+                .checkbox(&mut following, "Follow pattern")
+                .on_hover_text("Keep the living cells centered as they move")
+                .changed()
+            {
+                game.set_following(following);
+            }
+
+            let mut catch_up = game.is_catching_up();
+            if ui
+                .checkbox(&mut catch_up, "Catch up")
+                .on_hover_text("Take steps that were missed because one was slow instead of skipping them")
+                .changed()
+            {
+                game.set_catching_up(catch_up);
+            }
+
+            let ticks = game.get_ticks_per_step();
+            let mut deterministic = ticks.is_some();
+            let toggled = ui
+                .checkbox(&mut deterministic, "Step every")
+                .on_hover_text("Take a generation every so many frames instead of by time, so runs don't depend on the machine's speed")
+                .changed();
+            let mut frames = ticks.unwrap_or(10);
+            let edited = ui
+                .add_enabled(
+                    deterministic,
+                    egui::DragValue::new(&mut frames)
+                        .clamp_range(1..=600)
+                        .suffix(" frames"),
+                )
+                .changed();
+            if toggled || edited {
+                game.set_ticks_per_step(deterministic.then_some(frames));
+            }
+
+            let mut counts = game.is_showing_neighbor_counts();
+            if ui
+                .checkbox(&mut counts, "Neighbor counts")
+                .on_hover_text("While stopped, color cells by how many living neighbors they have")
+                .changed()
+            {
+                game.set_showing_neighbor_counts(counts);
+            }
+
+            #[cfg(target_arch = "wasm32")]
+            {
+                let mut keep = game.is_keeping_session();
+                if ui
+                    .checkbox(&mut keep, "Keep on reload")
+                    .on_hover_text("Save the board and view so that reloading the page restores them")
+                    .changed()
+                {
+                    game.set_keeping_session(keep);
+                }
+            }
+
+            let mut grid = game.is_showing_grid();
+            if ui
+                .checkbox(&mut grid, "Grid")
+                .on_hover_text("Draw lines between the cells")
+                .changed()
+            {
+                game.set_showing_grid(grid);
+            }
+
+            if game.world_bounds() != WorldBounds::Infinite {
+                let mut ghosts = game.is_showing_wrap_ghosts();
+                if ui
+                    .checkbox(&mut ghosts, "Wrap ghosts")
+                    .on_hover_text("Faintly repeat the cells on each edge past the opposite edge, where they wrap around to")
+                    .changed()
+                {
+                    game.set_showing_wrap_ghosts(ghosts);
+                }
+            }
+
+            let mut centroid = game.is_showing_centroid();
+            if ui
+                .checkbox(&mut centroid, "Center of mass")
+                .on_hover_text("Mark the average position of the living cells and trail where it has been")
+                .changed()
+            {
+                game.set_showing_centroid(centroid);
+            }
+            if centroid {
+                let mut length = game.get_trail_length();
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut length)
+                            .clamp_range(0..=500)
+                            .suffix(" trail"),
+                    )
+                    .changed()
+                {
+                    game.set_trail_length(length);
+                }
+            }
+
+            let mut inertia = game.has_pan_inertia();
+            if ui
+                .checkbox(&mut inertia, "Pan inertia")
+                .on_hover_text("Keep the view gliding for a moment after a quick pan")
+                .changed()
+            {
+                game.set_pan_inertia(inertia);
+            }
+
+            let mut locked = game.is_camera_locked();
+            if ui
+                .checkbox(&mut locked, "Lock camera")
+                .on_hover_text("Ignore scrolling and dragging so the view can't be moved by accident")
+                .changed()
+            {
+                game.set_camera_locked(locked);
+            }
+            if locked {
+                let mut edit = game.can_edit_while_locked();
+                if ui
+                    .checkbox(&mut edit, "Allow edits")
+                    .on_hover_text("Let clicks toggle cells while the camera is locked")
+                    .changed()
+                {
+                    game.set_edit_while_locked(edit);
+                }
+            }
+
+            let mut paint = game.is_paint_mode();
+            if ui
+                .checkbox(&mut paint, "Paint")
+                .on_hover_text("Drag with the left button to bring cells to life, instead of clicking to toggle them")
+                .changed()
+            {
+                game.set_paint_mode(paint);
+            }
+
+            let mut backend = game.compute_backend();
+            let gpu_supported = game.supports_gpu_compute();
+            egui::ComboBox::from_label("Compute")
+                .selected_text(backend.name())
+                .show_ui(ui, |ui| {
+                    for option in ComputeBackend::ALL {
+                        let enabled = option != ComputeBackend::GpuCompute || gpu_supported;
+                        ui.add_enabled_ui(enabled, |ui| {
+                            ui.selectable_value(&mut backend, option, option.name());
+                        });
+                    }
+                })
+                .response
+                .on_hover_text("What steps the simulation. The GPU needs compute shaders, which the web version doesn't have");
+            if backend != game.compute_backend() {
+                game.set_compute_backend(backend);
+            }
+
+            // Show rules that changed some other way, such as by loading a save
+            if game.rules() != self.shown_rules {
+                self.shown_rules = game.rules();
+                self.rules_text = self.shown_rules.to_string();
+                self.rules_error = None;
+            }
+            ui.label("Rules: ");
+            let rules_edit = ui
+                .add(TextEdit::singleline(&mut self.rules_text).desired_width(70.0))
+                .on_hover_text(format!(
+                    "Running {}. Type rules like B36/S23 and press Enter to switch",
+                    game.rules()
+                ));
+            let submitted =
+                rules_edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if ui.button("Apply").clicked() || submitted {
+                match Rules::from_bs_string(&self.rules_text) {
+                    Ok(rules) => {
+                        game.set_rules(rules);
+                        self.rules_text = rules.to_string();
+                        self.shown_rules = rules;
+                        self.rules_error = None;
+                    }
+                    Err(e) => self.rules_error = Some(e.to_string()),
+                }
+            }
+            if let Some(error) = &self.rules_error {
+                ui.colored_label(Color32::RED, error);
+            }
+            let current = self
+                .rule_presets
+                .iter()
+                .find(|preset| preset.rules == game.rules())
+                .map_or_else(|| "Custom".to_owned(), |preset| preset.name);
+            egui::ComboBox::from_label("Preset")
+                .selected_text(current)
+                .show_ui(ui, |ui| {
+                    for preset in self.rule_presets.iter() {
+                        let text = format!("{} ({})", preset.name, preset.rules);
+                        if ui.selectable_label(preset.rules == game.rules(), text).clicked() {
+                            game.set_rules(preset.rules);
+                        }
+                    }
+                });
+            ui.add(
+                TextEdit::singleline(&mut self.new_preset_name)
+                    .hint_text("Preset name")
+                    .desired_width(90.0),
+            );
+            let name = self.new_preset_name.trim();
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new("Save preset"))
+                .on_hover_text("Keep the rules being run as a preset with this name")
+                .clicked()
+            {
+                self.rule_presets.add(name.to_owned(), game.rules());
+                self.new_preset_name.clear();
+            }
+
+            let bounds = game.world_bounds();
+            if let WorldBounds::Torus { width, height } = bounds {
+                self.torus_size = [width, height];
+            }
+            let mut wrap = bounds != WorldBounds::Infinite;
+            let mut changed = ui
+                .checkbox(&mut wrap, "Wrap around")
+                .on_hover_text("Make the world a torus, whose edges wrap around to the opposite side. Cells outside it are removed")
+                .changed();
+            if wrap {
+                let [width, height] = &mut self.torus_size;
+                changed |= ui.add(egui::DragValue::new(width).clamp_range(1..=100_000)).changed();
+                ui.label("x");
+                changed |= ui.add(egui::DragValue::new(height).clamp_range(1..=100_000)).changed();
+            }
+            if changed {
+                let [width, height] = self.torus_size;
+                game.set_world_bounds(if wrap {
+                    WorldBounds::Torus { width, height }
+                } else {
+                    WorldBounds::Infinite
+                });
+            }
+
+            ui.label("Cell gap: ");
+            let mut padding = game.get_cell_padding();
+            let padding_slider = Slider::new(&mut padding, 0.0..=super::MAX_CELL_PADDING)
+                .show_value(false);
+            if ui.add(padding_slider).changed() {
+                game.set_cell_padding(padding);
+            }
+
+            ui.label("Background: ");
+            let mut clear_color = game.get_clear_color();
+            let mut rgb = [clear_color[0], clear_color[1], clear_color[2]];
+            if ui.color_edit_button_rgb(&mut rgb).changed() {
+                clear_color[..3].copy_from_slice(&rgb);
+                game.set_clear_color(clear_color);
+            }
+
+            ui.label("Cells: ");
+            let mut cell_color = game.get_cell_color();
+            if ui
+                .color_edit_button_rgba_unmultiplied(&mut cell_color)
+                .on_hover_text("The color of solid cells, and of textured ones when zoomed far out")
+                .changed()
+            {
+                game.set_cell_color(cell_color);
+            }
+
+            ui.label("Shape: ");
+            let mut cell_shape = game.get_cell_shape();
+            for shape in super::CellShape::ALL {
+                ui.radio_value(&mut cell_shape, shape, shape.name());
+            }
+            if cell_shape != game.get_cell_shape() {
+                game.set_cell_shape(cell_shape);
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                if ui
+                    .button("Load texture\u{2026}")
+                    .on_hover_text("Draw textured cells with an image instead")
+                    .clicked()
+                    && let Some(path) = rfd::FileDialog::new()
+                        .set_title("Cell texture")
+                        .add_filter("Images", &["png", "jpg", "jpeg"])
+                        .pick_file()
+                {
+                    match std::fs::read(path) {
+                        Ok(bytes) => {
+                            game.set_cell_texture(bytes);
+                            self.texture_error = None;
+                        }
+                        Err(e) => self.texture_error = Some(e.to_string()),
+                    }
+                }
+                if let Some(e) = &self.texture_error {
+                    ui.colored_label(Color32::RED, e);
+                }
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            if ui
+                .button("Save Screenshot")
+                .on_hover_text("Save a PNG of the board as it's shown, without these panels")
+                .clicked()
+            {
+                game.save_screenshot();
+            }
         });
+        if let Some(note) = degraded_note(game.is_worker_degraded()) {
+            ui.colored_label(Color32::YELLOW, note);
+        }
     }
 
     /// Render the simulation statistics within some `Ui`.
     fn simulation_stats_ui(&mut self, ui: &mut Ui) {
         let mut game = self.game_state.lock().unwrap();
-        ui.label(format!("Living Cells: {}", game.get_living_count()));
+        let layout = &mut self.stats_layout;
+        let old_layout = *layout;
+        ui.collapsing("Layout", |ui| {
+            ui.checkbox(&mut layout.pinned, "Pin window");
+            ui.checkbox(&mut layout.population, "Population");
+            ui.checkbox(&mut layout.generation, "Generation");
+            ui.checkbox(&mut layout.bounding_box, "Bounding box");
+            ui.checkbox(&mut layout.clusters, "Clusters");
+            ui.checkbox(&mut layout.spaceships, "Spaceships");
+            ui.checkbox(&mut layout.graph, "Graph");
+        });
+        if *layout != old_layout {
+            STATS_LAYOUT_DATA.store(layout);
+        }
+        let layout = *layout;
+
+        if layout.population {
+            ui.label(format!("Living Cells: {}", game.get_living_count()));
+        }
         ui.vertical_centered(|ui| {
             let reset_button = ui.button(
                 RichText::new("Reset stats and graph")
@@ -228,7 +916,108 @@ impl Gui {
                 game.toggle_record.clear();
             }
         });
-        ui.label(format!("Total Steps: {} ", game.step_count));
+        if layout.generation {
+            ui.label(format!("Total Steps: {} ", game.step_count));
+            ui.label(format!("Speed: {:.1} generations/s", game.steps_per_second()))
+                .on_hover_text("How many generations were actually computed per second lately");
+        }
+        if layout.bounding_box
+            && let Some((min, max)) = patterns::bounding_box(game.living_cells())
+        {
+            ui.label(format!(
+                "Bounding box: {} x {}",
+                max.x - min.x + 1,
+                max.y - min.y + 1
+            ));
+        }
+        if layout.clusters {
+            ui.label(format!(
+                "Clusters: {}",
+                patterns::count_clusters(game.living_cells())
+            ));
+        }
+        // Spaceships are only looked for while they're shown
+        if layout.spaceships != game.is_detecting_spaceships() {
+            game.set_detecting_spaceships(layout.spaceships);
+        }
+        if layout.spaceships {
+            let spaceships = game.spaceships();
+            ui.label(format!("Spaceships: {}", spaceships.len()));
+            let mut velocities: Vec<String> = spaceships.iter().map(|s| s.velocity()).collect();
+            velocities.sort();
+            velocities.dedup();
+            for velocity in velocities {
+                let count = spaceships.iter().filter(|s| s.velocity() == velocity).count();
+                ui.label(format!("    {count} moving at {velocity}"));
+            }
+        }
+        match game.stable_period() {
+            Some(1) => {
+                ui.label("Stable: still life");
+            }
+            Some(p) => {
+                ui.label(format!("Stable: period {p} oscillator"));
+            }
+            None => (),
+        }
+        if let Some(cell) = game.hovered_cell() {
+            ui.label(format!(
+                "Hovered cell {} ({}): {} living neighbors",
+                cell,
+                if game.get_cell(cell) { "alive" } else { "dead" },
+                game.neighbors_alive(cell)
+            ));
+        }
+        if !game.is_playing() {
+            let (births, deaths) = game.preview_step();
+            ui.label(format!(
+                "Next step: {} born, {} dying",
+                births.len(),
+                deaths.len()
+            ));
+            if let Some(diff) = game.last_step() {
+                last_step_ui(ui, diff);
+            }
+        }
+        let timings = self.frame_timings;
+        ui.collapsing("Frame timing", |ui| {
+            ui.label(format!("Simulation: {:.2?}", timings.simulation));
+            ui.label(format!("Scene: {:.2?}", timings.scene));
+            ui.label(format!("GUI: {:.2?}", timings.gui));
+            ui.label(format!("Total: {:.2?}", timings.total()));
+        });
+        if ui
+            .button("Export population series")
+            .on_hover_text("Save the population of every generation as JSON for plotting elsewhere")
+            .clicked()
+        {
+            let filename = format!(
+                "population-{}.json",
+                chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+            );
+            let json = game.population_series().to_json();
+            if let Err(e) = crate::export::save_bytes(&filename, json.as_bytes()) {
+                log::error!("Failed to export population series: {e}");
+            }
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if ui
+            .button("Export CSV")
+            .on_hover_text("Save the population of every generation, and whether it was edited, as CSV")
+            .clicked()
+        {
+            let filename = format!(
+                "population-{}.csv",
+                chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+            );
+            let csv = game.export_history_csv();
+            if let Err(e) = crate::export::save_bytes(&filename, csv.as_bytes()) {
+                log::error!("Failed to export population history: {e}");
+            }
+        }
+        if !layout.graph {
+            return;
+        }
         let line_values = game
             .living_count_history
             .iter()
@@ -240,10 +1029,13 @@ impl Gui {
             .show_axes(false) // This was causing annoying margins
             .show(ui, |plot_ui| {
                 plot_ui.line(line);
-                for i in game.toggle_record.iter() {
-                    if *i != 0 {
+                // Stamping a pattern records many cells in the same step
+                let mut steps: Vec<u64> = game.toggle_record.iter().map(|t| t.step).collect();
+                steps.dedup();
+                for i in steps {
+                    if i != 0 {
                         plot_ui
-                            .vline(VLine::new(*i as f64).color(Color32::LIGHT_GREEN));
+                            .vline(VLine::new(i as f64).color(Color32::LIGHT_GREEN));
                     }
                 }
             });
@@ -260,11 +1052,50 @@ impl Gui {
             ui.horizontal(|ui| {
                 ui.label(&save.name);
                 ui.label(&save.created.format("%B %e").to_string());
-                if ui.button("Load").clicked() {
-                    game.load_save(&save);
+                let simulation = save.simulation();
+                let other_settings =
+                    simulation.has_other_settings(game.rules(), game.world_bounds());
+                if self.pending_load == Some(i) {
+                    if other_settings {
+                        ui.checkbox(
+                            &mut self.adopt_save_settings,
+                            format!("Use {} on {}", simulation.rules, simulation.bounds),
+                        )
+                        .on_hover_text("Switch to the rules and world the save was made with");
+                    }
+                    let adopt = other_settings && self.adopt_save_settings;
+                    if ui
+                        .button("Replace")
+                        .on_hover_text("Clear the board and load the save")
+                        .clicked()
+                    {
+                        game.load_save_index(i, adopt);
+                        self.pending_load = None;
+                    }
+                    if ui
+                        .button("Merge")
+                        .on_hover_text("Add the save's cells to the board")
+                        .clicked()
+                    {
+                        game.merge_save_index(i, adopt);
+                        self.pending_load = None;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.pending_load = None;
+                    }
+                } else if ui.button("Load").clicked() {
+                    // Only ask when there's something to lose
+                    if needs_load_prompt(game.get_living_count(), other_settings) {
+                        self.pending_load = Some(i);
+                        self.adopt_save_settings = true;
+                    } else {
+                        game.load_save_index(i, false);
+                    }
                 }
                 if ui.button(RichText::new("Delete").color(Color32::RED)).clicked() {
                     let _ = game.save_file.as_mut().unwrap().delete_save(i);
+                    // The indices after it have shifted
+                    self.pending_load = None;
                 }
             });
             if i == save_count - 1 {
@@ -281,6 +1112,599 @@ impl Gui {
                 );
             game.save_file.as_mut().unwrap().add_save(new_save);
         }
+        let mut wrap = game.is_wrapping_saves();
+        if ui
+            .checkbox(&mut wrap, "Wrap around")
+            .on_hover_text("Whether Page Up and Page Down wrap around at the first and last saves")
+            .changed()
+        {
+            game.set_wrapping_saves(wrap);
+        }
+        let mut snapshot = game.is_snapshotting_on_stable();
+        if ui
+            .checkbox(&mut snapshot, "Save when stable")
+            .on_hover_text("Save automatically when the board becomes a still life or oscillator")
+            .changed()
+        {
+            game.set_snapshotting_on_stable(snapshot);
+        }
+    }
+
+    /// Render the pattern paste box and a preview of the parsed pattern within
+    /// some `Ui`.
+    fn paste_ui(&mut self, ui: &mut Ui) {
+        /// The side length of the preview thumbnail in points
+        const THUMBNAIL_SIZE: f32 = 128.0;
+        /// How many cells scaling can make before it's worth a warning
+        const SCALE_WARNING_POPULATION: usize = 100_000;
+
+        {
+            let mut game = self.game_state.lock().unwrap();
+            if game.is_pasting() {
+                ui.horizontal(|ui| {
+                    ui.label("Click on the board to place the pattern");
+                    let transforms: [(&str, &str, patterns::Transform); 4] = [
+                        ("Rotate right", "Rotate clockwise (R)", patterns::rotate_cw),
+                        ("Rotate left", "Rotate counter-clockwise (Shift+R)", patterns::rotate_ccw),
+                        ("Mirror", "Flip left to right (H)", patterns::flip_horizontal),
+                        ("Flip", "Flip top to bottom (V)", patterns::flip_vertical),
+                    ];
+                    for (label, hover, transform) in transforms {
+                        if ui.button(label).on_hover_text(hover).clicked() {
+                            game.transform_clipboard(transform);
+                        }
+                    }
+                    if ui.button("Cancel").clicked() {
+                        game.cancel_paste();
+                    }
+                });
+            }
+            ui.horizontal(|ui| {
+                if ui
+                    .button("Export board")
+                    .on_hover_text("Save the living cells as a Life 1.06 pattern")
+                    .clicked()
+                {
+                    let filename = format!(
+                        "life-{}.lif",
+                        chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+                    );
+                    let pattern = patterns::to_life106(game.living_cells());
+                    if let Err(e) = crate::export::save_bytes(&filename, pattern.as_bytes()) {
+                        log::error!("Failed to export board: {e}");
+                    }
+                }
+
+                #[cfg(not(target_arch = "wasm32"))]
+                if ui
+                    .button("Copy as RLE")
+                    .on_hover_text("Copy the living cells as an RLE pattern, for other Life software")
+                    .clicked()
+                {
+                    let rle = patterns::to_rle(game.living_cells(), &game.rules());
+                    ui.output_mut(|o| o.copied_text = rle);
+                }
+
+                #[cfg(target_arch = "wasm32")]
+                if ui
+                    .button("Download as RLE")
+                    .on_hover_text("Save the living cells as an RLE pattern, for other Life software")
+                    .clicked()
+                {
+                    let filename = format!(
+                        "life-{}.rle",
+                        chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+                    );
+                    let rle = patterns::to_rle(game.living_cells(), &game.rules());
+                    if let Err(e) = crate::export::save_bytes(&filename, rle.as_bytes()) {
+                        log::error!("Failed to export board: {e}");
+                    }
+                }
+
+                #[cfg(not(target_arch = "wasm32"))]
+                if ui
+                    .button("Copy as JSON")
+                    .on_hover_text("Copy the living cells as an array of [x, y] pairs")
+                    .clicked()
+                {
+                    let json = patterns::to_json(game.living_cells());
+                    ui.output_mut(|o| o.copied_text = json);
+                }
+
+                #[cfg(target_arch = "wasm32")]
+                if ui
+                    .button("Download as JSON")
+                    .on_hover_text("Save the living cells as an array of [x, y] pairs")
+                    .clicked()
+                {
+                    let filename = format!(
+                        "life-{}.json",
+                        chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+                    );
+                    let json = patterns::to_json(game.living_cells());
+                    if let Err(e) = crate::export::save_bytes(&filename, json.as_bytes()) {
+                        log::error!("Failed to export board: {e}");
+                    }
+                }
+            });
+        }
+
+        let text_edit = TextEdit::multiline(&mut self.paste_text)
+            .hint_text("Paste an RLE, Life 1.06, plaintext, or JSON pattern, or an apgcode")
+            .code_editor()
+            .desired_rows(4)
+            .show(ui);
+        if text_edit.response.changed() {
+            self.paste_preview = if self.paste_text.trim().is_empty() {
+                None
+            } else {
+                Some(patterns::parse_pattern(&self.paste_text))
+            };
+            self.large_import_confirmed = false;
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        ui.collapsing("From an image", |ui| {
+            let import = &mut self.image_import;
+            TextEdit::singleline(&mut import.path)
+                .hint_text("Image path")
+                .show(ui);
+            ui.add(Slider::new(&mut import.threshold, 1..=255).text("Darkness threshold"));
+            ui.add(Slider::new(&mut import.max_size, 8..=1000).text("Max size"));
+            if ui.button("Import").clicked() {
+                match image::open(import.path.trim()) {
+                    Ok(image) => {
+                        let cells =
+                            patterns::from_image(&image, import.threshold, import.max_size);
+                        self.paste_preview = Some(Ok(cells));
+                        self.large_import_confirmed = false;
+                        self.paste_text.clear();
+                        import.error = None;
+                    }
+                    Err(e) => import.error = Some(e.to_string()),
+                }
+            }
+            if let Some(e) = &import.error {
+                ui.colored_label(Color32::RED, e);
+            }
+        });
+
+        ui.collapsing("From the background", |ui| {
+            ui.add(Slider::new(&mut self.etch_threshold, 1..=255).text("Darkness threshold"));
+            if ui
+                .button("Etch")
+                .on_hover_text("Bring visible cells to life where the background is dark")
+                .clicked()
+            {
+                self.game_state
+                    .lock()
+                    .unwrap()
+                    .etch_background(self.etch_threshold);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::DragValue::new(&mut self.scale_factor)
+                    .clamp_range(2..=16)
+                    .prefix("Scale \u{00D7}"),
+            );
+            if let Some(Ok(cells)) = &mut self.paste_preview
+                && ui
+                    .button("Scale pattern")
+                    .on_hover_text("Replace each cell of the pattern below with a block")
+                    .clicked()
+            {
+                *cells = patterns::scale_pattern(cells, self.scale_factor);
+                self.large_import_confirmed = false;
+            }
+            let mut game = self.game_state.lock().unwrap();
+            if ui
+                .button("Scale board")
+                .on_hover_text("Replace each living cell with a block")
+                .clicked()
+            {
+                game.scale_board(self.scale_factor);
+            }
+            let population = game.get_living_count() * (self.scale_factor as usize).pow(2);
+            if population >= SCALE_WARNING_POPULATION {
+                ui.colored_label(
+                    Color32::YELLOW,
+                    format!("Scaling the board makes {population} cells"),
+                );
+            }
+        });
+
+        let cells = match &self.paste_preview {
+            None => return,
+            Some(Err(e)) => {
+                ui.colored_label(Color32::RED, e.to_string());
+                return;
+            }
+            Some(Ok(cells)) => cells,
+        };
+
+        ui.label(format!("Population: {}", cells.len()));
+        if let Some((min, max)) = patterns::bounding_box(cells) {
+            ui.label(format!(
+                "Bounding box: {} x {}",
+                max.x - min.x + 1,
+                max.y - min.y + 1
+            ));
+        }
+
+        // Draw the thumbnail
+        let (response, painter) = ui.allocate_painter(
+            egui::vec2(THUMBNAIL_SIZE, THUMBNAIL_SIZE),
+            Sense::hover(),
+        );
+        let rect = response.rect;
+        painter.rect_filled(rect, 0.0, Color32::BLACK);
+        let (size, pixels) = patterns::rasterize(cells, THUMBNAIL_SIZE as usize);
+        let pixel_size = THUMBNAIL_SIZE / size.max(1) as f32;
+        let cell_color = {
+            let [r, g, b, _] = super::CELL_COLOR.map(|c| (c * 255.0) as u8);
+            Color32::from_rgb(r, g, b)
+        };
+        for (i, _) in pixels.iter().enumerate().filter(|(_, alive)| **alive) {
+            let min = rect.min
+                + egui::vec2((i % size) as f32, (i / size) as f32) * pixel_size;
+            painter.rect_filled(
+                egui::Rect::from_min_size(min, egui::vec2(pixel_size, pixel_size)),
+                0.0,
+                cell_color,
+            );
+        }
+
+        // Huge patterns could use up the memory, so make sure they're wanted
+        let too_large =
+            needs_import_confirmation(cells.len(), self.import_limit, self.large_import_confirmed);
+        if too_large {
+            ui.colored_label(
+                Color32::YELLOW,
+                format!(
+                    "This pattern has {} cells, more than the limit of {}.",
+                    cells.len(),
+                    self.import_limit
+                ),
+            );
+            if ui.button("Allow placing it").clicked() {
+                self.large_import_confirmed = true;
+            }
+        }
+
+        ui.add_enabled_ui(!too_large, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Place").clicked() {
+                    let mut game = self.game_state.lock().unwrap();
+                    let at = game.view_center_cell()
+                        - patterns::bounding_box(cells)
+                            .map(|(min, max)| (min + max) / 2)
+                            .unwrap_or_default();
+                    game.stamp_pattern(cells.clone(), at);
+                }
+                if ui
+                    .button("Place with mouse")
+                    .on_hover_text("Click on the board to place the pattern")
+                    .clicked()
+                {
+                    self.game_state.lock().unwrap().begin_paste(cells.clone());
+                }
+            });
+        });
+    }
+
+    /// Render the dialog for running to a generation within some `Ui`.
+    fn go_to_ui(&mut self, ui: &mut Ui) {
+        let mut game = self.game_state.lock().unwrap();
+        let go_to = &mut self.go_to;
+
+        if let Some(target) = game.target_generation() {
+            ui.label(format!(
+                "Running to generation {target}, now at {}. Press Escape to stop.",
+                game.step_count
+            ));
+        }
+        ui.horizontal(|ui| {
+            ui.label("Generation");
+            ui.add(egui::DragValue::new(&mut go_to.generation));
+        });
+
+        // Where the run starts, which is the save's generation if one is loaded
+        #[allow(unused_mut)]
+        let mut start = Some(game.step_count);
+        #[cfg(feature = "saving")]
+        {
+            let saves: Vec<_> = game
+                .save_file
+                .as_ref()
+                .map(|f| f.saves_iter().collect())
+                .unwrap_or_default();
+            let name = |i: Option<usize>| match i.and_then(|i| saves.get(i)) {
+                Some(save) => save.name.clone(),
+                None => "The current board".to_owned(),
+            };
+            egui::ComboBox::from_label("Start from")
+                .selected_text(name(go_to.save))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut go_to.save, None, name(None));
+                    for i in 0..saves.len() {
+                        ui.selectable_value(&mut go_to.save, Some(i), name(Some(i)));
+                    }
+                });
+            if let Some(i) = go_to.save {
+                start = saves.get(i).map(|s| s.simulation().generation);
+            }
+        }
+
+        if ui.button("Go").clicked() {
+            go_to.error = check_go_to(go_to.generation, start).err();
+            if go_to.error.is_none() {
+                #[cfg(feature = "saving")]
+                if let Some(i) = go_to.save {
+                    game.load_save_index(i, true);
+                }
+                game.run_to_generation(go_to.generation);
+                go_to.open = false;
+            }
+        }
+        if let Some(e) = &go_to.error {
+            ui.colored_label(Color32::RED, e);
+        }
+    }
+
+    /// Render the GIF recording controls within some `Ui`.
+    fn recording_ui(&mut self, ui: &mut Ui) {
+        if let Some((done, total)) = self.recording_progress {
+            ui.label(format!("Recording frame {done} of {total}..."));
+            return;
+        }
+
+        let settings = &mut self.gif_settings;
+        ui.add(Slider::new(&mut settings.frames, 2..=500).text("Generations"));
+        ui.add(Slider::new(&mut settings.fps, 1..=60).text("Frames per second"));
+        ui.add(Slider::new(&mut settings.scale, 0.1..=1.0).text("Resolution scale"));
+        ui.checkbox(&mut settings.deterministic, "Step once per frame")
+            .on_hover_text("Otherwise, auto-play is recorded as it runs");
+        let label = &mut settings.label;
+        ui.checkbox(&mut label.enabled, "Label frames with the generation");
+        ui.add_enabled_ui(label.enabled, |ui| {
+            ui.checkbox(&mut label.population, "Include the population");
+            egui::ComboBox::from_label("Label corner")
+                .selected_text(label.corner.name())
+                .show_ui(ui, |ui| {
+                    for corner in Corner::ALL {
+                        ui.selectable_value(&mut label.corner, corner, corner.name());
+                    }
+                });
+        });
+        if ui.button("Record GIF").clicked() {
+            let mut game = self.game_state.lock().unwrap();
+            game.start_recording(*settings);
+            self.recording_progress = Some((0, settings.frames as usize));
+        }
+    }
+
+    /// Render a map of the living cells and the part of the world in view
+    /// within some `Ui`. Clicking or dragging on it moves the view there.
+    fn minimap_ui(&mut self, ui: &mut Ui) {
+        /// The side length of the map in points
+        const MINIMAP_SIZE: f32 = 160.0;
+        /// The side length of the grid the cells are gathered into, so that
+        /// each square drawn can stand for any number of cells
+        const MINIMAP_BUCKETS: usize = 80;
+        /// How much room is left around what's shown, as a fraction of it
+        const MINIMAP_MARGIN: f64 = 0.05;
+
+        let mut game = self.game_state.lock().unwrap();
+        let (view_min, view_max) = game.visible_cell_bounds();
+        // Show both the living cells and the view, so the view can always be
+        // found
+        let (min, max) = match patterns::bounding_box(game.living_cells()) {
+            Some((min, max)) => (
+                Vector2::new(min.x.min(view_min.x), min.y.min(view_min.y)),
+                Vector2::new(max.x.max(view_max.x), max.y.max(view_max.y)),
+            ),
+            None => (view_min, view_max),
+        };
+        let width = (max.x - min.x + 1) as f64;
+        let height = (max.y - min.y + 1) as f64;
+        let span = width.max(height) * (1.0 + 2.0 * MINIMAP_MARGIN);
+        // The grid point at the map's top-left corner, with the shown area
+        // centered
+        let origin = Vector2::new(
+            min.x as f64 + (width - span) / 2.0,
+            min.y as f64 + (height - span) / 2.0,
+        );
+
+        let (response, painter) = ui.allocate_painter(
+            egui::vec2(MINIMAP_SIZE, MINIMAP_SIZE),
+            Sense::click_and_drag(),
+        );
+        let rect = response.rect;
+        let to_map = |x: f64, y: f64| {
+            rect.min
+                + egui::vec2(
+                    ((x - origin.x) / span) as f32 * MINIMAP_SIZE,
+                    ((y - origin.y) / span) as f32 * MINIMAP_SIZE,
+                )
+        };
+        painter.rect_filled(rect, 0.0, Color32::BLACK);
+
+        let mut buckets = vec![false; MINIMAP_BUCKETS * MINIMAP_BUCKETS];
+        let per_bucket = span / MINIMAP_BUCKETS as f64;
+        for cell in game.living_cells() {
+            let bx = (((cell.x as f64 - origin.x) / per_bucket) as usize).min(MINIMAP_BUCKETS - 1);
+            let by = (((cell.y as f64 - origin.y) / per_bucket) as usize).min(MINIMAP_BUCKETS - 1);
+            buckets[by * MINIMAP_BUCKETS + bx] = true;
+        }
+        let bucket_size = MINIMAP_SIZE / MINIMAP_BUCKETS as f32;
+        let cell_color = {
+            let [r, g, b, _] = game.get_cell_color().map(|c| (c * 255.0) as u8);
+            Color32::from_rgb(r, g, b)
+        };
+        for (i, _) in buckets.iter().enumerate().filter(|(_, alive)| **alive) {
+            let min = rect.min
+                + egui::vec2((i % MINIMAP_BUCKETS) as f32, (i / MINIMAP_BUCKETS) as f32)
+                    * bucket_size;
+            painter.rect_filled(
+                egui::Rect::from_min_size(min, egui::vec2(bucket_size, bucket_size)),
+                0.0,
+                cell_color,
+            );
+        }
+
+        let view = egui::Rect::from_min_max(
+            to_map(view_min.x as f64, view_min.y as f64),
+            to_map(view_max.x as f64 + 1.0, view_max.y as f64 + 1.0),
+        );
+        painter.rect_stroke(view, 0.0, egui::Stroke::new(1.0, Color32::WHITE));
+
+        if (response.clicked() || response.dragged())
+            && let Some(pos) = response.interact_pointer_pos()
+        {
+            let offset = (pos - rect.min) / MINIMAP_SIZE;
+            game.look_at(Vector2::new(
+                origin.x + offset.x as f64 * span,
+                origin.y + offset.y as f64 * span,
+            ));
+        }
+    }
+
+    /// Render the alert settings within some `Ui`.
+    fn alerts_ui(&mut self, ui: &mut Ui) {
+        let mut game = self.game_state.lock().unwrap();
+        ui.horizontal(|ui| {
+            let mut cap = game.get_population_cap();
+            let mut capped = cap.is_some();
+            ui.checkbox(&mut capped, "Stop at population");
+            let mut value = cap.unwrap_or(100_000);
+            ui.add_enabled(capped, egui::DragValue::new(&mut value).speed(100));
+            cap = capped.then_some(value.max(1));
+            if cap != game.get_population_cap() {
+                game.set_population_cap(cap);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Ask before placing patterns over");
+            ui.add(
+                egui::DragValue::new(&mut self.import_limit)
+                    .clamp_range(1..=usize::MAX)
+                    .speed(1000)
+                    .suffix(" cells"),
+            );
+        });
+        let mut phase_zero = game.is_pausing_at_phase_zero();
+        if ui
+            .checkbox(&mut phase_zero, "Pause oscillators at phase zero")
+            .on_hover_text("Once the board repeats itself, stop whenever it returns to the first state of the cycle")
+            .changed()
+        {
+            game.set_pausing_at_phase_zero(phase_zero);
+        }
+        ui.horizontal(|ui| {
+            let timeout = game.get_idle_timeout();
+            let mut enabled = timeout.is_some();
+            let toggled = ui
+                .checkbox(&mut enabled, "Pause after")
+                .on_hover_text("Pause auto-play when nobody has touched the board for a while")
+                .changed();
+            let mut minutes = timeout.map_or(10.0, |t| t.as_secs_f64() / 60.0);
+            let edited = ui
+                .add_enabled(
+                    enabled,
+                    egui::DragValue::new(&mut minutes)
+                        .clamp_range(0.1..=1440.0)
+                        .speed(0.1)
+                        .suffix(" min idle"),
+                )
+                .changed();
+            // Only set it on changes, since setting it restarts the countdown
+            if toggled || edited {
+                game.set_idle_timeout(
+                    enabled.then(|| std::time::Duration::from_secs_f64(minutes * 60.0)),
+                );
+            }
+        });
+        ui.horizontal(|ui| {
+            let after = game.get_power_save_after();
+            let mut enabled = after.is_some();
+            ui.checkbox(&mut enabled, "Save power after")
+                .on_hover_text("Stop redrawing until something happens once the board has sat still for a while");
+            let mut seconds = after.map_or(30.0, |a| a.as_secs_f64());
+            ui.add_enabled(
+                enabled,
+                egui::DragValue::new(&mut seconds)
+                    .clamp_range(1.0..=600.0)
+                    .speed(1.0)
+                    .suffix(" s still"),
+            );
+            let after = enabled.then(|| std::time::Duration::from_secs_f64(seconds));
+            if after != game.get_power_save_after() {
+                game.set_power_save_after(after);
+            }
+        });
+
+        ui.separator();
+        ui.label("Flash the screen when:");
+        let cues = &mut self.cue_settings;
+        let old_cues = *cues;
+        ui.checkbox(&mut cues.stabilized, "The board becomes stable");
+        ui.checkbox(&mut cues.population_cap, "The population cap is reached");
+        ui.checkbox(&mut cues.board_emptied, "The board becomes empty");
+        #[cfg(not(target_arch = "wasm32"))]
+        ui.checkbox(&mut cues.beep, "Beep too");
+        if *cues != old_cues {
+            CUE_SETTINGS_DATA.store(cues);
+        }
+    }
+
+    /// Draw the flash cue over everything, if one is showing.
+    fn flash_ui(&mut self, ctx: &Context) {
+        let Some(started) = self.flash_started else {
+            return;
+        };
+        let elapsed = started.elapsed();
+        if elapsed >= FLASH_DURATION {
+            self.flash_started = None;
+            return;
+        }
+        let fade = 1.0 - elapsed.as_secs_f32() / FLASH_DURATION.as_secs_f32();
+        let painter = ctx.layer_painter(egui::LayerId::new(
+            egui::Order::Foreground,
+            Id::new("flash_cue"),
+        ));
+        painter.rect_filled(
+            ctx.screen_rect(),
+            0.0,
+            Color32::from_white_alpha((fade * 96.0) as u8),
+        );
+        ctx.request_repaint();
+    }
+
+    fn benchmark_ui(&mut self, ui: &mut Ui) {
+        let mut game = self.game_state.lock().unwrap();
+        if game.is_benchmarking() {
+            ui.label("Running...");
+            return;
+        }
+
+        let settings = &mut self.benchmark_settings;
+        ui.add(Slider::new(&mut settings.size, 16..=2048).text("Board size"));
+        ui.add(Slider::new(&mut settings.density, 0.05..=0.95).text("Density"));
+        ui.add(Slider::new(&mut settings.steps, 1..=1000).text("Steps"));
+        if ui.button("Run").clicked() {
+            game.start_benchmark(*settings);
+        }
+
+        if let Some(summary) = game.benchmark_summary() {
+            ui.separator();
+            ui.label(format!("Steps: {}", summary.steps));
+            ui.label(format!("Min: {:.2?}", summary.min));
+            ui.label(format!("Avg: {:.2?}", summary.avg));
+            ui.label(format!("Max: {:.2?}", summary.max));
+            ui.label(format!("Final population: {}", summary.final_population));
+        }
     }
 
     /// Render the interface to an `Egui::Context`.
@@ -292,8 +1716,38 @@ impl Gui {
             .show(ctx, |ui| {
                 self.top_panel_ui(ui);
             });
+
+        // Briefly show the speed after it's changed with the keyboard
+        let speed = self.game_state.lock().unwrap().speed_indicator();
+        if let Some(speed) = speed {
+            egui::Area::new(Id::new("speed_indicator"))
+                .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -32.0])
+                .interactable(false)
+                .show(ctx, |ui| {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.label(format!("{speed:.1} steps/s"));
+                    });
+                });
+            // Keep repainting so that it disappears on time
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+        // Explain why auto-play stopped if nobody was around
+        let idle_paused = self.game_state.lock().unwrap().is_idle_paused();
+        if idle_paused {
+            egui::Area::new(Id::new("idle_prompt"))
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.label("Auto-play was paused after a while without input");
+                        if ui.button("Resume").clicked() {
+                            self.game_state.lock().unwrap().toggle_playing();
+                        }
+                    });
+                });
+        }
         // Collapsible window with statistics shown
         egui::Window::new("Simulation Stats")
+            .movable(!self.stats_layout.pinned)
             .show(ctx, |ui| {
                 self.simulation_stats_ui(ui);
             })
@@ -306,6 +1760,55 @@ impl Gui {
                 self.saving_ui(ui);
             });
 
+        // Collapsible window for pasting in patterns
+        egui::Window::new("Paste Pattern")
+            .default_open(false)
+            .show(ctx, |ui| {
+                self.paste_ui(ui);
+            });
+
+        // Collapsible window for recording GIFs
+        egui::Window::new("Record GIF")
+            .default_open(false)
+            .show(ctx, |ui| {
+                self.recording_ui(ui);
+            });
+
+        // Collapsible map of the whole board in the corner
+        egui::Window::new("Minimap")
+            .default_open(false)
+            .resizable(false)
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-8.0, -8.0])
+            .show(ctx, |ui| {
+                self.minimap_ui(ui);
+            });
+
+        // Collapsible window for alert settings
+        egui::Window::new("Alerts")
+            .default_open(false)
+            .show(ctx, |ui| {
+                self.alerts_ui(ui);
+            });
+
+        // Collapsible window for stress testing the simulation
+        egui::Window::new("Benchmark")
+            .default_open(false)
+            .show(ctx, |ui| {
+                self.benchmark_ui(ui);
+            });
+
+        let mut go_to_open = self.go_to.open;
+        egui::Window::new("Go To Generation")
+            .open(&mut go_to_open)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .collapsible(false)
+            .show(ctx, |ui| {
+                self.go_to_ui(ui);
+            });
+        // Going closes the window too
+        self.go_to.open &= go_to_open;
+
         egui::Window::new("Introduction").open(&mut self.intro_text_open)
             .resizable(false)
             .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
@@ -314,5 +1817,204 @@ impl Gui {
                 let cache = &mut self.commonmark_cache;
                 commonmark_str!("intro_text", ui, cache, "life/src/render/intro.md");
             });
+
+        self.flash_ui(ctx);
+    }
+}
+
+/// List the cells the last manual step changed.
+fn last_step_ui(ui: &mut Ui, diff: &StepDiff) {
+    ui.collapsing("What changed", |ui| {
+        ui.label(format!(
+            "Generation {}: {} born, {} died",
+            diff.generation,
+            diff.births.len(),
+            diff.deaths.len()
+        ));
+        egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+            for (heading, cells) in [("Born", &diff.births), ("Died", &diff.deaths)] {
+                ui.strong(heading);
+                for cell in cells {
+                    ui.label(format!("    {cell}"));
+                }
+            }
+        });
+        if ui.button("Export").clicked() {
+            let filename = format!("step-{}.json", diff.generation);
+            if let Err(e) = crate::export::save_bytes(&filename, diff.to_json().as_bytes()) {
+                log::error!("Failed to export step changes: {e}");
+            }
+        }
+    });
+}
+
+/// The auto-play interval a position on the speed slider stands for. The
+/// slider shows the square root of the interval so that it feels natural.
+/// Positions that aren't a valid duration, like NaN, are `None`.
+fn slider_interval(position: f64) -> Option<std::time::Duration> {
+    std::time::Duration::try_from_secs_f64(position.powi(2)).ok()
+}
+
+/// The position on the speed slider for an auto-play interval.
+fn slider_position(interval: std::time::Duration) -> f64 {
+    interval.as_secs_f64().sqrt()
+}
+
+/// Check that the simulation can run to `target` from generation `start`,
+/// which is `None` if the save it would start from no longer exists.
+fn check_go_to(target: u64, start: Option<u64>) -> Result<(), String> {
+    match start {
+        None => Err("That save no longer exists".to_owned()),
+        Some(start) if target < start => Err(format!(
+            "Generation {target} is before the start, generation {start}"
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Whether loading a save onto a board of `population` cells needs the player
+/// to choose how, because it would clear cells or the save was made under
+/// `other_settings` than the current rules and world bounds.
+#[cfg(feature = "saving")]
+fn needs_load_prompt(population: usize, other_settings: bool) -> bool {
+    population > 0 || other_settings
+}
+
+/// The warning shown when steps are computed on the main thread, if they are.
+fn degraded_note(worker_degraded: bool) -> Option<&'static str> {
+    worker_degraded.then_some(
+        "Steps are computed on the main thread here, so large boards will make \
+         everything stutter. Auto-play starts slower to compensate.",
+    )
+}
+
+/// Whether a pattern of `population` cells is over the import `limit` and
+/// hasn't been allowed anyway, so it can't be placed yet.
+fn needs_import_confirmation(population: usize, limit: usize, confirmed: bool) -> bool {
+    population > limit && !confirmed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{clamp_interval, MIN_INTERVAL, WORKER_DEGRADED};
+
+    #[test]
+    fn short_intervals_are_clamped() {
+        use std::time::Duration;
+
+        assert_eq!(clamp_interval(Duration::ZERO), MIN_INTERVAL);
+        assert_eq!(clamp_interval(Duration::from_micros(300)), MIN_INTERVAL);
+        assert_eq!(clamp_interval(Duration::from_millis(250)), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn slider_never_sets_zero_interval() {
+        for position in [0.0, -0.0, 1e-9, 0.01, 0.5, 1.0] {
+            let interval = clamp_interval(slider_interval(position).unwrap());
+            assert!(interval >= MIN_INTERVAL, "position {position}");
+            assert!(slider_position(interval) > 0.0);
+        }
+        for position in [f64::NAN, f64::INFINITY] {
+            assert_eq!(slider_interval(position), None);
+        }
+        // Positions the slider shows map back to themselves
+        let position = slider_position(slider_interval(0.3).unwrap());
+        assert!((position - 0.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stats_layout_restores() {
+        let layout = StatsLayout {
+            pinned: true,
+            graph: false,
+            clusters: true,
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&layout).unwrap();
+        assert_eq!(serde_json::from_str::<StatsLayout>(&json).unwrap(), layout);
+
+        // Flags missing from older saved layouts keep their defaults
+        let old: StatsLayout = serde_json::from_str(r#"{"pinned":true}"#).unwrap();
+        assert_eq!(old, StatsLayout { pinned: true, ..Default::default() });
+    }
+
+    #[test]
+    fn cues_follow_settings() {
+        let events = [
+            GameEvent::Stabilized { period: 2 },
+            GameEvent::PopulationCapReached,
+            GameEvent::BoardEmptied,
+        ];
+        let none = CueSettings::default();
+        assert!(events.iter().all(|e| !none.wants_cue(e)));
+
+        let only = |i| {
+            let settings = CueSettings {
+                stabilized: i == 0,
+                population_cap: i == 1,
+                board_emptied: i == 2,
+                beep: false,
+            };
+            events.iter().map(|e| settings.wants_cue(e)).collect::<Vec<_>>()
+        };
+        assert_eq!(only(0), [true, false, false]);
+        assert_eq!(only(1), [false, true, false]);
+        assert_eq!(only(2), [false, false, true]);
+    }
+
+    #[test]
+    fn degraded_note_follows_the_worker() {
+        assert!(degraded_note(false).is_none());
+        assert!(degraded_note(true).is_some_and(|note| note.contains("slower")));
+        // Only builds without a worker thread show it
+        assert_eq!(
+            degraded_note(WORKER_DEGRADED).is_some(),
+            !cfg!(feature = "native_threads")
+        );
+    }
+
+    #[test]
+    fn large_imports_need_confirmation() {
+        let limit = DEFAULT_IMPORT_LIMIT;
+        assert!(!needs_import_confirmation(5, limit, false));
+        assert!(!needs_import_confirmation(limit, limit, false));
+        assert!(needs_import_confirmation(limit + 1, limit, false));
+        assert!(!needs_import_confirmation(limit + 1, limit, true));
+    }
+
+    #[test]
+    fn go_to_rejects_invalid_targets() {
+        assert_eq!(check_go_to(10, Some(4)), Ok(()));
+        // Already there, so nothing needs to run
+        assert_eq!(check_go_to(4, Some(4)), Ok(()));
+        assert_eq!(
+            check_go_to(3, Some(4)),
+            Err("Generation 3 is before the start, generation 4".to_owned())
+        );
+        assert!(check_go_to(10, None).is_err());
+    }
+
+    #[cfg(feature = "saving")]
+    #[test]
+    fn loading_other_rules_asks_first() {
+        use crate::game::snapshot::SimulationSnapshot;
+
+        let highlife = Rules::from_bs_string("B36/S23").unwrap();
+        let cells = [Vector2::new(0, 0)].into_iter().collect();
+        let save = SimulationSnapshot::new(&cells, 7, highlife, WorldBounds::Infinite);
+        let conway = Rules::default();
+
+        // Even onto an empty board, the player picks which rules to keep
+        let other_settings = save.has_other_settings(conway, WorldBounds::Infinite);
+        assert!(needs_load_prompt(0, other_settings));
+        assert!(!needs_load_prompt(0, false));
+        assert!(needs_load_prompt(3, false));
+
+        let adopted = save.for_load(conway, WorldBounds::Infinite, true);
+        assert_eq!(adopted.rules, highlife);
+        let kept = save.for_load(conway, WorldBounds::Infinite, false);
+        assert_eq!(kept.rules, conway);
+        assert_eq!((kept.cells(), kept.generation), (cells, 7));
     }
 }
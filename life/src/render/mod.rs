@@ -1,18 +1,43 @@
 use std::{
-    iter,
-    sync::{Arc, Mutex},
+    io, iter,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
 };
 
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
 use wgpu::util::DeviceExt;
 use winit::window::Window;
 
 use crate::game::GameState;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::game::compute::GpuStepper;
 
 /// The color of living cells when using solid coloring instead of a texture
 pub const CELL_COLOR: [f32; 4] = [0.17, 0.65, 0.22, 1.0]; // #2CA738
+/// The color the frame is cleared to unless it's changed
+pub const CLEAR_COLOR: [f32; 4] = [0.1, 0.2, 0.3, 1.0];
 
 mod texture;
 
+pub mod recording;
+
+pub mod timing;
+
+fn to_wgpu_color([r, g, b, a]: [f32; 4]) -> wgpu::Color {
+    wgpu::Color {
+        r: r as f64,
+        g: g as f64,
+        b: b as f64,
+        a: a as f64,
+    }
+}
+
 /// A cell that will be rendered to the screen.
 ///
 /// Although the cell generally uses normalized device coordinates, it will
@@ -27,6 +52,10 @@ pub struct Cell {
 
 impl Cell {
     fn as_instance(&self, _radius: f32) -> Instance {
+        self.as_tinted_instance([0.0; 4])
+    }
+
+    fn as_tinted_instance(&self, tint: [f32; 4]) -> Instance {
         let normalized_location = [
             self.location[0] * 2.0 - 1.0,
             -1.0 * (self.location[1] * 2.0 - 1.0),
@@ -35,11 +64,99 @@ impl Cell {
         Instance {
             offset: normalized_location,
             center,
+            tint,
+        }
+    }
+}
+
+/// A cell drawn over the living cells in a solid color, used to highlight
+/// positions such as where a pasted pattern will land.
+#[derive(Debug)]
+pub struct OverlayCell {
+    pub cell: Cell,
+    /// The color of the cell. It should usually be translucent.
+    pub tint: [f32; 4],
+}
+
+/// The color of the copies of cells drawn past the opposite edge of a torus
+const WRAP_GHOST_TINT: [f32; 4] = [0.6, 0.6, 0.6, 0.35];
+
+/// The largest allowed cell padding, as a fraction of the cell's size.
+pub const MAX_CELL_PADDING: f32 = 0.4;
+
+/// How living cells are drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CellShape {
+    /// The cell texture, fading to the solid cell color when zoomed far out
+    #[default]
+    Textured,
+    /// A solid square in the cell color
+    Square,
+    /// A solid circle in the cell color
+    Circle,
+}
+
+impl CellShape {
+    pub const ALL: [Self; 3] = [Self::Textured, Self::Square, Self::Circle];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Textured => "Textured",
+            Self::Square => "Square",
+            Self::Circle => "Circle",
+        }
+    }
+}
+
+/// What the cell shader needs to know to draw the cell shape. The padding
+/// brings it to the 16 bytes WebGL requires of uniforms.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct ShapeUniform {
+    /// The shape, numbered in the order of `CellShape`'s variants
+    shape: u32,
+    /// The cell padding, to work out how many pixels across each quad is
+    cell_padding: f32,
+    padding: [f32; 2],
+}
+
+impl ShapeUniform {
+    fn new(shape: CellShape, cell_padding: f32) -> Self {
+        Self {
+            shape: shape as u32,
+            cell_padding,
+            padding: [0.0; 2],
         }
     }
 }
 
-fn cell_vertices(radius: f32) -> [Vertex; 6] {
+/// Make a bind group for sampling a texture in the cell or background shader.
+fn texture_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    texture: &texture::Texture,
+    label: &str,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&texture.view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(&texture.sampler),
+            },
+        ],
+        label: Some(label),
+    })
+}
+
+/// Get the vertices of a cell's quad. `padding` is the fraction of the cell's
+/// size to leave empty between it and its neighbors.
+fn cell_vertices(grid_size: f32, padding: f32) -> [Vertex; 6] {
+    let radius = grid_size * (1.0 - padding);
     [
         Vertex {
             position: [-radius, -radius, 0.0],
@@ -73,6 +190,9 @@ fn cell_vertices(radius: f32) -> [Vertex; 6] {
 struct Instance {
     offset: [f32; 2],
     center: [f32; 2],
+    /// A solid color to draw instead of the cell's texture. It is ignored if
+    /// fully transparent.
+    tint: [f32; 4],
 }
 
 impl Instance {
@@ -94,6 +214,12 @@ impl Instance {
                     shader_location: 2,
                     format: wgpu::VertexFormat::Float32x2,
                 },
+                // The tint
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
             ],
         }
     }
@@ -158,19 +284,285 @@ impl Vertex {
     }
 }
 
+/// Settings used when creating a `RenderState`. The defaults are what the app
+/// itself uses; use the builder methods to change them.
+#[derive(Debug, Clone)]
+pub struct RenderConfig {
+    /// The graphics APIs that may be used
+    backends: wgpu::Backends,
+    /// How many cells the instance buffers can hold before they're reallocated
+    start_capacity: u64,
+    /// How the instance buffers grow and shrink
+    buffer_policy: BufferPolicy,
+    /// The color the frame is cleared to before anything is drawn
+    clear_color: wgpu::Color,
+    /// The present mode to use if the surface supports it, otherwise `Fifo`
+    present_mode: wgpu::PresentMode,
+    /// The number of samples per pixel used for the scene. 1 disables
+    /// multisampling.
+    sample_count: u32,
+}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            backends: wgpu::Backends::PRIMARY,
+            #[cfg(target_arch = "wasm32")]
+            backends: wgpu::Backends::GL,
+            start_capacity: 0,
+            buffer_policy: BufferPolicy::default(),
+            clear_color: to_wgpu_color(CLEAR_COLOR),
+            present_mode: wgpu::PresentMode::Fifo,
+            sample_count: 1,
+        }
+    }
+}
+
+// Not every option is changed by the app itself
+#[allow(dead_code)]
+impl RenderConfig {
+    pub fn backends(mut self, backends: wgpu::Backends) -> Self {
+        self.backends = backends;
+        self
+    }
+
+    pub fn start_capacity(mut self, start_capacity: u64) -> Self {
+        self.start_capacity = start_capacity;
+        self
+    }
+
+    pub fn buffer_policy(mut self, buffer_policy: BufferPolicy) -> Self {
+        self.buffer_policy = buffer_policy;
+        self
+    }
+
+    pub fn clear_color(mut self, clear_color: wgpu::Color) -> Self {
+        self.clear_color = clear_color;
+        self
+    }
+
+    pub fn present_mode(mut self, present_mode: wgpu::PresentMode) -> Self {
+        self.present_mode = present_mode;
+        self
+    }
+
+    /// Set the number of samples per pixel. It must be supported by the
+    /// surface's format, which 1 and 4 always are.
+    pub fn sample_count(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+}
+
+/// How instance buffers are resized to fit the number of cells.
+#[derive(Debug, Clone, Copy)]
+pub struct BufferPolicy {
+    /// How much larger than the number of instances a buffer is made when it
+    /// has to be reallocated. Growing exponentially keeps reallocations rare.
+    pub growth_factor: f32,
+    /// The fraction of a buffer's capacity below which it's considered
+    /// underused. This should be well below `1 / growth_factor` so that a
+    /// freshly resized buffer isn't immediately underused.
+    pub shrink_below: f32,
+    /// How many updates in a row a buffer must be underused before it is
+    /// shrunk, or `None` to never shrink buffers.
+    pub shrink_after: Option<u32>,
+}
+
+impl Default for BufferPolicy {
+    fn default() -> Self {
+        Self {
+            growth_factor: 1.5,
+            shrink_below: 0.25,
+            shrink_after: Some(120),
+        }
+    }
+}
+
+/// A buffer of instances that is reallocated as the number of instances
+/// changes.
+struct InstanceBuffer {
+    buffer: wgpu::Buffer,
+    label: &'static str,
+    capacity: InstanceCapacity,
+}
+
+impl InstanceBuffer {
+    fn new(device: &wgpu::Device, label: &'static str, capacity: u64) -> Self {
+        Self {
+            buffer: create_instance_buffer(device, label, capacity),
+            label,
+            capacity: InstanceCapacity::new(capacity),
+        }
+    }
+
+    /// Write instances to the buffer, replacing it with a larger one if its
+    /// capacity is insufficient, or with a smaller one if it has been mostly
+    /// empty for a while.
+    fn write(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        policy: &BufferPolicy,
+        instances: &[Instance],
+    ) {
+        if let Some(capacity) = self.capacity.fit(policy, instances.len() as u64) {
+            self.buffer = create_instance_buffer(device, self.label, capacity);
+        }
+
+        queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(instances));
+    }
+
+    /// Write living cells, followed by their wrap ghosts if they're on a torus
+    /// of size `ghost_torus`, and return how many instances were written.
+    fn write_cells(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        policy: &BufferPolicy,
+        cells: &[Cell],
+        ghost_torus: Option<vec2::Vector2<i32>>,
+        grid_size: f32,
+    ) -> u32 {
+        let mut instances = cells
+            .iter()
+            .map(|c| c.as_instance(grid_size))
+            .collect::<Vec<_>>();
+        if let Some(torus) = ghost_torus {
+            instances.extend(
+                wrap_ghosts(cells, torus, grid_size)
+                    .iter()
+                    .map(|c| c.as_tinted_instance(WRAP_GHOST_TINT)),
+            );
+        }
+        self.write(device, queue, policy, &instances);
+        instances.len() as u32
+    }
+
+    /// Read back the first `count` instances.
+    #[cfg(test)]
+    fn read(&self, device: &wgpu::Device, queue: &wgpu::Queue, count: u32) -> Vec<Instance> {
+        let size = std::mem::size_of::<Instance>() as u64 * count as u64;
+        let staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Instance Readback Buffer"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&Default::default());
+        encoder.copy_buffer_to_buffer(&self.buffer, 0, &staging, 0, size);
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = staging.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |res| res.unwrap());
+        device.poll(wgpu::Maintain::Wait);
+        let instances = bytemuck::cast_slice(&slice.get_mapped_range()).to_vec();
+        staging.unmap();
+        instances
+    }
+}
+
+/// Tracks whether anything that affects the scene has changed since the last
+/// render, so that frames where nothing has can be skipped.
+#[derive(Debug)]
+struct DirtyTracker {
+    dirty: bool,
+}
+
+impl DirtyTracker {
+    /// Start dirty, since nothing has been rendered yet.
+    fn new() -> Self {
+        Self { dirty: true }
+    }
+
+    fn mark(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Record a change of grid size, returning whether it should be used.
+    /// Sizes that aren't positive are ignored and leave the scene unchanged.
+    fn grid_size_changed(&mut self, new: f32) -> bool {
+        if new <= 0.0 {
+            return false;
+        }
+        self.mark();
+        true
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Record that the scene was just rendered.
+    fn rendered(&mut self) {
+        self.dirty = false;
+    }
+}
+
+/// Tracks how many instances a buffer has room for and decides when it should
+/// be reallocated.
+#[derive(Debug)]
+struct InstanceCapacity {
+    capacity: u64,
+    /// The smallest the buffer will shrink to
+    min_capacity: u64,
+    /// How many updates in a row the buffer has been underused for
+    underused_updates: u32,
+}
+
+impl InstanceCapacity {
+    fn new(capacity: u64) -> Self {
+        Self {
+            capacity,
+            min_capacity: capacity,
+            underused_updates: 0,
+        }
+    }
+
+    /// Record an update of `count` instances, and return the capacity to
+    /// reallocate the buffer with if it's too small or has been underused for
+    /// long enough.
+    fn fit(&mut self, policy: &BufferPolicy, count: u64) -> Option<u64> {
+        let fitted_size = ((count as f32 * policy.growth_factor) as u64)
+            .max(count)
+            .max(self.min_capacity);
+
+        if (count as f32) < self.capacity as f32 * policy.shrink_below {
+            self.underused_updates += 1;
+        } else {
+            self.underused_updates = 0;
+        }
+        let should_shrink = policy
+            .shrink_after
+            .is_some_and(|after| self.underused_updates >= after)
+            && fitted_size < self.capacity;
+
+        if count > self.capacity || should_shrink {
+            self.capacity = fitted_size;
+            self.underused_updates = 0;
+            Some(fitted_size)
+        } else {
+            None
+        }
+    }
+}
+
 /// A struct that holds the core of the render state.
 struct RenderCore<'a> {
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    instance: Arc<wgpu::Instance>,
     surface: Arc<wgpu::Surface<'a>>,
     device: Arc<wgpu::Device>,
-    queue: wgpu::Queue,
+    queue: Arc<wgpu::Queue>,
     config: wgpu::SurfaceConfiguration,
 }
 
 /// The buffers, bind groups, and textures that the renderer requires
 struct BuffersAndGroups {
     vertex_buffer: wgpu::Buffer,
-    instance_buffer: wgpu::Buffer,
-    instance_buffer_capacity: u64,
+    instance_buffer: InstanceBuffer,
+    overlay_buffer: InstanceBuffer,
 
     #[allow(dead_code)]
     radius_buffer: wgpu::Buffer,
@@ -178,6 +570,8 @@ struct BuffersAndGroups {
 
     #[allow(dead_code)]
     color_buffer: wgpu::Buffer,
+    shape_buffer: wgpu::Buffer,
+    /// Holds both the color and the shape buffers
     color_bind_group: wgpu::BindGroup,
 
     res_buffer: wgpu::Buffer,
@@ -186,6 +580,8 @@ struct BuffersAndGroups {
     #[allow(dead_code)]
     diffuse_texture: texture::Texture,
     diffuse_bind_group: wgpu::BindGroup,
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    texture_bind_group_layout: wgpu::BindGroupLayout,
 
     #[allow(dead_code)]
     bg_texture: texture::Texture,
@@ -208,10 +604,48 @@ pub struct RenderState<'a> {
     window: Arc<Window>,
     num_vertices: u32,
     cells: Vec<Cell>,
+    /// The number of overlay cells in the overlay buffer
+    overlay_count: u32,
+    /// How many instances of living cells, including wrap ghosts, are in the
+    /// instance buffer
+    cell_instance_count: u32,
+    /// The size in cells of the torus whose edge cells are copied past the
+    /// opposite edges, if they are
+    wrap_ghosts: Option<vec2::Vector2<i32>>,
     grid_size: f32,
+    /// The fraction of each cell's size left empty around it
+    cell_padding: f32,
+    cell_shape: CellShape,
+    /// The image living cells are drawn with, if it isn't the built-in one
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    cell_texture: Option<Vec<u8>>,
     rsc: BuffersAndGroups,
     bg_render_pipeline: wgpu::RenderPipeline,
+    /// Draws lines along the cell boundaries over the background
+    grid_render_pipeline: wgpu::RenderPipeline,
+    /// Whether the grid lines are drawn
+    grid_visible: bool,
     egui: gui::GuiState,
+    /// The GIF currently being recorded, if any
+    recorder: Option<recording::GifRecorder>,
+    dirty: DirtyTracker,
+    clear_color: wgpu::Color,
+    /// The solid color of cells at far zooms, as last written to the color
+    /// buffer
+    cell_color: [f32; 4],
+    sample_count: u32,
+    /// The multisampled texture the scene is drawn to before being resolved
+    /// to the frame, if multisampling is enabled
+    msaa_view: Option<wgpu::TextureView>,
+    /// How long the parts of each frame are taking
+    timer: timing::FrameTimer,
+    /// The settings the renderer was created with
+    render_config: RenderConfig,
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    offset: vec2::Vector2<f32>,
+    /// Set when the graphics device stops working, so that the renderer can
+    /// be recreated
+    device_lost: Arc<AtomicBool>,
 }
 
 impl<'a> RenderState<'a> {
@@ -223,26 +657,60 @@ impl<'a> RenderState<'a> {
     ///
     /// grid_size:
     /// The size of each grid cell as a fraction of the viewport's height.
+    ///
+    /// start_capacity:
+    /// How many cells the renderer will have room for before reallocating.
     pub async fn new(
         window: Arc<Window>,
         grid_size: f32,
         start_capacity: u64,
         game_state: Arc<Mutex<GameState>>,
     ) -> RenderState<'a> {
-        let size = window.inner_size();
+        let config = RenderConfig::default().start_capacity(start_capacity);
+        Self::with_config(window, grid_size, game_state, config).await
+    }
 
+    /// Create a new `RenderState` with non-default settings. See
+    /// [`RenderState::new`] for the other arguments.
+    pub async fn with_config(
+        window: Arc<Window>,
+        grid_size: f32,
+        game_state: Arc<Mutex<GameState>>,
+        render_config: RenderConfig,
+    ) -> RenderState<'a> {
         // The instance is a handle to our GPU
-        // BackendBit::PRIMARY => Vulkan + Metal + DX12 + Browser WebGPU
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            #[cfg(not(target_arch = "wasm32"))]
-            backends: wgpu::Backends::PRIMARY,
-            #[cfg(target_arch = "wasm32")]
-            backends: wgpu::Backends::GL,
+            backends: render_config.backends,
             ..Default::default()
         });
 
         let surface = instance.create_surface(window.clone()).unwrap();
 
+        Self::with_surface(
+            Arc::new(instance),
+            Arc::new(surface),
+            window,
+            grid_size,
+            game_state,
+            render_config,
+        )
+        .await
+    }
+
+    /// Create a new `RenderState` that draws to an existing surface, with a
+    /// new device.
+    async fn with_surface(
+        instance: Arc<wgpu::Instance>,
+        surface: Arc<wgpu::Surface<'a>>,
+        window: Arc<Window>,
+        grid_size: f32,
+        game_state: Arc<Mutex<GameState>>,
+        render_config: RenderConfig,
+    ) -> RenderState<'a> {
+        let size = window.inner_size();
+        let start_capacity = render_config.start_capacity;
+        let sample_count = render_config.sample_count;
+
         let adapter = instance
             .request_adapter(&wgpu::RequestAdapterOptions {
                 power_preference: wgpu::PowerPreference::default(),
@@ -284,6 +752,20 @@ impl<'a> RenderState<'a> {
             .await
             .unwrap();
 
+        // Dropping or replacing the device also calls this, but those are on
+        // purpose
+        let device_lost = Arc::new(AtomicBool::new(false));
+        {
+            let device_lost = Arc::clone(&device_lost);
+            device.set_device_lost_callback(move |reason, message| {
+                use wgpu::DeviceLostReason as Reason;
+                if matches!(reason, Reason::Unknown | Reason::DeviceInvalid) {
+                    log::error!("Graphics device lost: {message}");
+                    device_lost.store(true, Ordering::Relaxed);
+                }
+            });
+        }
+
         let surface_caps = surface.get_capabilities(&adapter);
         // Shader code in this assumes an Srgb surface texture. Using a different
         // one will result all the colors comming out darker. If we want to support non
@@ -294,12 +776,21 @@ impl<'a> RenderState<'a> {
             .copied()
             .find(|f| f.is_srgb())
             .unwrap_or(surface_caps.formats[0]);
+        // Fifo is the only present mode that's guaranteed to be supported
+        let present_mode = if surface_caps
+            .present_modes
+            .contains(&render_config.present_mode)
+        {
+            render_config.present_mode
+        } else {
+            wgpu::PresentMode::Fifo
+        };
         let config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format: surface_format,
             width: size.width,
             height: size.height,
-            present_mode: wgpu::PresentMode::Fifo,
+            present_mode,
             alpha_mode: surface_caps.alpha_modes[0],
             view_formats: vec![],
             desired_maximum_frame_latency: 2,
@@ -369,13 +860,19 @@ impl<'a> RenderState<'a> {
         let color_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Color Buffer"),
             contents: bytemuck::cast_slice(&CELL_COLOR),
-            usage: wgpu::BufferUsages::UNIFORM,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        // The shape goes in the same group, since there are only so many
+        let shape_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Shape Buffer"),
+            contents: bytemuck::cast_slice(&[ShapeUniform::new(CellShape::default(), 0.0)]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
         let color_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 label: Some("Color Bind Group Layout"),
-                entries: &[wgpu::BindGroupLayoutEntry {
-                    binding: 0,
+                entries: &[0, 1].map(|binding| wgpu::BindGroupLayoutEntry {
+                    binding,
                     visibility: wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Buffer {
                         ty: wgpu::BufferBindingType::Uniform,
@@ -383,29 +880,25 @@ impl<'a> RenderState<'a> {
                         min_binding_size: None,
                     },
                     count: None,
-                }],
+                }),
             });
         let color_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Color Bind Group"),
             layout: &color_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: color_buffer.as_entire_binding(),
-            }],
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: color_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: shape_buffer.as_entire_binding(),
+                },
+            ],
         });
 
-        let instances: Vec<Instance> = Vec::new();
-
-        let instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Instance Buffer"),
-            // size: std::mem::size_of::<Instance>() as u64 * 80u64,
-            size: std::mem::size_of::<Instance>() as u64 * start_capacity,
-            usage: wgpu::BufferUsages::VERTEX
-                | wgpu::BufferUsages::COPY_DST
-                | wgpu::BufferUsages::COPY_SRC,
-            mapped_at_creation: false,
-        });
-        queue.write_buffer(&instance_buffer, 0, bytemuck::cast_slice(&instances));
+        let instance_buffer = InstanceBuffer::new(&device, "Instance Buffer", start_capacity);
+        let overlay_buffer = InstanceBuffer::new(&device, "Overlay Buffer", start_capacity);
 
         let diffuse_bytes = include_bytes!("../../rsc/live.png");
         let diffuse_texture =
@@ -434,40 +927,24 @@ impl<'a> RenderState<'a> {
                 label: Some("texture_bind_group_layout"),
             });
 
-        let diffuse_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &texture_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&diffuse_texture.view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&diffuse_texture.sampler),
-                },
-            ],
-            label: Some("diffuse_bind_group"),
-        });
+        let diffuse_bind_group = texture_bind_group(
+            &device,
+            &texture_bind_group_layout,
+            &diffuse_texture,
+            "diffuse_bind_group",
+        );
 
         let bg_texture_bytes = include_bytes!("../../rsc/dead.png");
         let bg_texture =
             texture::Texture::from_bytes(&device, &queue, bg_texture_bytes, "dead.png").unwrap();
-        let bg_texture_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &texture_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&bg_texture.view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&bg_texture.sampler),
-                },
-            ],
-            label: Some("bg_texture_bind_group"),
-        });
+        let bg_texture_bind_group = texture_bind_group(
+            &device,
+            &texture_bind_group_layout,
+            &bg_texture,
+            "bg_texture_bind_group",
+        );
 
-        let vertices = cell_vertices(grid_size);
+        let vertices = cell_vertices(grid_size, 0.0);
 
         let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Vertex Buffer"),
@@ -568,7 +1045,7 @@ impl<'a> RenderState<'a> {
             },
             depth_stencil: None,
             multisample: wgpu::MultisampleState {
-                count: 1,
+                count: sample_count,
                 mask: !0,
                 alpha_to_coverage_enabled: false,
             },
@@ -623,17 +1100,80 @@ impl<'a> RenderState<'a> {
             },
             depth_stencil: None,
             multisample: wgpu::MultisampleState {
-                count: 1,
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+
+        let grid_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Grid Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("./grid.wgsl").into()),
+        });
+        let grid_render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Grid Render Pipeline Layout"),
+                bind_group_layouts: &[
+                    &offset_bind_group_layout,
+                    &grid_size_bind_group_layout,
+                    &res_bind_group_layout,
+                ],
+                push_constant_ranges: &[],
+            });
+        let grid_render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Grid Render Pipeline"),
+            layout: Some(&grid_render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &grid_shader,
+                entry_point: "vs_main",
+                buffers: &[Vertex::desc()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &grid_shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: config.format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(wgpu::Face::Back),
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
                 mask: !0,
                 alpha_to_coverage_enabled: false,
             },
             multiview: None,
         });
 
-        let surface = Arc::new(surface);
+        let msaa_view = create_msaa_view(&device, &config, sample_count);
+
         let device = Arc::new(device);
+        let queue = Arc::new(queue);
+
+        // Give the game its own way of stepping on this device, replacing any
+        // from a device that was lost
+        #[cfg(not(target_arch = "wasm32"))]
+        game_state.lock().unwrap().set_gpu_stepper(GpuStepper::new(
+            &adapter,
+            Arc::clone(&device),
+            Arc::clone(&queue),
+        ));
 
         let core = RenderCore {
+            instance,
             surface,
             device,
             queue,
@@ -643,12 +1183,13 @@ impl<'a> RenderState<'a> {
         let bag = BuffersAndGroups {
             vertex_buffer,
             instance_buffer,
-            instance_buffer_capacity: start_capacity,
+            overlay_buffer,
 
             radius_buffer: grid_size_buffer,
             radius_bind_group: grid_size_bind_group,
 
             color_buffer,
+            shape_buffer,
             color_bind_group,
 
             res_buffer,
@@ -656,6 +1197,7 @@ impl<'a> RenderState<'a> {
 
             diffuse_bind_group,
             diffuse_texture,
+            texture_bind_group_layout,
 
             offset_buffer,
             offset_bind_group,
@@ -681,10 +1223,28 @@ impl<'a> RenderState<'a> {
             window,
             num_vertices: vertices.len() as u32,
             cells: Vec::new(),
+            overlay_count: 0,
+            cell_instance_count: 0,
+            wrap_ghosts: None,
             grid_size,
+            cell_padding: 0.0,
+            cell_shape: CellShape::default(),
+            cell_texture: None,
             rsc: bag,
             bg_render_pipeline,
+            grid_render_pipeline,
+            grid_visible: false,
             egui,
+            recorder: None,
+            dirty: DirtyTracker::new(),
+            clear_color: render_config.clear_color,
+            cell_color: CELL_COLOR,
+            sample_count,
+            msaa_view,
+            timer: timing::FrameTimer::default(),
+            render_config,
+            offset: vec2::Vector2::new(0.0, 0.0),
+            device_lost,
         }
     }
 
@@ -692,46 +1252,43 @@ impl<'a> RenderState<'a> {
     ///
     /// Automatically allocates new buffers when their capacity is insufficient
     pub fn update_cells(&mut self, cells: Vec<Cell>) {
+        self.dirty.mark();
+
         // Update internal record of the cells
         self.cells = cells;
 
         // Convert the cells to instances for the shader
-        let new_instances = self
-            .cells
+        self.cell_instance_count = self.rsc.instance_buffer.write_cells(
+            &self.core.device,
+            &self.core.queue,
+            &self.render_config.buffer_policy,
+            &self.cells,
+            self.wrap_ghosts,
+            self.grid_size,
+        );
+    }
+
+    /// Draw faint copies of the cells on the edges of a torus of this size
+    /// past the opposite edges, or stop drawing them if it's `None`. They
+    /// appear with the next `update_cells`.
+    pub fn set_wrap_ghosts(&mut self, torus: Option<vec2::Vector2<i32>>) {
+        self.wrap_ghosts = torus;
+    }
+
+    /// Update the overlay cells, which are drawn on top of the living cells.
+    pub fn update_overlay(&mut self, cells: Vec<OverlayCell>) {
+        self.dirty.mark();
+        let new_instances = cells
             .iter()
-            .map(|c| c.as_instance(self.grid_size))
+            .map(|c| c.cell.as_tinted_instance(c.tint))
             .collect::<Vec<_>>();
-
-        // Determine the required size of the buffer to hold all the cells
-        let instance_count = new_instances.len();
-        let new_size = (instance_count as f32 * 1.5) as u64;
-
-        // Create a new buffer and replace the old one if needed. The new buffer
-        // grows exponentially to get amortized O(1) insertions.
-        if instance_count as u64 > self.rsc.instance_buffer_capacity {
-            let instance_buffer = self.core.device.create_buffer(&wgpu::BufferDescriptor {
-                label: Some("Instance Buffer"),
-                // size: std::mem::size_of::<Instance>() as u64 * 80u64,
-                size: std::mem::size_of::<Instance>() as u64 * new_size,
-                usage: wgpu::BufferUsages::VERTEX
-                    | wgpu::BufferUsages::COPY_DST
-                    | wgpu::BufferUsages::COPY_SRC,
-                mapped_at_creation: false,
-            });
-            // Write the data
-            self.core
-                .queue
-                .write_buffer(&instance_buffer, 0, bytemuck::cast_slice(&new_instances));
-            self.rsc.instance_buffer_capacity = new_size;
-            self.rsc.instance_buffer = instance_buffer;
-        } else {
-            // Write the data
-            self.core.queue.write_buffer(
-                &self.rsc.instance_buffer,
-                0,
-                bytemuck::cast_slice(&new_instances),
-            );
-        }
+        self.overlay_count = new_instances.len() as u32;
+        self.rsc.overlay_buffer.write(
+            &self.core.device,
+            &self.core.queue,
+            &self.render_config.buffer_policy,
+            &new_instances,
+        );
     }
 
     /// Get an `Arc` to the current window being rendered to.
@@ -741,6 +1298,8 @@ impl<'a> RenderState<'a> {
 
     /// Update the panning value used in the shader.
     pub fn update_offset(&mut self, new_offset: vec2::Vector2<f32>) {
+        self.dirty.mark();
+        self.offset = new_offset;
         let offset: [f32; 2] = new_offset.into();
         let mut data = Vec::with_capacity(4);
         data.extend(offset);
@@ -751,18 +1310,118 @@ impl<'a> RenderState<'a> {
     }
 
     /// Change the grid size used for rendering.
-    pub fn change_grid_size(&self, new: f32) {
-        if new <= 0.0 {
+    pub fn change_grid_size(&mut self, new: f32) {
+        if !self.dirty.grid_size_changed(new) {
             return;
         }
-        let vertices = cell_vertices(new);
+        self.grid_size = new;
+        self.write_cell_vertices();
+
+        self.core
+            .queue
+            .write_buffer(&self.rsc.radius_buffer, 0, bytemuck::cast_slice(&[new, 0.0, 0.0, 0.0]));
+    }
+
+    /// Set the gap between rendered cells as a fraction of their size, from 0
+    /// (touching) up to `MAX_CELL_PADDING`.
+    pub fn set_cell_padding(&mut self, padding: f32) {
+        self.dirty.mark();
+        self.cell_padding = padding.clamp(0.0, MAX_CELL_PADDING);
+        self.write_cell_vertices();
+        self.write_cell_shape();
+    }
+
+    /// Choose between the cell texture and a solid square or circle.
+    pub fn set_cell_shape(&mut self, shape: CellShape) {
+        self.dirty.mark();
+        self.cell_shape = shape;
+        self.write_cell_shape();
+    }
+
+    /// Draw living cells with an image, such as the contents of a PNG file,
+    /// instead of the built-in texture. The image is stretched over each
+    /// cell, whatever its shape. If it can't be decoded or is too large for
+    /// the graphics device, the current texture is kept.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn set_cell_texture(&mut self, bytes: &[u8]) -> anyhow::Result<()> {
+        let image = image::load_from_memory(bytes)?;
+        let max_size = self.core.device.limits().max_texture_dimension_2d;
+        if image.width() > max_size || image.height() > max_size {
+            anyhow::bail!(
+                "the image is {}x{}, larger than the {max_size}x{max_size} the GPU allows",
+                image.width(),
+                image.height()
+            );
+        }
+        let texture = texture::Texture::from_image(
+            &self.core.device,
+            &self.core.queue,
+            &image,
+            Some("cell texture"),
+        )?;
+        self.rsc.diffuse_bind_group = texture_bind_group(
+            &self.core.device,
+            &self.rsc.texture_bind_group_layout,
+            &texture,
+            "diffuse_bind_group",
+        );
+        self.rsc.diffuse_texture = texture;
+        self.cell_texture = Some(bytes.to_vec());
+        self.dirty.mark();
+        Ok(())
+    }
+
+    /// Show why a cell texture couldn't be loaded in the GUI.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn show_texture_error(&mut self, error: String) {
+        self.egui.set_texture_error(error);
+        self.dirty.mark();
+    }
+
+    /// Rewrite the shape uniform for the current shape and padding.
+    fn write_cell_shape(&self) {
+        let uniform = ShapeUniform::new(self.cell_shape, self.cell_padding);
+        self.core
+            .queue
+            .write_buffer(&self.rsc.shape_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    }
+
+    /// Rewrite the cell quad's vertices for the current grid size and padding.
+    fn write_cell_vertices(&self) {
+        let vertices = cell_vertices(self.grid_size, self.cell_padding);
         self.core
             .queue
             .write_buffer(&self.rsc.vertex_buffer, 0, bytemuck::cast_slice(&vertices));
+    }
+
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))] // Only read when recreating
+    pub fn clear_color(&self) -> [f32; 4] {
+        let wgpu::Color { r, g, b, a } = self.clear_color;
+        [r as f32, g as f32, b as f32, a as f32]
+    }
+
+    /// Set the color the next frames are cleared to, as RGBA from 0 to 1.
+    pub fn set_clear_color(&mut self, color: [f32; 4]) {
+        self.dirty.mark();
+        self.clear_color = to_wgpu_color(color);
+    }
 
+    /// Set the solid color cells are drawn in at far zooms, as RGBA from 0 to
+    /// 1. The alpha is clamped to that range.
+    pub fn set_cell_color(&mut self, mut rgba: [f32; 4]) {
+        rgba[3] = rgba[3].clamp(0.0, 1.0);
+        self.dirty.mark();
+        self.cell_color = rgba;
         self.core
             .queue
-            .write_buffer(&self.rsc.radius_buffer, 0, bytemuck::cast_slice(&[new, 0.0, 0.0, 0.0]));
+            .write_buffer(&self.rsc.color_buffer, 0, bytemuck::cast_slice(&rgba));
+    }
+
+    /// Show or hide lines along the cell boundaries. They fade out when zoomed
+    /// far enough out that the cells are only a few pixels across.
+    pub fn set_grid_visible(&mut self, visible: bool) {
+        self.dirty.mark();
+        self.grid_visible = visible;
     }
 
     /// Reconfigure and update the renderer for a new resolution
@@ -770,12 +1429,14 @@ impl<'a> RenderState<'a> {
         if new_size.width == 0 || new_size.height == 0 {
             return;
         }
+        self.dirty.mark();
         self.size = new_size;
         self.core.config.width = new_size.width;
         self.core.config.height = new_size.height;
         self.core
             .surface
             .configure(&self.core.device, &self.core.config);
+        self.msaa_view = create_msaa_view(&self.core.device, &self.core.config, self.sample_count);
 
         self.core.queue.write_buffer(
             &self.rsc.res_buffer,
@@ -784,6 +1445,61 @@ impl<'a> RenderState<'a> {
         );
     }
 
+    /// Add time spent outside the renderer to the current frame's timings.
+    pub fn add_time(&mut self, phase: timing::Phase, duration: std::time::Duration) {
+        self.timer.add(phase, duration);
+    }
+
+    /// Load a pattern file into the GUI's paste preview.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn import_file(&mut self, path: &std::path::Path) {
+        self.egui.import_file(path);
+        self.dirty.mark();
+    }
+
+    /// Let the GUI react to notable game events.
+    pub fn handle_game_events(&mut self, events: &[crate::game::GameEvent]) {
+        self.egui.handle_game_events(events);
+        self.dirty.mark();
+    }
+
+    /// Whether the graphics device has been lost, in which case nothing can be
+    /// drawn until the renderer is recreated.
+    pub fn is_device_lost(&self) -> bool {
+        self.device_lost.load(Ordering::Relaxed)
+    }
+
+    /// Recreate the device and everything made with it after the device has
+    /// been lost, keeping the cells, camera, and GUI state.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn recreate(&mut self) {
+        let mut new = Self::with_surface(
+            Arc::clone(&self.core.instance),
+            Arc::clone(&self.core.surface),
+            Arc::clone(&self.window),
+            self.grid_size,
+            self.egui.game_state(),
+            self.render_config.clone(),
+        )
+        .await;
+        new.egui.take_app(&mut self.egui);
+        new.resize(self.size);
+        new.update_offset(self.offset);
+        new.set_cell_padding(self.cell_padding);
+        new.set_cell_shape(self.cell_shape);
+        if let Some(bytes) = self.cell_texture.take() {
+            // It loaded before, so it will again
+            let _ = new.set_cell_texture(&bytes);
+        }
+        new.set_clear_color(self.clear_color());
+        new.set_cell_color(self.cell_color);
+        new.set_grid_visible(self.grid_visible);
+        new.set_wrap_ghosts(self.wrap_ghosts);
+        new.update_cells(std::mem::take(&mut self.cells));
+        new.recorder = self.recorder.take();
+        *self = new;
+    }
+
     /// Reconfigure the surface
     pub fn reconfigure(&mut self) {
         self.resize(self.size);
@@ -796,34 +1512,31 @@ impl<'a> RenderState<'a> {
 
     pub fn update(&mut self) {}
 
-    /// Render to the window.
-    pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
-        let output = self.core.surface.get_current_texture()?;
-        let view = output
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+    /// Whether anything has changed since the last render, either in the
+    /// scene or in the GUI. If not, rendering can be skipped.
+    pub fn needs_redraw(&self) -> bool {
+        self.dirty.is_dirty() || self.egui.needs_repaint()
+    }
 
-        let mut encoder =
-            self.core
-                .device
-                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                    label: Some("Render Encoder"),
-                });
+    /// Record the background and cell render passes into `encoder`, drawing
+    /// to `view`.
+    fn draw_scene(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView) {
+        // When multisampling, draw to the multisampled texture and resolve it
+        // to the frame at the end of each pass
+        let (view, resolve_target) = match &self.msaa_view {
+            Some(msaa_view) => (msaa_view, Some(view)),
+            None => (view, None),
+        };
 
         // Create and complete the render pass for the background
         {
             let mut first_render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("BG Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
+                    view,
+                    resolve_target,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.1,
-                            g: 0.2,
-                            b: 0.3,
-                            a: 1.0,
-                        }),
+                        load: wgpu::LoadOp::Clear(self.clear_color),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -842,14 +1555,23 @@ impl<'a> RenderState<'a> {
             first_render_pass.set_vertex_buffer(0, self.rsc.bg_vertex_buffer.slice(..));
 
             first_render_pass.draw(0..6, 0..1);
+
+            // The grid goes between the background and the cells
+            if self.grid_visible {
+                first_render_pass.set_pipeline(&self.grid_render_pipeline);
+                first_render_pass.set_bind_group(0, &self.rsc.offset_bind_group, &[]);
+                first_render_pass.set_bind_group(1, &self.rsc.radius_bind_group, &[]);
+                first_render_pass.set_bind_group(2, &self.rsc.res_bind_group, &[]);
+                first_render_pass.draw(0..6, 0..1);
+            }
         }
         // Create and complete the primary render pass, for the cells.
         {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
+                    view,
+                    resolve_target,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Load,
                         store: wgpu::StoreOp::Store,
@@ -868,22 +1590,463 @@ impl<'a> RenderState<'a> {
             render_pass.set_bind_group(4, &self.rsc.offset_bind_group, &[]);
             render_pass.set_vertex_buffer(0, self.rsc.vertex_buffer.slice(..));
 
-            render_pass.set_vertex_buffer(1, self.rsc.instance_buffer.slice(..));
+            render_pass.set_vertex_buffer(1, self.rsc.instance_buffer.buffer.slice(..));
+
+            render_pass.draw(0..self.num_vertices, 0..self.cell_instance_count);
 
-            render_pass.draw(0..self.num_vertices, 0..self.cells.len() as _);
+            if self.overlay_count > 0 {
+                render_pass.set_vertex_buffer(1, self.rsc.overlay_buffer.buffer.slice(..));
+                render_pass.draw(0..self.num_vertices, 0..self.overlay_count);
+            }
         }
+    }
+
+    /// Render the scene (without the GUI) into an image the size of the
+    /// window.
+    pub fn capture_frame(&self) -> anyhow::Result<image::RgbaImage> {
+        /// Rows in a texture-to-buffer copy must be aligned to this many bytes
+        const ROW_ALIGNMENT: u32 = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+        let device = &self.core.device;
+        let (width, height) = (self.core.config.width, self.core.config.height);
+        let format = self.core.config.format;
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Capture Texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let unpadded_row = width * 4;
+        let padded_row = unpadded_row.div_ceil(ROW_ALIGNMENT) * ROW_ALIGNMENT;
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Capture Buffer"),
+            size: (padded_row * height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Capture Encoder"),
+        });
+        self.draw_scene(&mut encoder, &view);
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                aspect: wgpu::TextureAspect::All,
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.core.queue.submit(iter::once(encoder.finish()));
+
+        // Wait for the copy to finish so the buffer can be read
+        let slice = buffer.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |res| {
+            let _ = tx.send(res);
+        });
+        device.poll(wgpu::Maintain::Wait);
+        rx.try_recv()??;
+
+        // Strip the row padding and convert to RGBA
+        let is_bgra = matches!(
+            format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        );
+        let mut pixels = Vec::with_capacity((unpadded_row * height) as usize);
+        for row in slice.get_mapped_range().chunks(padded_row as usize) {
+            for px in row[..unpadded_row as usize].chunks(4) {
+                if is_bgra {
+                    pixels.extend([px[2], px[1], px[0], px[3]]);
+                } else {
+                    pixels.extend_from_slice(px);
+                }
+            }
+        }
+        buffer.unmap();
+
+        image::RgbaImage::from_raw(width, height, pixels)
+            .ok_or_else(|| anyhow::anyhow!("captured frame had the wrong size"))
+    }
+
+    /// Save the scene as a timestamped PNG.
+    pub fn save_screenshot(&self) {
+        let filename = format!(
+            "life-{}.png",
+            chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+        );
+        let result = self.capture_frame().and_then(|frame| {
+            let mut bytes = Vec::new();
+            frame.write_to(&mut io::Cursor::new(&mut bytes), image::ImageFormat::Png)?;
+            crate::export::save_bytes(&filename, &bytes)
+        });
+        if let Err(e) = result {
+            log::error!("Failed to save screenshot: {e}");
+        }
+    }
+
+    /// Start recording an animated GIF. Any recording in progress is dropped.
+    pub fn start_recording(&mut self, settings: recording::GifSettings) {
+        self.recorder = Some(recording::GifRecorder::new(settings));
+    }
+
+    /// Capture a frame for the GIF being recorded, if there is one and this
+    /// generation hasn't been captured yet. When enough frames have been
+    /// captured, the GIF is encoded and saved.
+    ///
+    /// Returns `true` if a frame was captured and the recording is
+    /// deterministic, meaning the next generation should be stepped right
+    /// away.
+    pub fn record_frame(&mut self, generation: u64, population: usize) -> bool {
+        let Some(recorder) = self.recorder.as_ref() else {
+            return false;
+        };
+        if !recorder.wants_frame(generation) {
+            return false;
+        }
+        let frame = match self.capture_frame() {
+            Ok(v) => v,
+            Err(e) => {
+                log::error!("Failed to capture frame, stopping recording: {e}");
+                self.recorder = None;
+                return false;
+            }
+        };
+
+        let recorder = self.recorder.as_mut().unwrap();
+        recorder.add_frame(frame, generation, population);
+        self.egui.set_recording_progress(Some(recorder.progress()));
+        if !recorder.is_done() {
+            return recorder.is_deterministic();
+        }
+
+        let recorder = self.recorder.take().unwrap();
+        self.egui.set_recording_progress(None);
+        let filename = format!(
+            "life-{}.gif",
+            chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+        );
+        let result = recorder
+            .encode()
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| crate::export::save_bytes(&filename, &bytes));
+        if let Err(e) = result {
+            log::error!("Failed to save recording: {e}");
+        }
+        false
+    }
+
+    /// Render to the window.
+    pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+        let output = self.core.surface.get_current_texture()?;
+        let view = output
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder =
+            self.core
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("Render Encoder"),
+                });
+
+        let scene_start = Instant::now();
+        self.draw_scene(&mut encoder, &view);
+        self.timer.add(timing::Phase::Scene, scene_start.elapsed());
 
         // Render the GUI
+        let gui_start = Instant::now();
         let (encoder, egui_tdelta) =
             self.egui
                 .render(&self.core.config, &self.core.queue, &view, encoder);
+        self.timer.add(timing::Phase::Gui, gui_start.elapsed());
 
+        let submit_start = Instant::now();
         self.core.queue.submit(iter::once(encoder.finish()));
+        self.timer.add(timing::Phase::Scene, submit_start.elapsed());
 
         output.present();
 
         self.egui.remove_textures(egui_tdelta);
+        self.dirty.rendered();
+
+        self.timer.end_frame();
+        self.egui.set_frame_timings(self.timer.last());
 
         Ok(())
     }
 }
+
+/// Create the multisampled texture for the scene to be drawn to, or `None` if
+/// `sample_count` is 1 or the surface is empty.
+fn create_msaa_view(
+    device: &wgpu::Device,
+    config: &wgpu::SurfaceConfiguration,
+    sample_count: u32,
+) -> Option<wgpu::TextureView> {
+    if sample_count <= 1 || config.width == 0 || config.height == 0 {
+        return None;
+    }
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Multisampled Frame"),
+        size: wgpu::Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count,
+        dimension: wgpu::TextureDimension::D2,
+        format: config.format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    Some(texture.create_view(&wgpu::TextureViewDescriptor::default()))
+}
+
+/// Create an empty buffer with room for `capacity` instances.
+fn create_instance_buffer(device: &wgpu::Device, label: &str, capacity: u64) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(label),
+        size: std::mem::size_of::<Instance>() as u64 * capacity,
+        usage: wgpu::BufferUsages::VERTEX
+            | wgpu::BufferUsages::COPY_DST
+            | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    })
+}
+
+/// Copies of the cells on the edges of a `torus` of cells, moved past the
+/// opposite edges to where they are neighbors across the wrap. Cells in the
+/// corners are copied past three edges.
+fn wrap_ghosts(cells: &[Cell], torus: vec2::Vector2<i32>, grid_size: f32) -> Vec<Cell> {
+    // How far to move a cell at `at` along an axis `size` cells long
+    let shifts = |at: i32, size: i32| {
+        let mut shifts = vec![0];
+        if at == 0 {
+            shifts.push(size);
+        }
+        if at == size - 1 {
+            shifts.push(-size);
+        }
+        shifts
+    };
+    let mut ghosts = Vec::new();
+    for cell in cells {
+        // Cells are placed at the centers of their squares
+        let [x, y] = cell.location.map(|c| (c / grid_size - 0.5).round() as i32);
+        for dx in shifts(x, torus.x) {
+            for dy in shifts(y, torus.y) {
+                if (dx, dy) != (0, 0) {
+                    ghosts.push(Cell {
+                        location: [
+                            cell.location[0] + dx as f32 * grid_size,
+                            cell.location[1] + dy as f32 * grid_size,
+                        ],
+                    });
+                }
+            }
+        }
+    }
+    ghosts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn right_edge_cells_have_ghosts_past_the_left_edge() {
+        let grid_size = 10.0;
+        let at = |x: i32, y: i32| Cell {
+            location: [(x as f32 + 0.5) * grid_size, (y as f32 + 0.5) * grid_size],
+        };
+        let torus = vec2::Vector2::new(8, 6);
+        let locations = |cells: &[Cell]| {
+            wrap_ghosts(cells, torus, grid_size)
+                .into_iter()
+                .map(|c| c.location)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(locations(&[at(7, 3)]), [at(-1, 3).location]);
+        // Away from the edges there's nothing to copy
+        assert!(locations(&[at(3, 3)]).is_empty());
+        // A corner is a neighbor of the three other corners
+        let corner = locations(&[at(0, 5)]);
+        assert_eq!(corner.len(), 3);
+        for ghost in [at(0, -1), at(8, 5), at(8, -1)] {
+            assert!(corner.contains(&ghost.location));
+        }
+    }
+
+    #[test]
+    fn cell_vertices_shrink_with_padding() {
+        for (padding, radius) in [(0.0, 10.0), (0.25, 7.5), (0.5, 5.0)] {
+            let vertices = cell_vertices(10.0, padding);
+            for vertex in vertices {
+                let [x, y, _] = vertex.position;
+                assert_eq!((x.abs(), y.abs()), (radius, radius), "padding {padding}");
+            }
+            // The texture is squeezed rather than cropped
+            let corners = vertices.map(|v| v.tex_coords);
+            assert!(corners.contains(&[0.0, 0.0]) && corners.contains(&[1.0, 1.0]));
+        }
+    }
+
+    #[test]
+    fn shape_uniform_carries_padding() {
+        let uniform = ShapeUniform::new(CellShape::Circle, 0.25);
+        assert_eq!(uniform.shape, 2);
+        assert_eq!(uniform.cell_padding, 0.25);
+    }
+
+    #[test]
+    fn render_config_builder() {
+        let config = RenderConfig::default()
+            .backends(wgpu::Backends::VULKAN)
+            .start_capacity(64)
+            .buffer_policy(BufferPolicy {
+                shrink_after: None,
+                ..Default::default()
+            })
+            .clear_color(wgpu::Color::RED)
+            .present_mode(wgpu::PresentMode::Mailbox)
+            .sample_count(4);
+        assert_eq!(config.backends, wgpu::Backends::VULKAN);
+        assert_eq!(config.start_capacity, 64);
+        assert_eq!(config.buffer_policy.shrink_after, None);
+        assert_eq!(config.clear_color, wgpu::Color::RED);
+        assert_eq!(config.present_mode, wgpu::PresentMode::Mailbox);
+        assert_eq!(config.sample_count, 4);
+
+        let default = RenderConfig::default();
+        assert_eq!(default.sample_count, 1);
+        assert_eq!(default.clear_color, to_wgpu_color(CLEAR_COLOR));
+    }
+
+    /// A device on the default adapter, if there is one.
+    fn headless_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+        pollster::block_on(async {
+            let adapter = wgpu::Instance::default()
+                .request_adapter(&wgpu::RequestAdapterOptions::default())
+                .await?;
+            adapter
+                .request_device(&wgpu::DeviceDescriptor::default(), None)
+                .await
+                .ok()
+        })
+    }
+
+    #[test]
+    fn recreated_instance_buffer_holds_the_kept_cells() {
+        let (Some((lost, lost_queue)), Some((device, queue))) =
+            (headless_device(), headless_device())
+        else {
+            eprintln!("No adapter, skipping the instance buffer comparison");
+            return;
+        };
+        let grid_size = 10.0;
+        let cells: Vec<Cell> = [(0, 0), (3, 1), (7, 5)]
+            .into_iter()
+            .map(|(x, y)| Cell {
+                location: [(x as f32 + 0.5) * grid_size, (y as f32 + 0.5) * grid_size],
+            })
+            .collect();
+        let torus = Some(vec2::Vector2::new(8, 6));
+        let policy = BufferPolicy::default();
+
+        let mut before = InstanceBuffer::new(&lost, "Lost Instance Buffer", 1);
+        let count = before.write_cells(&lost, &lost_queue, &policy, &cells, torus, grid_size);
+        let drawn = before.read(&lost, &lost_queue, count);
+        drop(before);
+        drop((lost, lost_queue));
+
+        // As `recreate` does, a new device gets a fresh buffer that is filled
+        // from the cells that were kept
+        let mut after = InstanceBuffer::new(&device, "Instance Buffer", 1);
+        let restored = after.write_cells(&device, &queue, &policy, &cells, torus, grid_size);
+        assert_eq!(restored, count);
+        let bytes = |instances: &[Instance]| bytemuck::cast_slice::<_, u8>(instances).to_vec();
+        assert_eq!(bytes(&after.read(&device, &queue, restored)), bytes(&drawn));
+        assert!(drawn.len() > cells.len(), "the wrap ghosts should be restored too");
+    }
+
+    #[test]
+    fn dirty_until_rendered() {
+        let mut dirty = DirtyTracker::new();
+        assert!(dirty.is_dirty());
+        dirty.rendered();
+        assert!(!dirty.is_dirty());
+
+        // What new cells or a new offset do
+        dirty.mark();
+        assert!(dirty.is_dirty());
+        dirty.rendered();
+
+        assert!(!dirty.grid_size_changed(0.0));
+        assert!(!dirty.grid_size_changed(-3.0));
+        assert!(!dirty.is_dirty());
+        assert!(dirty.grid_size_changed(12.0));
+        assert!(dirty.is_dirty());
+        dirty.rendered();
+        assert!(!dirty.is_dirty());
+    }
+
+    #[test]
+    fn instance_capacity_grows_and_shrinks() {
+        let policy = BufferPolicy {
+            growth_factor: 2.0,
+            shrink_below: 0.25,
+            shrink_after: Some(3),
+        };
+        let mut capacity = InstanceCapacity::new(10);
+        assert_eq!(capacity.fit(&policy, 8), None);
+        // Overflowing grows with room to spare
+        assert_eq!(capacity.fit(&policy, 50), Some(100));
+
+        // A single small update isn't enough to shrink
+        assert_eq!(capacity.fit(&policy, 5), None);
+        assert_eq!(capacity.fit(&policy, 60), None);
+        // Sustained low usage is, but not below the starting capacity
+        assert_eq!(capacity.fit(&policy, 5), None);
+        assert_eq!(capacity.fit(&policy, 5), None);
+        assert_eq!(capacity.fit(&policy, 2), Some(10));
+    }
+
+    #[test]
+    fn instance_capacity_never_shrinks_without_policy() {
+        let policy = BufferPolicy {
+            shrink_after: None,
+            ..Default::default()
+        };
+        let mut capacity = InstanceCapacity::new(0);
+        assert!(capacity.fit(&policy, 1000).is_some());
+        for _ in 0..1000 {
+            assert_eq!(capacity.fit(&policy, 0), None);
+        }
+    }
+}
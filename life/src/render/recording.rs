@@ -0,0 +1,294 @@
+use image::{codecs::gif, Delay, Frame, Rgba, RgbaImage};
+
+/// How many image pixels wide each pixel of the label font is
+const LABEL_SCALE: u32 = 2;
+/// The gap between the label and the edges of the frame, in image pixels
+const LABEL_MARGIN: u32 = 4;
+const LABEL_TEXT_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const LABEL_BACKGROUND_COLOR: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+/// Options for recording an animated GIF of the simulation.
+#[derive(Clone, Copy, Debug)]
+pub struct GifSettings {
+    /// The number of generations to capture
+    pub frames: u32,
+    /// The playback speed of the finished GIF
+    pub fps: u32,
+    /// The size of the GIF relative to the window, between 0 and 1
+    pub scale: f32,
+    /// Whether to step once per captured frame instead of recording auto-play
+    /// in real time. This keeps slow captures from skipping generations.
+    pub deterministic: bool,
+    /// What to write on each frame
+    pub label: LabelSettings,
+}
+
+/// Options for labeling each frame of a recording with the generation it
+/// shows, so that exported frames can be told apart.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LabelSettings {
+    pub enabled: bool,
+    /// Whether to show the population after the generation
+    pub population: bool,
+    pub corner: Corner,
+}
+
+/// A corner of an image.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Corner {
+    #[default]
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Corner {
+    pub const ALL: [Self; 4] = [
+        Self::TopLeft,
+        Self::TopRight,
+        Self::BottomLeft,
+        Self::BottomRight,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::TopLeft => "Top left",
+            Self::TopRight => "Top right",
+            Self::BottomLeft => "Bottom left",
+            Self::BottomRight => "Bottom right",
+        }
+    }
+}
+
+impl Default for GifSettings {
+    fn default() -> Self {
+        Self {
+            frames: 50,
+            fps: 10,
+            scale: 0.5,
+            deterministic: true,
+            label: LabelSettings::default(),
+        }
+    }
+}
+
+/// Collects captured frames, one per generation, until a GIF can be encoded.
+pub struct GifRecorder {
+    settings: GifSettings,
+    frames: Vec<RgbaImage>,
+    last_generation: Option<u64>,
+}
+
+impl GifRecorder {
+    pub fn new(settings: GifSettings) -> Self {
+        Self {
+            settings,
+            frames: Vec::with_capacity(settings.frames as usize),
+            last_generation: None,
+        }
+    }
+
+    /// Whether the recorder still needs a frame for the given generation.
+    pub fn wants_frame(&self, generation: u64) -> bool {
+        !self.is_done() && self.last_generation != Some(generation)
+    }
+
+    /// Add a frame for a generation, scaling it to the configured size and
+    /// labeling it if enabled.
+    pub fn add_frame(&mut self, frame: RgbaImage, generation: u64, population: usize) {
+        let scale = self.settings.scale.clamp(0.05, 1.0);
+        let width = ((frame.width() as f32 * scale) as u32).max(1);
+        let height = ((frame.height() as f32 * scale) as u32).max(1);
+        let mut frame = if (width, height) == frame.dimensions() {
+            frame
+        } else {
+            image::imageops::resize(&frame, width, height, image::imageops::FilterType::Triangle)
+        };
+        let label = self.settings.label;
+        if label.enabled {
+            let text = if label.population {
+                format!("GEN {generation}  POP {population}")
+            } else {
+                format!("GEN {generation}")
+            };
+            // Labeling after scaling keeps the text sharp
+            draw_label(&mut frame, &text, label.corner);
+        }
+        self.frames.push(frame);
+        self.last_generation = Some(generation);
+    }
+
+    /// Whether the recording steps once per frame rather than following
+    /// auto-play.
+    pub fn is_deterministic(&self) -> bool {
+        self.settings.deterministic
+    }
+
+    /// Whether all of the requested frames have been captured.
+    pub fn is_done(&self) -> bool {
+        self.frames.len() >= self.settings.frames as usize
+    }
+
+    /// Get the number of frames captured so far and the number requested.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.frames.len(), self.settings.frames as usize)
+    }
+
+    /// Encode the captured frames into a looping GIF.
+    pub fn encode(self) -> Result<Vec<u8>, image::ImageError> {
+        let delay = Delay::from_numer_denom_ms(1000, self.settings.fps.max(1));
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = gif::GifEncoder::new(&mut bytes);
+            encoder.set_repeat(gif::Repeat::Infinite)?;
+            encoder.encode_frames(
+                self.frames
+                    .into_iter()
+                    .map(|f| Frame::from_parts(f, 0, 0, delay)),
+            )?;
+        }
+        Ok(bytes)
+    }
+}
+
+/// Rows of a 3x5 pixel glyph, top first, with the leftmost pixel in the
+/// highest of the three bits. Unknown characters are blank.
+fn glyph(ch: char) -> [u8; 5] {
+    match ch {
+        '0' | 'O' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'E' => [0b111, 0b100, 0b111, 0b100, 0b111],
+        'G' => [0b111, 0b100, 0b101, 0b101, 0b111],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'P' => [0b111, 0b101, 0b111, 0b100, 0b100],
+        _ => [0; 5],
+    }
+}
+
+/// Write text in a corner of an image with a small built-in font, on a solid
+/// background so that it's readable over any cells. Text that doesn't fit is
+/// cut off.
+fn draw_label(image: &mut RgbaImage, text: &str, corner: Corner) {
+    // Each glyph is 3 pixels wide plus 1 of spacing, and the background has 1
+    // pixel of padding all around
+    let chars = text.chars().count() as u32;
+    let box_width = (chars * 4 + 1) * LABEL_SCALE;
+    let box_height = 7 * LABEL_SCALE;
+    let (width, height) = image.dimensions();
+    let left = match corner {
+        Corner::TopLeft | Corner::BottomLeft => LABEL_MARGIN,
+        Corner::TopRight | Corner::BottomRight => {
+            width.saturating_sub(box_width + LABEL_MARGIN)
+        }
+    };
+    let top = match corner {
+        Corner::TopLeft | Corner::TopRight => LABEL_MARGIN,
+        Corner::BottomLeft | Corner::BottomRight => {
+            height.saturating_sub(box_height + LABEL_MARGIN)
+        }
+    };
+
+    let mut fill = |x: u32, y: u32, color: Rgba<u8>| {
+        if x < width && y < height {
+            image.put_pixel(x, y, color);
+        }
+    };
+    for y in top..top + box_height {
+        for x in left..left + box_width {
+            fill(x, y, LABEL_BACKGROUND_COLOR);
+        }
+    }
+    for (i, ch) in text.chars().enumerate() {
+        for (row, bits) in glyph(ch).into_iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) == 0 {
+                    continue;
+                }
+                let x = left + (1 + i as u32 * 4 + col) * LABEL_SCALE;
+                let y = top + (1 + row as u32) * LABEL_SCALE;
+                for dy in 0..LABEL_SCALE {
+                    for dx in 0..LABEL_SCALE {
+                        fill(x + dx, y + dy, LABEL_TEXT_COLOR);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::AnimationDecoder;
+
+    use super::*;
+
+    fn blank_frame() -> RgbaImage {
+        RgbaImage::from_pixel(40, 30, Rgba([30, 60, 90, 255]))
+    }
+
+    #[test]
+    fn records_requested_frames() {
+        let mut recorder = GifRecorder::new(GifSettings {
+            frames: 3,
+            scale: 0.5,
+            ..Default::default()
+        });
+        for generation in 0..3 {
+            assert!(recorder.wants_frame(generation));
+            recorder.add_frame(blank_frame(), generation, 0);
+            assert!(!recorder.wants_frame(generation));
+        }
+        assert!(recorder.is_done());
+        assert_eq!(recorder.progress(), (3, 3));
+
+        let bytes = recorder.encode().unwrap();
+        let decoder = gif::GifDecoder::new(std::io::Cursor::new(bytes)).unwrap();
+        let frames = decoder.into_frames().collect_frames().unwrap();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].buffer().dimensions(), (20, 15));
+    }
+
+
+    #[test]
+    fn label_goes_in_its_corner() {
+        let background = Rgba([30, 60, 90, 255]);
+        for corner in Corner::ALL {
+            let mut frame = RgbaImage::from_pixel(100, 60, background);
+            draw_label(&mut frame, "GEN 7", corner);
+            let (x, y) = match corner {
+                Corner::TopLeft => (LABEL_MARGIN, LABEL_MARGIN),
+                Corner::TopRight => (99 - LABEL_MARGIN, LABEL_MARGIN),
+                Corner::BottomLeft => (LABEL_MARGIN, 59 - LABEL_MARGIN),
+                Corner::BottomRight => (99 - LABEL_MARGIN, 59 - LABEL_MARGIN),
+            };
+            assert_eq!(*frame.get_pixel(x, y), LABEL_BACKGROUND_COLOR, "{}", corner.name());
+            assert!(frame.pixels().any(|p| *p == LABEL_TEXT_COLOR));
+            // The opposite corner is left alone
+            assert_eq!(*frame.get_pixel(99 - x, 59 - y), background, "{}", corner.name());
+        }
+    }
+
+    #[test]
+    fn disabled_label_leaves_frames_alone() {
+        let mut recorder = GifRecorder::new(GifSettings {
+            frames: 2,
+            scale: 1.0,
+            ..Default::default()
+        });
+        recorder.add_frame(blank_frame(), 0, 5);
+        assert_eq!(recorder.frames[0], blank_frame());
+
+        recorder.settings.label.enabled = true;
+        recorder.add_frame(blank_frame(), 1, 5);
+        assert_ne!(recorder.frames[1], blank_frame());
+    }
+}
@@ -1,6 +1,7 @@
 struct InstanceInput {
     @location(1) offset: vec2<f32>,
     @location(2) center: vec2<f32>,
+    @location(4) tint: vec4<f32>,
 }
 
 struct Res {
@@ -24,6 +25,19 @@ var<uniform> radius: Rad;
 @group(2) @binding(0)
 var<uniform> color: vec4<f32>;
 
+// Numbered in the order of `CellShape`'s variants
+const SHAPE_TEXTURED: u32 = 0u;
+const SHAPE_CIRCLE: u32 = 2u;
+
+struct Shape {
+    kind: u32,
+    cell_padding: f32,
+    padding: vec2<f32>,
+}
+
+@group(2) @binding(1)
+var<uniform> shape: Shape;
+
 struct Pan {
     data: vec2<f32>,
     padding: vec2<f32>,
@@ -43,6 +57,7 @@ struct VertexOutput {
     @location(0) frag_coord: vec4<f32>,
     @location(4) circle_center: vec2<f32>,
     @location(3) tex_coords: vec2<f32>,
+    @location(5) tint: vec4<f32>,
 };
 
 @vertex
@@ -66,6 +81,7 @@ fn vs_main(
     out.frag_coord = out.clip_position;
     out.circle_center = instance.center;
     out.tex_coords = model.tex_coords;
+    out.tint = instance.tint;
     return out;
 }
 
@@ -82,5 +98,18 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     //let pan = pan.data;
     // At exremely far zooms, interpolate between the texture and a solid color
     let factor = smoothstep(0.01, 0.02, radius);
-    return factor * textureSample(t_diffuse, s_diffuse, in.tex_coords) + (color * (1 - factor));
+    let textured = factor * textureSample(t_diffuse, s_diffuse, in.tex_coords)
+        + (color * (1 - factor));
+
+    // The position within the quad, from -1 to 1 along each axis, and how
+    // much of that one pixel spans, for anti-aliasing the edge
+    let p = in.tex_coords * 2.0 - 1.0;
+    let pixel = 2.0 / (radius * (1.0 - shape.cell_padding) * res.data.y);
+    let dist = select(max(abs(p.x), abs(p.y)), length(p), shape.kind == SHAPE_CIRCLE) - 1.0;
+    let coverage = clamp(0.5 - dist / pixel, 0.0, 1.0);
+    let solid = vec4<f32>(color.rgb, color.a * coverage);
+
+    let base = select(solid, textured, shape.kind == SHAPE_TEXTURED);
+    // Tinted cells are overlays drawn in a solid color
+    return select(base, in.tint, in.tint.a > 0.0);
 }
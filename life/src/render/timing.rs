@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+/// The parts of a frame whose CPU time is measured.
+#[derive(Debug, Clone, Copy)]
+pub enum Phase {
+    /// Updating the game state, including applying finished steps
+    Simulation,
+    /// Recording and submitting the background and cell passes
+    Scene,
+    /// Laying out, tessellating, and drawing the GUI
+    Gui,
+}
+
+/// The time spent in each phase over one frame.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrameTimings {
+    pub simulation: Duration,
+    pub scene: Duration,
+    pub gui: Duration,
+}
+
+impl FrameTimings {
+    pub fn total(&self) -> Duration {
+        self.simulation + self.scene + self.gui
+    }
+}
+
+/// Accumulates the time spent in each phase until the end of a frame.
+#[derive(Debug, Default)]
+pub struct FrameTimer {
+    current: FrameTimings,
+    last: FrameTimings,
+}
+
+impl FrameTimer {
+    /// Add time spent in `phase` to the current frame.
+    pub fn add(&mut self, phase: Phase, duration: Duration) {
+        let total = match phase {
+            Phase::Simulation => &mut self.current.simulation,
+            Phase::Scene => &mut self.current.scene,
+            Phase::Gui => &mut self.current.gui,
+        };
+        *total += duration;
+    }
+
+    /// Finish the current frame and start accumulating the next one.
+    pub fn end_frame(&mut self) {
+        self.last = std::mem::take(&mut self.current);
+    }
+
+    /// The timings of the last finished frame.
+    pub fn last(&self) -> FrameTimings {
+        self.last
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accumulates_and_resets_each_frame() {
+        let ms = Duration::from_millis;
+        let mut timer = FrameTimer::default();
+        timer.add(Phase::Simulation, ms(2));
+        timer.add(Phase::Scene, ms(5));
+        timer.add(Phase::Simulation, ms(1));
+        // Nothing is reported until the frame ends
+        assert_eq!(timer.last().total(), Duration::ZERO);
+
+        timer.end_frame();
+        let last = timer.last();
+        assert_eq!(last.simulation, ms(3));
+        assert_eq!(last.scene, ms(5));
+        assert_eq!(last.gui, Duration::ZERO);
+        assert_eq!(last.total(), ms(8));
+
+        timer.add(Phase::Gui, ms(4));
+        timer.end_frame();
+        let last = timer.last();
+        assert_eq!(last.simulation, Duration::ZERO);
+        assert_eq!(last.total(), ms(4));
+    }
+}
@@ -0,0 +1,89 @@
+//! Persisting small pieces of data, such as settings, between sessions.
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// A named slot of persistent data. On native, each slot is a JSON file in the
+/// `data` directory; on the web, it is an entry in local storage.
+#[derive(Debug, Clone, Copy)]
+pub struct DataHandle {
+    key: &'static str,
+}
+
+impl DataHandle {
+    pub const fn new(key: &'static str) -> Self {
+        Self { key }
+    }
+
+    /// Load the stored value, or `None` if there isn't one or it couldn't be
+    /// read.
+    pub fn load<T: DeserializeOwned>(&self) -> Option<T> {
+        let text = self.read()?;
+        match serde_json::from_str(&text) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                log::warn!("Ignoring unreadable data for {}: {e}", self.key);
+                None
+            }
+        }
+    }
+
+    /// Store a value, replacing the previous one. Failures are logged.
+    pub fn store<T: Serialize>(&self, value: &T) {
+        let result = serde_json::to_string(value)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| self.write(&text));
+        if let Err(e) = result {
+            log::error!("Failed to store data for {}: {e}", self.key);
+        }
+    }
+
+    /// Delete the stored value, so tests start from nothing.
+    #[cfg(all(test, not(target_arch = "wasm32")))]
+    pub fn remove(&self) {
+        let _ = std::fs::remove_file(self.path());
+    }
+
+    /// Store text as it is, so tests can store data that can't be read.
+    #[cfg(all(test, not(target_arch = "wasm32")))]
+    pub fn store_raw(&self, text: &str) {
+        self.write(text).unwrap();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn path(&self) -> std::path::PathBuf {
+        std::path::Path::new("data").join(format!("{}.json", self.key))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read(&self) -> Option<String> {
+        std::fs::read_to_string(self.path()).ok()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write(&self, text: &str) -> anyhow::Result<()> {
+        let path = self.path();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, text)?;
+        Ok(())
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn read(&self) -> Option<String> {
+        Self::storage()?.get_item(self.key).ok()?
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn write(&self, text: &str) -> anyhow::Result<()> {
+        Self::storage()
+            .ok_or_else(|| anyhow::anyhow!("local storage is unavailable"))?
+            .set_item(self.key, text)
+            .map_err(|e| anyhow::anyhow!("{e:?}"))
+    }
+}
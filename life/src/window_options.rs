@@ -0,0 +1,98 @@
+//! Command line options for the window the app starts in, on native.
+
+use clap::Parser;
+use winit::{
+    dpi::LogicalSize,
+    window::{Fullscreen, WindowBuilder},
+};
+
+/// The size winit gives windows when none is requested, used for whichever
+/// dimension isn't given
+const DEFAULT_SIZE: LogicalSize<u32> = LogicalSize::new(800, 600);
+
+/// How the window should start
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Parser)]
+#[command(name = "life", about = "Conway's Game of Life")]
+pub struct WindowOptions {
+    /// Start in borderless fullscreen
+    #[arg(long)]
+    pub fullscreen: bool,
+    /// The starting width of the window, in logical pixels
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub width: Option<u32>,
+    /// The starting height of the window, in logical pixels
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub height: Option<u32>,
+}
+
+impl WindowOptions {
+    /// Read the options given to this process. Invalid options print the usage
+    /// and exit.
+    pub fn from_env() -> Self {
+        Self::parse()
+    }
+
+    /// The size requested for the window, if either dimension was given.
+    pub fn size(&self) -> Option<LogicalSize<u32>> {
+        (self.width.is_some() || self.height.is_some()).then(|| {
+            LogicalSize::new(
+                self.width.unwrap_or(DEFAULT_SIZE.width),
+                self.height.unwrap_or(DEFAULT_SIZE.height),
+            )
+        })
+    }
+
+    /// Set up a window builder with these options. The size still applies
+    /// when leaving fullscreen.
+    pub fn apply(&self, mut builder: WindowBuilder) -> WindowBuilder {
+        if let Some(size) = self.size() {
+            builder = builder.with_inner_size(size);
+        }
+        if self.fullscreen {
+            builder = builder.with_fullscreen(Some(Fullscreen::Borderless(None)));
+        }
+        builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<WindowOptions, clap::Error> {
+        WindowOptions::try_parse_from(std::iter::once("life").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn no_options() {
+        let options = parse(&[]).unwrap();
+        assert_eq!(options, WindowOptions::default());
+        assert_eq!(options.size(), None);
+    }
+
+    #[test]
+    fn all_options() {
+        let options = parse(&["--fullscreen", "--width", "1280", "--height=720"]).unwrap();
+        assert!(options.fullscreen);
+        assert_eq!(options.size(), Some(LogicalSize::new(1280, 720)));
+    }
+
+    #[test]
+    fn missing_dimension_uses_default() {
+        let options = parse(&["--height", "300"]).unwrap();
+        assert_eq!(options.size(), Some(LogicalSize::new(DEFAULT_SIZE.width, 300)));
+    }
+
+    #[test]
+    fn bad_options() {
+        for args in [
+            &["--width"][..],
+            &["--width", "0"],
+            &["--height", "tall"],
+            &["--fullscreen=yes"],
+            &["--speed", "2"],
+        ] {
+            assert!(parse(args).is_err(), "{args:?} should be rejected");
+        }
+    }
+}
@@ -0,0 +1,28 @@
+//! Steps a known oscillator through the public API, without a window.
+
+use life::{next_generation, LivingList, Rules, WorldBounds};
+use vec2::Vector2;
+
+fn cells(coords: &[(i32, i32)]) -> LivingList {
+    coords.iter().map(|&(x, y)| Vector2::new(x, y)).collect()
+}
+
+fn step(cells: &LivingList) -> LivingList {
+    next_generation(cells, &Rules::CONWAY, &WorldBounds::Infinite)
+}
+
+#[test]
+fn blinker_has_period_two() {
+    let horizontal = cells(&[(-1, 0), (0, 0), (1, 0)]);
+    let vertical = cells(&[(0, -1), (0, 0), (0, 1)]);
+
+    let once = step(&horizontal);
+    assert_eq!(once, vertical);
+    assert_eq!(step(&once), horizontal);
+
+    let mut board = horizontal.clone();
+    for _ in 0..1000 {
+        board = step(&step(&board));
+    }
+    assert_eq!(board, horizontal);
+}
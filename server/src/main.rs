@@ -1,5 +1,21 @@
-use axum::Router;
-use std::net::SocketAddr;
+use axum::{
+    extract::{Request, State},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use axum_server::tls_rustls::RustlsConfig;
+use serde::Serialize;
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
 use tower_http::{
     services::{ServeDir, ServeFile},
     trace::TraceLayer,
@@ -17,6 +33,28 @@ struct Args {
     port: u16,
     #[arg(long)]
     public: bool,
+    /// A PEM certificate chain to serve HTTPS with. Requires `--tls-key`
+    #[arg(long, requires = "tls_key")]
+    tls_cert: Option<PathBuf>,
+    /// The PEM private key for `--tls-cert`
+    #[arg(long, requires = "tls_cert")]
+    tls_key: Option<PathBuf>,
+    /// Serve Prometheus metrics at `/metrics`
+    #[arg(long)]
+    metrics: bool,
+}
+
+/// Counters shared by the monitoring endpoints.
+struct Stats {
+    started: Instant,
+    requests: AtomicU64,
+}
+
+/// The body of `/healthz`.
+#[derive(Serialize)]
+struct Health {
+    status: &'static str,
+    uptime_secs: u64,
 }
 
 #[tokio::main]
@@ -30,7 +68,33 @@ async fn main() {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    serve(serve_assets(), args.port, args.public).await;
+    let tls = match (args.tls_cert, args.tls_key) {
+        (Some(cert), Some(key)) => match load_tls(&cert, &key).await {
+            Ok(config) => Some(config),
+            Err(e) => {
+                eprintln!(
+                    "Failed to load TLS certificate {} and key {}: {e}",
+                    cert.display(),
+                    key.display()
+                );
+                std::process::exit(1);
+            }
+        },
+        _ => None,
+    };
+    let stats = Arc::new(Stats {
+        started: Instant::now(),
+        requests: AtomicU64::new(0),
+    });
+    let app = serve_monitoring(Arc::clone(&stats), args.metrics)
+        .merge(serve_assets())
+        .layer(middleware::from_fn_with_state(stats, count_request));
+    serve(app, args.port, args.public, tls).await;
+}
+
+/// Load the certificate and key to serve HTTPS with.
+async fn load_tls(cert: &Path, key: &Path) -> std::io::Result<RustlsConfig> {
+    RustlsConfig::from_pem_file(cert, key).await
 }
 
 fn serve_assets() -> Router {
@@ -44,12 +108,150 @@ fn serve_assets() -> Router {
         .fallback_service(serve_dir)
 }
 
-async fn serve(app: Router, port: u16, public: bool) {
+/// Routes for checking on the server.
+fn serve_monitoring(stats: Arc<Stats>, metrics: bool) -> Router {
+    let mut router = Router::new().route("/healthz", get(health));
+    if metrics {
+        router = router.route("/metrics", get(prometheus_metrics));
+    }
+    router.with_state(stats)
+}
+
+async fn count_request(State(stats): State<Arc<Stats>>, request: Request, next: Next) -> Response {
+    stats.requests.fetch_add(1, Ordering::Relaxed);
+    next.run(request).await
+}
+
+async fn health(State(stats): State<Arc<Stats>>) -> Json<Health> {
+    Json(Health {
+        status: "ok",
+        uptime_secs: stats.started.elapsed().as_secs(),
+    })
+}
+
+async fn prometheus_metrics(State(stats): State<Arc<Stats>>) -> impl IntoResponse {
+    let body = format!(
+        "# HELP server_uptime_seconds Time since the server started.\n\
+         # TYPE server_uptime_seconds gauge\n\
+         server_uptime_seconds {}\n\
+         # HELP server_requests_total Requests received, including for assets.\n\
+         # TYPE server_requests_total counter\n\
+         server_requests_total {}\n",
+        stats.started.elapsed().as_secs_f64(),
+        stats.requests.load(Ordering::Relaxed),
+    );
+    ([("content-type", "text/plain; version=0.0.4")], body)
+}
+
+async fn serve(app: Router, port: u16, public: bool, tls: Option<RustlsConfig>) {
     let ip = if public { [0, 0, 0, 0] } else { [127, 0, 0, 1] };
     let addr = SocketAddr::from((ip, port));
+    let app = app.layer(TraceLayer::new_for_http());
+    if let Some(config) = tls {
+        tracing::debug!("listening with TLS on {addr}");
+        axum_server::bind_rustls(addr, config)
+            .serve(app.into_make_service())
+            .await
+            .unwrap();
+        return;
+    }
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
     tracing::debug!("listening on {}", listener.local_addr().unwrap());
-    axum::serve(listener, app.layer(TraceLayer::new_for_http()))
+    axum::serve(listener, app)
         .await
         .unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio_rustls::{rustls, TlsConnector};
+
+    use super::*;
+
+    /// A self-signed certificate for `localhost`, valid until 2124.
+    const CERT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/cert.pem");
+    const KEY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/key.pem");
+
+    fn stats() -> Arc<Stats> {
+        Arc::new(Stats {
+            started: Instant::now(),
+            requests: AtomicU64::new(0),
+        })
+    }
+
+    #[tokio::test]
+    async fn serves_https_with_certificate() {
+        let config = load_tls(Path::new(CERT), Path::new(KEY)).await.unwrap();
+        let handle = axum_server::Handle::new();
+        let server = axum_server::bind_rustls(SocketAddr::from(([127, 0, 0, 1], 0)), config)
+            .handle(handle.clone())
+            .serve(serve_monitoring(stats(), false).into_make_service());
+        tokio::spawn(server);
+        let addr = handle.listening().await.unwrap();
+
+        let mut roots = rustls::RootCertStore::empty();
+        let pem = std::fs::read(CERT).unwrap();
+        for cert in rustls_pemfile::certs(&mut &pem[..]) {
+            roots.add(&rustls::Certificate(cert.unwrap().to_vec())).unwrap();
+        }
+        let client = rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let tcp = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let mut tls = TlsConnector::from(Arc::new(client))
+            .connect("localhost".try_into().unwrap(), tcp)
+            .await
+            .unwrap();
+        tls.write_all(b"GET /healthz HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        tls.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.contains(r#""status":"ok""#), "{response}");
+        handle.shutdown();
+    }
+
+    #[tokio::test]
+    async fn missing_certificate_fails_to_load() {
+        let missing = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/missing.pem");
+        assert!(load_tls(&missing, Path::new(KEY)).await.is_err());
+        // The certificate isn't a key
+        assert!(load_tls(Path::new(CERT), Path::new(CERT)).await.is_err());
+    }
+
+    async fn get_body(app: Router, uri: &str) -> (axum::http::StatusCode, String) {
+        use tower::ServiceExt;
+
+        let request = Request::builder().uri(uri).body(axum::body::Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn health_reports_status_and_uptime() {
+        let (status, body) = get_body(serve_monitoring(stats(), false), "/healthz").await;
+        assert_eq!(status, 200);
+        assert_eq!(body, r#"{"status":"ok","uptime_secs":0}"#);
+    }
+
+    #[tokio::test]
+    async fn metrics_only_with_flag() {
+        let (status, _) = get_body(serve_monitoring(stats(), false), "/metrics").await;
+        assert_eq!(status, 404);
+
+        let stats = stats();
+        let app = serve_monitoring(Arc::clone(&stats), true)
+            .layer(middleware::from_fn_with_state(Arc::clone(&stats), count_request));
+        get_body(app.clone(), "/healthz").await;
+        let (status, body) = get_body(app, "/metrics").await;
+        assert_eq!(status, 200);
+        assert!(body.contains("server_uptime_seconds "), "{body}");
+        // The request for the metrics is counted too
+        assert!(body.contains("server_requests_total 2\n"), "{body}");
+    }
+}
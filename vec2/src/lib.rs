@@ -1,4 +1,5 @@
 use std::fmt;
+use std::iter::Sum;
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
 use serde::{Deserialize, Serialize};
@@ -83,6 +84,13 @@ impl Vector2<f64> {
         }
     }
 
+    /// Returns the unit vector pointing at an angle in degrees, measured
+    /// counterclockwise from `RIGHT`.
+    pub fn from_angle(degrees: f64) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Self::new(cos, sin)
+    }
+
     /// Returns a copy of vector with its magnitude clamped to max_length.
     pub fn clamp_magnitude(vector: Self, max_length: f64) -> Self {
         let sqr_magnitude = vector.sqr_magnitude();
@@ -170,6 +178,12 @@ impl Vector2<f64> {
         Self::new(a.x * b.x, a.y * b.y)
     }
 
+    /// Returns v rotated counterclockwise by an angle in degrees.
+    pub fn rotate(v: Self, degrees: f64) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Self::new(v.x * cos - v.y * sin, v.x * sin + v.y * cos)
+    }
+
     /// Gets the signed angle in degrees between from and to.
     pub fn signed_angle(from: Self, to: Self) -> f64 {
         let unsigned_angle = Self::angle(from, to);
@@ -191,6 +205,72 @@ impl<T> Vector2<T> {
         self.x = new_x;
         self.y = new_y;
     }
+
+    /// Returns a vector made by applying f to each component, such as to
+    /// cast them.
+    ///
+    /// ```
+    /// use vec2::Vector2;
+    ///
+    /// let cell = Vector2::new(3, -4);
+    /// assert_eq!(cell.map(|c| c as f32), Vector2::new(3.0, -4.0));
+    /// ```
+    pub fn map<U, F: Fn(T) -> U>(self, f: F) -> Vector2<U> {
+        Vector2::new(f(self.x), f(self.y))
+    }
+
+    /// Returns a vector made by applying f to the matching components of
+    /// this vector and other.
+    ///
+    /// ```
+    /// use vec2::Vector2;
+    ///
+    /// let cell = Vector2::new(3, -4);
+    /// let scale = Vector2::new(0.5, 2.0);
+    /// let scaled = cell.zip_with(scale, |c, s| c as f64 * s);
+    /// assert_eq!(scaled, Vector2::new(1.5, -8.0));
+    /// ```
+    pub fn zip_with<U, V, F: Fn(T, U) -> V>(self, other: Vector2<U>, f: F) -> Vector2<V> {
+        Vector2::new(f(self.x, other.x), f(self.y, other.y))
+    }
+}
+
+impl<T: Sub<Output = T> + Add<Output = T> + PartialOrd + Copy> Vector2<T> {
+    /// Returns a vector made from the absolute differences between the
+    /// components of a and b.
+    pub fn abs_diff(a: Self, b: Self) -> Self {
+        let diff = |a: T, b: T| if a > b { a - b } else { b - a };
+        Self::new(diff(a.x, b.x), diff(a.y, b.y))
+    }
+
+    /// Returns the taxicab distance between a and b, the sum of how far apart
+    /// they are along each axis.
+    ///
+    /// ```
+    /// use vec2::Vector2;
+    ///
+    /// let a = Vector2::new(1, -2);
+    /// let b = Vector2::new(-3, 4);
+    /// assert_eq!(Vector2::manhattan_distance(a, b), 10);
+    /// assert_eq!(Vector2::manhattan_distance(b, a), 10);
+    /// assert_eq!(Vector2::manhattan_distance(a, a), 0);
+    /// ```
+    pub fn manhattan_distance(a: Self, b: Self) -> T {
+        let diff = Self::abs_diff(a, b);
+        diff.x + diff.y
+    }
+
+    /// Returns the chessboard distance between a and b, the most they are
+    /// apart along either axis. Cells whose chebyshev distance is 1 are
+    /// neighbors.
+    pub fn chebyshev_distance(a: Self, b: Self) -> T {
+        let diff = Self::abs_diff(a, b);
+        if diff.x > diff.y {
+            diff.x
+        } else {
+            diff.y
+        }
+    }
 }
 
 impl<T: Copy> From<[T; 2]> for Vector2<T> {
@@ -317,6 +397,12 @@ impl<T: Add<Output = T> + Copy> AddAssign for Vector2<T> {
     }
 }
 
+impl<T: Add<Output = T> + Default + Copy> Sum for Vector2<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |acc, v| acc + v)
+    }
+}
+
 impl<T: fmt::Display> fmt::Display for Vector2<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "({}, {})", self.x, self.y)
@@ -403,6 +489,13 @@ impl Vector2<f32> {
         }
     }
 
+    /// Returns the unit vector pointing at an angle in degrees, measured
+    /// counterclockwise from `RIGHT`.
+    pub fn from_angle(degrees: f32) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Self::new(cos, sin)
+    }
+
     /// Returns a copy of vector with its magnitude clamped to max_length.
     pub fn clamp_magnitude(vector: Self, max_length: f32) -> Self {
         let sqr_magnitude = vector.sqr_magnitude();
@@ -490,6 +583,12 @@ impl Vector2<f32> {
         Self::new(a.x * b.x, a.y * b.y)
     }
 
+    /// Returns v rotated counterclockwise by an angle in degrees.
+    pub fn rotate(v: Self, degrees: f32) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Self::new(v.x * cos - v.y * sin, v.x * sin + v.y * cos)
+    }
+
     /// Gets the signed angle in degrees between from and to.
     pub fn signed_angle(from: Self, to: Self) -> f32 {
         let unsigned_angle = Self::angle(from, to);
@@ -500,3 +599,42 @@ impl Vector2<f32> {
     const K_EPSILON: f32 = 0.00001;
     const K_EPSILON_NORMAL_SQRT: f32 = 1e-15;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Vector2;
+
+    type V64 = Vector2<f64>;
+    type V32 = Vector2<f32>;
+
+    #[test]
+    fn rotate_right_angle() {
+        let rotated = V64::rotate(V64::RIGHT, 90.0);
+        assert!(V64::distance(rotated, V64::UP) < V64::K_EPSILON);
+
+        let rotated = V32::rotate(V32::RIGHT, 90.0);
+        assert!(V32::distance(rotated, V32::UP) < V32::K_EPSILON);
+    }
+
+    #[test]
+    fn rotate_leaves_input() {
+        let v = V64::new(3.0, -2.0);
+        let rotated = V64::rotate(v, 45.0);
+        assert_eq!(v, V64::new(3.0, -2.0));
+        assert!((rotated.magnitude() - v.magnitude()).abs() < V64::K_EPSILON);
+    }
+
+    #[test]
+    fn from_angle_is_unit_and_counterclockwise() {
+        assert!(V64::distance(V64::from_angle(0.0), V64::RIGHT) < V64::K_EPSILON);
+        assert!(V64::distance(V64::from_angle(90.0), V64::UP) < V64::K_EPSILON);
+        assert!(V32::distance(V32::from_angle(90.0), V32::UP) < V32::K_EPSILON);
+
+        for degrees in (-720..=720).step_by(15) {
+            let v = V64::from_angle(degrees as f64);
+            assert!((v.magnitude() - 1.0).abs() < V64::K_EPSILON);
+            let v = V32::from_angle(degrees as f32);
+            assert!((v.magnitude() - 1.0).abs() < V32::K_EPSILON);
+        }
+    }
+}